@@ -58,12 +58,12 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     let max_possible_x = (final_view_transform.window_ext_x as f64 * scale_x).max(svg_width_logical);
     let max_possible_y = (final_view_transform.window_ext_y as f64 * scale_y).max(svg_height_logical);
     
-    eprintln!("EMF Header - rclFrame (physical size): {:.2}mm x {:.2}mm ({:.2}px x {:.2}px)", 
+    log::debug!("EMF Header - rclFrame (physical size): {:.2}mm x {:.2}mm ({:.2}px x {:.2}px)",
               frame_width_mm, frame_height_mm, frame_width_px, frame_height_px);
-    eprintln!("EMF Header - rclBounds (logical units): {:.2} x {:.2}", _bounds_width, _bounds_height);
-    eprintln!("Final viewport extents: ({}, {})", final_view_transform.viewport_ext_x, final_view_transform.viewport_ext_y);
-    eprintln!("Final window extents: ({}, {})", final_view_transform.window_ext_x, final_view_transform.window_ext_y);
-    eprintln!("Scale: ({}, {})", scale_x, scale_y);
+    log::debug!("EMF Header - rclBounds (logical units): {:.2} x {:.2}", _bounds_width, _bounds_height);
+    log::debug!("Final viewport extents: ({}, {})", final_view_transform.viewport_ext_x, final_view_transform.viewport_ext_y);
+    log::debug!("Final window extents: ({}, {})", final_view_transform.window_ext_x, final_view_transform.window_ext_y);
+    log::debug!("Scale: ({}, {})", scale_x, scale_y);
     
     // Use frame size (physical size) as the SVG dimensions if valid
     // This ensures the SVG has the correct default size as intended by the EMF file
@@ -80,7 +80,7 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
         max_possible_y.max(2000.0)
     };
     
-    eprintln!("SVG dimensions (using rclFrame): {:.2}x{:.2}", svg_width, svg_height);
+    log::debug!("SVG dimensions (using rclFrame): {:.2}x{:.2}", svg_width, svg_height);
     
     // Normalize dimensions
     let width = normalize_dimension(svg_width);
@@ -98,7 +98,7 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     match parse_emf_records(data, header_size, &mut svg, initial_view_transform) {
         Ok(_) => rendered_gdi = true,
         Err(e) => {
-            eprintln!("EMF GDI parsing failed: {}", e);
+            log::warn!("EMF GDI parsing failed: {}", e);
         }
     }
 
@@ -109,7 +109,7 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     emfplus_parser.parse();
 
     if !rendered_gdi && !emfplus_parser.has_detected_records() {
-        eprintln!("Warning: EMF rendering failed and no EMF+ records detected; output may be empty.");
+        log::warn!("EMF rendering failed and no EMF+ records detected; output may be empty.");
     }
 
     Ok(svg.finish())
@@ -304,15 +304,12 @@ impl ViewTransform {
         let device_y = (y - self.window_org_y as f64) * scale_y + self.viewport_org_y as f64;
         
         // Debug first few transforms
-        static mut DEBUG_COUNT: u32 = 0;
-        unsafe {
-            if DEBUG_COUNT < 3 {
-                eprintln!("Transform: logical=({}, {}) -> device=({}, {}), scale=({}, {}), window_ext=({}, {}), viewport_ext=({}, {})", 
-                    x, y, device_x, device_y, scale_x, scale_y, 
-                    self.window_ext_x, self.window_ext_y, 
-                    self.viewport_ext_x, self.viewport_ext_y);
-                DEBUG_COUNT += 1;
-            }
+        static DEBUG_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        if DEBUG_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed) < 3 {
+            log::trace!("Transform: logical=({}, {}) -> device=({}, {}), scale=({}, {}), window_ext=({}, {}), viewport_ext=({}, {})",
+                x, y, device_x, device_y, scale_x, scale_y,
+                self.window_ext_x, self.window_ext_y,
+                self.viewport_ext_x, self.viewport_ext_y);
         }
         
         (device_x, device_y)
@@ -419,10 +416,10 @@ fn parse_emf_records(data: &[u8], header_size: u32, svg: &mut SvgWriter, initial
         
         record_count += 1;
         
-        // Debug: log record types (first 20 records)
+        // Log record types (first 20 records)
         if record_count <= 20 {
-            eprintln!("Record {}: type={} ({}) size={}", 
-                     record_count, 
+            log::debug!("Record {}: type={} ({}) size={}",
+                     record_count,
                      record_type,
                      emf_records::get_record_type_name(record_type),
                      record_size);
@@ -434,28 +431,28 @@ fn parse_emf_records(data: &[u8], header_size: u32, svg: &mut SvgWriter, initial
                 if data_size >= 8 {
                     state.view_transform.window_org_x = cursor.read_i32::<LittleEndian>()?;
                     state.view_transform.window_org_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETWINDOWORGEX: ({}, {})", state.view_transform.window_org_x, state.view_transform.window_org_y);
+                    log::trace!("SETWINDOWORGEX: ({}, {})", state.view_transform.window_org_x, state.view_transform.window_org_y);
                 }
             }
             emf_records::EMR_SETWINDOWEXTEX => {
                 if data_size >= 8 {
                     state.view_transform.window_ext_x = cursor.read_i32::<LittleEndian>()?;
                     state.view_transform.window_ext_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETWINDOWEXTEX: ({}, {})", state.view_transform.window_ext_x, state.view_transform.window_ext_y);
+                    log::trace!("SETWINDOWEXTEX: ({}, {})", state.view_transform.window_ext_x, state.view_transform.window_ext_y);
                 }
             }
             emf_records::EMR_SETVIEWPORTORGEX => {
                 if data_size >= 8 {
                     state.view_transform.viewport_org_x = cursor.read_i32::<LittleEndian>()?;
                     state.view_transform.viewport_org_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETVIEWPORTORGEX: ({}, {})", state.view_transform.viewport_org_x, state.view_transform.viewport_org_y);
+                    log::trace!("SETVIEWPORTORGEX: ({}, {})", state.view_transform.viewport_org_x, state.view_transform.viewport_org_y);
                 }
             }
             emf_records::EMR_SETVIEWPORTEXTEX => {
                 if data_size >= 8 {
                     state.view_transform.viewport_ext_x = cursor.read_i32::<LittleEndian>()?;
                     state.view_transform.viewport_ext_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETVIEWPORTEXTEX: ({}, {})", state.view_transform.viewport_ext_x, state.view_transform.viewport_ext_y);
+                    log::trace!("SETVIEWPORTEXTEX: ({}, {})", state.view_transform.viewport_ext_x, state.view_transform.viewport_ext_y);
                 }
             }
             emf_records::EMR_SETTEXTCOLOR => {
@@ -577,7 +574,7 @@ fn parse_emf_records(data: &[u8], header_size: u32, svg: &mut SvgWriter, initial
     }
     
     if record_count > 0 {
-        eprintln!("Parsed {} EMF records", record_count);
+        log::debug!("Parsed {} EMF records", record_count);
     }
     
     Ok(state.view_transform)
@@ -2034,13 +2031,13 @@ fn argb_to_svg_color(argb: u32) -> String {
 
 /// Normalize dimension value
 fn normalize_dimension(value: f64) -> u32 {
-    eprintln!("normalize_dimension called with: {}", value);
+    log::trace!("normalize_dimension called with: {}", value);
     if value.is_finite() && value > 0.0 && value < 20000.0 {
         let result = value.ceil() as u32;
-        eprintln!("normalize_dimension returning: {}", result);
+        log::trace!("normalize_dimension returning: {}", result);
         result
     } else {
-        eprintln!("normalize_dimension returning default: 800");
+        log::trace!("normalize_dimension returning default: 800");
         800 // Default fallback
     }
 }
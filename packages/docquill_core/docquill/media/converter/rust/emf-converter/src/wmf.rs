@@ -40,7 +40,7 @@ pub fn convert_wmf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     let width = normalize_dimension(width_px);
     let height = normalize_dimension(height_px);
 
-    eprintln!("WMF - Final SVG dimensions: {}x{} pixels", width, height);
+    log::debug!("WMF - Final SVG dimensions: {}x{} pixels", width, height);
 
     // For now, create a placeholder SVG
     // TODO: Implement full WMF parsing
@@ -92,15 +92,15 @@ fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>>
                 let width_px = width_logical * logical_to_px;
                 let height_px = height_logical * logical_to_px;
                 
-                eprintln!("WMF Placeable Header - BoundingBox: {}x{} logical units, {} units/inch", 
+                log::debug!("WMF Placeable Header - BoundingBox: {}x{} logical units, {} units/inch", 
                           width_logical, height_logical, units_per_inch);
-                eprintln!("WMF Placeable Header - Size: {:.2}px x {:.2}px (96 DPI)", width_px, height_px);
+                log::debug!("WMF Placeable Header - Size: {:.2}px x {:.2}px (96 DPI)", width_px, height_px);
                 
                 return Ok((width_px.max(1.0), height_px.max(1.0)));
             } else if width_logical > 0.0 && height_logical > 0.0 {
                 // If units_per_inch is 0 or invalid, use logical units directly
                 // This is a fallback, but may not be accurate
-                eprintln!("WMF Placeable Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
+                log::debug!("WMF Placeable Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
                           width_logical, height_logical);
                 return Ok((width_logical.max(1.0), height_logical.max(1.0)));
             }
@@ -119,7 +119,7 @@ fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>>
                 let width = (right - left).abs() as f64;
                 let height = (bottom - top).abs() as f64;
                 if width > 0.0 && height > 0.0 {
-                    eprintln!("WMF Standard Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
+                    log::debug!("WMF Standard Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
                               width, height);
                     return Ok((width, height));
                 }
@@ -128,7 +128,7 @@ fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>>
     }
     
     // Fallback: default size
-    eprintln!("WMF - No valid size found in header, using default: 800x600");
+    log::debug!("WMF - No valid size found in header, using default: 800x600");
     Ok((800.0, 600.0))
 }
 
@@ -42,6 +42,16 @@ pub struct PdfCanvas {
     // Cache for CID bytes: (code_point) -> [u8; 2]
     cid_cache: HashMap<u32, [u8; 2]>,
     cached_font: Option<Name<'static>>,
+    // Number of PDF content-stream operators written so far, for diagnostics
+    // (`PdfCanvasRenderer::current_page_stats`). Incremented once per `op()` call.
+    operator_count: usize,
+    // When set, `save_state`/`restore_state`, text objects, and `cm` calls are
+    // tracked for balance so `validation_errors` can name a mismatch before it
+    // reaches Acrobat as a malformed content stream.
+    debug_validate: bool,
+    text_depth: i32,
+    unmatched_restore_count: u32,
+    unscoped_transform_count: u32,
 }
 
 impl PdfCanvas {
@@ -52,16 +62,99 @@ impl PdfCanvas {
             state_stack: Vec::new(),
             cid_cache: HashMap::new(),
             cached_font: None,
+            operator_count: 0,
+            debug_validate: false,
+            text_depth: 0,
+            unmatched_restore_count: 0,
+            unscoped_transform_count: 0,
         }
     }
 
+    /// Enable content-stream balance tracking for `save_state`/`restore_state`,
+    /// text objects, and unscoped `cm` transforms (see `validation_errors`).
+    pub fn set_debug_validate(&mut self, enabled: bool) {
+        self.debug_validate = enabled;
+    }
+
+    /// Describe every save/restore, text-object, or transform imbalance found
+    /// so far. Empty when the content stream is well-formed. Only populated
+    /// when `debug_validate` is enabled.
+    pub fn validation_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.state_stack.is_empty() {
+            errors.push(format!(
+                "{} unmatched 'q' (save_state) with no closing 'Q'",
+                self.state_stack.len()
+            ));
+        }
+        if self.unmatched_restore_count > 0 {
+            errors.push(format!(
+                "{} 'Q' (restore_state) with no matching 'q'",
+                self.unmatched_restore_count
+            ));
+        }
+        if self.text_depth > 0 {
+            errors.push(format!(
+                "{} unmatched 'BT' (begin_text) with no closing 'ET'",
+                self.text_depth
+            ));
+        } else if self.text_depth < 0 {
+            errors.push(format!(
+                "{} 'ET' (end_text) with no matching 'BT'",
+                -self.text_depth
+            ));
+        }
+        if self.unscoped_transform_count > 0 {
+            errors.push(format!(
+                "{} 'cm' transform(s) applied outside any 'q'/'Q' scope and never undone",
+                self.unscoped_transform_count
+            ));
+        }
+        errors
+    }
+
     /// Get mutable reference to content
     pub fn content_mut(&mut self) -> &mut Content {
         &mut self.content
     }
 
-    /// Get content (for finalizing)
-    pub fn finish(self) -> Vec<u8> {
+    /// Get mutable reference to content, counting this as one operator written.
+    /// Every drawing/state-change method below goes through this instead of
+    /// `self.content` directly so `operator_count` stays accurate.
+    #[inline]
+    fn op(&mut self) -> &mut Content {
+        self.operator_count += 1;
+        &mut self.content
+    }
+
+    /// Total number of content-stream operators written so far.
+    pub fn operator_count(&self) -> usize {
+        self.operator_count
+    }
+
+    /// Get content (for finalizing). Any `save_state` left un-restored (e.g.
+    /// because Python code raised mid-render) or text object left open is
+    /// closed here so the stream never reaches the writer unbalanced; a
+    /// warning is logged with the count so the leak is still visible.
+    pub fn finish(mut self) -> Vec<u8> {
+        if self.text_depth > 0 {
+            log::warn!(
+                "PdfCanvas::finish: auto-closing {} unmatched 'BT' (begin_text) with no 'ET'",
+                self.text_depth
+            );
+            for _ in 0..self.text_depth {
+                self.op().end_text();
+            }
+        }
+        if !self.state_stack.is_empty() {
+            log::warn!(
+                "PdfCanvas::finish: auto-closing {} unmatched 'q' (save_state) with no 'Q'",
+                self.state_stack.len()
+            );
+            while self.state_stack.pop().is_some() {
+                self.op().restore_state();
+            }
+        }
         self.content.finish()
     }
 
@@ -70,46 +163,101 @@ impl PdfCanvas {
         self.state.font_name
     }
 
+    /// Get current font size
+    pub fn get_font_size(&self) -> f64 {
+        self.state.font_size
+    }
+
     // ===== State Management =====
 
     pub fn save_state(&mut self) {
         self.state_stack.push(self.state.clone());
-        self.content.save_state();
+        self.op().save_state();
     }
 
     pub fn restore_state(&mut self) {
         if let Some(state) = self.state_stack.pop() {
             self.state = state;
-            self.content.restore_state();
+            self.op().restore_state();
+        } else if self.debug_validate {
+            self.unmatched_restore_count += 1;
         }
     }
 
+    /// Wraps `Content::begin_text`, tracking nesting depth so `finish` can
+    /// auto-close a dangling text object and `validation_errors` can report
+    /// an unmatched `BT`/`ET` when `debug_validate` is enabled.
+    #[inline]
+    fn begin_text(&mut self) {
+        self.text_depth += 1;
+        self.op().begin_text();
+    }
+
+    /// Wraps `Content::end_text`, tracking nesting depth so `finish` can
+    /// auto-close a dangling text object and `validation_errors` can report
+    /// an unmatched `BT`/`ET` when `debug_validate` is enabled.
+    #[inline]
+    fn end_text(&mut self) {
+        self.text_depth -= 1;
+        self.op().end_text();
+    }
+
     // ===== Colors =====
 
     #[inline]
     pub fn set_fill_color(&mut self, color: Color) {
         self.state.fill_color = color;
         let (r, g, b) = (color.r as f32, color.g as f32, color.b as f32);
-        self.content.set_fill_rgb(r, g, b);
+        self.op().set_fill_rgb(r, g, b);
+    }
+
+    /// Get current fill color
+    #[inline]
+    pub fn get_fill_color(&self) -> Color {
+        self.state.fill_color
     }
 
     #[inline]
     pub fn set_stroke_color(&mut self, color: Color) {
         self.state.stroke_color = color;
         let (r, g, b) = (color.r as f32, color.g as f32, color.b as f32);
-        self.content.set_stroke_rgb(r, g, b);
+        self.op().set_stroke_rgb(r, g, b);
+    }
+
+    /// Set fill color in CMYK (each component 0.0-1.0), for print-oriented
+    /// work device RGB can't represent exactly (e.g. a spot-matched brand
+    /// color). `fill_color` is kept as an RGB approximation of the CMYK
+    /// value so text and decoration lines drawn afterwards still pick up a
+    /// reasonable color.
+    #[inline]
+    pub fn set_fill_color_cmyk(&mut self, c: f64, m: f64, y: f64, k: f64) {
+        self.state.fill_color = Color::rgb(
+            (1.0 - c) * (1.0 - k),
+            (1.0 - m) * (1.0 - k),
+            (1.0 - y) * (1.0 - k),
+        );
+        self.op().set_fill_cmyk(c as f32, m as f32, y as f32, k as f32);
+    }
+
+    /// Set the fill color to a previously registered gradient pattern (see
+    /// `PdfCanvasRenderer::canvas_set_linear_gradient`). Subsequent fills use
+    /// the gradient, like any other fill color, until changed again.
+    pub fn set_fill_pattern(&mut self, pattern_name: Name<'static>) {
+        self.op()
+            .set_fill_color_space(pdf_writer::types::ColorSpaceOperand::Pattern);
+        self.op().set_fill_pattern(std::iter::empty(), pattern_name);
     }
 
     #[inline]
     pub fn set_line_width(&mut self, width: f64) {
         self.state.line_width = width;
-        self.content.set_line_width(width as f32);
+        self.op().set_line_width(width as f32);
     }
 
     pub fn set_dash(&mut self, pattern: Vec<f64>, offset: f64) {
         self.state.dash_pattern = Some((pattern.clone(), offset));
         let pattern_f32: Vec<f32> = pattern.iter().map(|&x| x as f32).collect();
-        self.content
+        self.op()
             .set_dash_pattern(pattern_f32.iter().copied(), offset as f32);
     }
 
@@ -125,24 +273,24 @@ impl PdfCanvas {
     }
 
     pub fn set_ext_graphics_state(&mut self, name: Name<'static>) {
-        self.content.set_parameters(name);
+        self.op().set_parameters(name);
     }
 
     // ===== Drawing =====
 
     #[inline]
     pub fn rect(&mut self, rect: Rect, fill: bool, stroke: bool) {
-        self.content.rect(
+        self.op().rect(
             rect.x as f32,
             rect.y as f32,
             rect.width as f32,
             rect.height as f32,
         );
         if fill {
-            self.content.fill_nonzero();
+            self.op().fill_nonzero();
         }
         if stroke {
-            self.content.stroke();
+            self.op().stroke();
         }
     }
 
@@ -166,13 +314,13 @@ impl PdfCanvas {
         let c = r * 0.55228475;
 
         // Start from top-left corner (after rounded corner)
-        self.content.move_to((x + r) as f32, (y + h) as f32);
+        self.op().move_to((x + r) as f32, (y + h) as f32);
 
         // Top edge
-        self.content.line_to((x + w - r) as f32, (y + h) as f32);
+        self.op().line_to((x + w - r) as f32, (y + h) as f32);
 
         // Top-right rounded corner (bezier curve)
-        self.content.cubic_to(
+        self.op().cubic_to(
             (x + w - r + c) as f32,
             (y + h) as f32,
             (x + w) as f32,
@@ -182,10 +330,10 @@ impl PdfCanvas {
         );
 
         // Right edge
-        self.content.line_to((x + w) as f32, (y + r) as f32);
+        self.op().line_to((x + w) as f32, (y + r) as f32);
 
         // Bottom-right rounded corner
-        self.content.cubic_to(
+        self.op().cubic_to(
             (x + w) as f32,
             (y + r - c) as f32,
             (x + w - r + c) as f32,
@@ -195,10 +343,10 @@ impl PdfCanvas {
         );
 
         // Bottom edge
-        self.content.line_to((x + r) as f32, y as f32);
+        self.op().line_to((x + r) as f32, y as f32);
 
         // Bottom-left rounded corner
-        self.content.cubic_to(
+        self.op().cubic_to(
             (x + r - c) as f32,
             y as f32,
             x as f32,
@@ -208,10 +356,10 @@ impl PdfCanvas {
         );
 
         // Left edge
-        self.content.line_to(x as f32, (y + h - r) as f32);
+        self.op().line_to(x as f32, (y + h - r) as f32);
 
         // Top-left rounded corner
-        self.content.cubic_to(
+        self.op().cubic_to(
             x as f32,
             (y + h - r + c) as f32,
             (x + r - c) as f32,
@@ -221,21 +369,79 @@ impl PdfCanvas {
         );
 
         // Close path
-        self.content.close_path();
+        self.op().close_path();
 
         if fill {
-            self.content.fill_nonzero();
+            self.op().fill_nonzero();
         }
         if stroke {
-            self.content.stroke();
+            self.op().stroke();
         }
     }
 
     #[inline]
     pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
-        self.content.move_to(x1 as f32, y1 as f32);
-        self.content.line_to(x2 as f32, y2 as f32);
-        self.content.stroke();
+        self.op().move_to(x1 as f32, y1 as f32);
+        self.op().line_to(x2 as f32, y2 as f32);
+        self.op().stroke();
+    }
+
+    /// Intersect the clipping path with `rect` (`re W n`). Affects all
+    /// drawing until the next `restore_state` back to the `save_state` this
+    /// was scoped under -- callers almost always want `save_state`/
+    /// `restore_state` around a clip.
+    pub fn clip_rect(&mut self, rect: Rect) {
+        self.op().rect(
+            rect.x as f32,
+            rect.y as f32,
+            rect.width as f32,
+            rect.height as f32,
+        );
+        self.op().clip_nonzero();
+        self.op().end_path();
+    }
+
+    // ===== Paths =====
+
+    #[inline]
+    pub fn path_move_to(&mut self, x: f64, y: f64) {
+        self.op().move_to(x as f32, y as f32);
+    }
+
+    #[inline]
+    pub fn path_line_to(&mut self, x: f64, y: f64) {
+        self.op().line_to(x as f32, y as f32);
+    }
+
+    #[inline]
+    pub fn path_curve_to(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) {
+        self.op().cubic_to(
+            x1 as f32, y1 as f32, x2 as f32, y2 as f32, x3 as f32, y3 as f32,
+        );
+    }
+
+    #[inline]
+    pub fn path_close(&mut self) {
+        self.op().close_path();
+    }
+
+    /// Paint the path built since the last paint with `path_move_to`/
+    /// `path_line_to`/`path_curve_to`/`path_close`. Same fill/stroke flags as
+    /// `rect`; `even_odd` picks the fill rule when `fill` is set.
+    pub fn path_paint(&mut self, fill: bool, stroke: bool, even_odd: bool) {
+        if fill {
+            if even_odd {
+                self.op().fill_even_odd();
+            } else {
+                self.op().fill_nonzero();
+            }
+        }
+        if stroke {
+            self.op().stroke();
+        }
+        if !fill && !stroke {
+            self.op().end_path();
+        }
     }
 
     // ===== Text =====
@@ -253,12 +459,12 @@ impl PdfCanvas {
             self.state.fill_color.g as f32,
             self.state.fill_color.b as f32,
         );
-        self.content.set_fill_rgb(r, g, b);
+        self.op().set_fill_rgb(r, g, b);
 
-        self.content.begin_text();
-        self.content
-            .set_font(self.state.font_name, self.state.font_size as f32);
-        self.content.next_line(x as f32, y as f32);
+        self.begin_text();
+        let (font_name, font_size) = (self.state.font_name, self.state.font_size as f32);
+        self.op().set_font(font_name, font_size);
+        self.op().next_line(x as f32, y as f32);
 
         // Type0 font: convert Unicode code points to CIDs using the map
         // Use cache to avoid repeated lookups for the same characters
@@ -288,16 +494,90 @@ impl PdfCanvas {
         }
         // Use show_text if available (more efficient), otherwise fall back to show
         // Note: pdf-writer may not have show_text, so we use show
-        self.content.show(Str(&cid_bytes));
-        self.content.end_text();
+        self.op().show(Str(&cid_bytes));
+        self.end_text();
+    }
+
+    /// Draw text as a sequence of segments joined by `TJ` adjustments, one per gap
+    /// between consecutive segments (`adjustments.len() == segments.len() - 1`).
+    /// Used for justification: widening the gaps between words to hit an exact
+    /// target line width with a single text-showing operator.
+    pub fn draw_string_positioned(
+        &mut self,
+        x: f64,
+        y: f64,
+        segments: &[String],
+        adjustments: &[f32],
+        cid_map: &CidMap,
+    ) {
+        let (r, g, b) = (
+            self.state.fill_color.r as f32,
+            self.state.fill_color.g as f32,
+            self.state.fill_color.b as f32,
+        );
+        self.op().set_fill_rgb(r, g, b);
+
+        self.begin_text();
+        let (font_name, font_size) = (self.state.font_name, self.state.font_size as f32);
+        self.op().set_font(font_name, font_size);
+        self.op().next_line(x as f32, y as f32);
+
+        // Bypass `op()` here: `positioned`/`items` hold a borrow of `self.content`
+        // for the whole loop below, which needs `self.cid_cache` free alongside it.
+        self.operator_count += 1;
+        let mut positioned = self.content.show_positioned();
+        let mut items = positioned.items();
+        for (i, segment) in segments.iter().enumerate() {
+            let mut cid_bytes = Vec::with_capacity(segment.len() * 2);
+            for ch in segment.chars() {
+                let code_point = ch as u32;
+                let cid_byte_pair = *self.cid_cache.entry(code_point).or_insert_with(|| {
+                    if let Some(&cid) = cid_map.get(&code_point) {
+                        [(cid >> 8) as u8, (cid & 0xFF) as u8]
+                    } else {
+                        [0, 0]
+                    }
+                });
+                cid_bytes.extend_from_slice(&cid_byte_pair);
+            }
+            items.show(Str(&cid_bytes));
+            if let Some(&adjustment) = adjustments.get(i) {
+                items.adjust(adjustment);
+            }
+        }
+        drop(items);
+        drop(positioned);
+        self.end_text();
+    }
+
+    /// Draw text as a sequence of runs, switching the active font (`Tf`) between
+    /// runs within a single text object. Used for glyph fallback: the caller has
+    /// already split the string by which registered font covers each code point
+    /// and pre-encoded each run's CID bytes, so this just emits `Tf`/`Tj` pairs
+    /// back to back — the text position advances naturally between them.
+    pub fn draw_string_multi_font(&mut self, x: f64, y: f64, runs: &[(Name<'static>, Vec<u8>)]) {
+        let (r, g, b) = (
+            self.state.fill_color.r as f32,
+            self.state.fill_color.g as f32,
+            self.state.fill_color.b as f32,
+        );
+        self.op().set_fill_rgb(r, g, b);
+
+        self.begin_text();
+        self.op().next_line(x as f32, y as f32);
+        let font_size = self.state.font_size as f32;
+        for (font_name, cid_bytes) in runs {
+            self.op().set_font(*font_name, font_size);
+            self.op().show(Str(cid_bytes));
+        }
+        self.end_text();
     }
 
     // ===== Transformations =====
 
     #[inline]
     pub fn translate(&mut self, x: f64, y: f64) {
-        self.content
-            .transform([1.0, 0.0, 0.0, 1.0, x as f32, y as f32]);
+        self.transform([1.0, 0.0, 0.0, 1.0, x as f32, y as f32]);
     }
 
     #[inline]
@@ -305,18 +585,22 @@ impl PdfCanvas {
         let angle_rad = angle_degrees.to_radians();
         let cos_a = angle_rad.cos() as f32;
         let sin_a = angle_rad.sin() as f32;
-        self.content
-            .transform([cos_a, sin_a, -sin_a, cos_a, 0.0, 0.0]);
+        self.transform([cos_a, sin_a, -sin_a, cos_a, 0.0, 0.0]);
     }
 
     #[inline]
     pub fn scale(&mut self, sx: f64, sy: f64) {
-        self.content
-            .transform([sx as f32, 0.0, 0.0, sy as f32, 0.0, 0.0]);
+        self.transform([sx as f32, 0.0, 0.0, sy as f32, 0.0, 0.0]);
     }
 
     pub fn transform(&mut self, matrix: [f32; 6]) {
-        self.content.transform(matrix);
+        // A `cm` outside any `q`/`Q` scope changes the CTM for the rest of the
+        // page with nothing to undo it; that's almost always a leaked
+        // transform rather than intentional, so it counts as an imbalance.
+        if self.debug_validate && self.state_stack.is_empty() {
+            self.unscoped_transform_count += 1;
+        }
+        self.op().transform(matrix);
     }
 
     // ===== Images =====
@@ -329,7 +613,7 @@ impl PdfCanvas {
         width: f64,
         height: f64,
     ) {
-        self.content.save_state();
+        self.op().save_state();
         // PDF transformation matrix: [a b c d e f]
         // Where:
         //   a = horizontal scaling (width)
@@ -347,10 +631,34 @@ impl PdfCanvas {
         //
         // IMPORTANT: The transformation matrix positions the bottom-left corner of the image at (x, y).
         // The image is then scaled to width x height from that point.
-        self.content
+        self.op()
             .transform([width as f32, 0.0, 0.0, height as f32, x as f32, y as f32]);
-        self.content.x_object(image_name);
-        self.content.restore_state();
+        self.op().x_object(image_name);
+        self.op().restore_state();
+    }
+
+    /// Draw a Form XObject (e.g. an imported PDF page) uniformly scaled by
+    /// `scale`, positioning its BBox's lower-left corner at `(x, y)`.
+    pub fn draw_xobject(
+        &mut self,
+        xobject_name: Name<'static>,
+        x: f64,
+        y: f64,
+        scale: f64,
+        bbox: (f64, f64, f64, f64),
+    ) {
+        let (bbox_x0, bbox_y0, _, _) = bbox;
+        self.op().save_state();
+        self.op().transform([
+            scale as f32,
+            0.0,
+            0.0,
+            scale as f32,
+            (x - bbox_x0 * scale) as f32,
+            (y - bbox_y0 * scale) as f32,
+        ]);
+        self.op().x_object(xobject_name);
+        self.op().restore_state();
     }
 }
 
@@ -0,0 +1,83 @@
+//! Structured errors for the PDF renderer, so Python callers can `except` on a
+//! specific failure (no current page, an unknown font, a degenerate matrix)
+//! instead of pattern-matching a `RuntimeError`/`ValueError` message string.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::PyErr;
+
+create_exception!(docquill_pdf_rust, NoCurrentPageError, PyRuntimeError);
+create_exception!(docquill_pdf_rust, FontNotFoundError, PyValueError);
+create_exception!(docquill_pdf_rust, InvalidMatrixError, PyValueError);
+create_exception!(docquill_pdf_rust, ImageDecodeError, PyValueError);
+create_exception!(docquill_pdf_rust, UnbalancedContentStreamError, PyValueError);
+create_exception!(docquill_pdf_rust, PdfImportError, PyValueError);
+
+/// Failure raised by [`crate::PdfCanvasRenderer`] operations, converted to one
+/// of the custom exception classes registered on the `rust_pdf_canvas` module.
+#[derive(Debug)]
+pub enum RenderError {
+    /// A canvas operation was called before `new_page`/after `save`.
+    NoCurrentPage,
+    /// `resolve_font` couldn't find the named font and no default is registered.
+    FontNotFound(String),
+    /// A transform/matrix argument isn't a valid affine transform (wrong
+    /// element count, non-invertible, etc).
+    InvalidMatrix(String),
+    /// Image bytes passed to `canvas_draw_image` couldn't be decoded.
+    ImageDecode(String),
+    /// `debug_validate` caught unbalanced `q`/`Q`, `BT`/`ET`, or `cm` calls on
+    /// a page as it was finalized. Message names each imbalance found.
+    UnbalancedContentStream(String),
+    /// A source PDF passed to `import_pdf_page` couldn't be parsed, or named
+    /// a page index it doesn't have.
+    PdfImport(String),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::NoCurrentPage => write!(f, "no current page"),
+            RenderError::FontNotFound(name) => write!(f, "font not found: {}", name),
+            RenderError::InvalidMatrix(msg) => write!(f, "invalid matrix: {}", msg),
+            RenderError::ImageDecode(msg) => write!(f, "failed to decode image: {}", msg),
+            RenderError::UnbalancedContentStream(msg) => {
+                write!(f, "unbalanced content stream: {}", msg)
+            }
+            RenderError::PdfImport(msg) => write!(f, "failed to import PDF page: {}", msg),
+            RenderError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RenderError {
+    fn from(e: std::io::Error) -> Self {
+        RenderError::Io(e)
+    }
+}
+
+impl From<RenderError> for PyErr {
+    fn from(err: RenderError) -> PyErr {
+        match err {
+            RenderError::NoCurrentPage => NoCurrentPageError::new_err(err.to_string()),
+            RenderError::FontNotFound(_) => FontNotFoundError::new_err(err.to_string()),
+            RenderError::InvalidMatrix(_) => InvalidMatrixError::new_err(err.to_string()),
+            RenderError::ImageDecode(_) => ImageDecodeError::new_err(err.to_string()),
+            RenderError::UnbalancedContentStream(_) => {
+                UnbalancedContentStreamError::new_err(err.to_string())
+            }
+            RenderError::PdfImport(_) => PdfImportError::new_err(err.to_string()),
+            RenderError::Io(e) => PyIOError::new_err(e.to_string()),
+        }
+    }
+}
@@ -12,6 +12,27 @@ use ttf_parser::Face;
 /// Map Unicode code point to CID (Character ID) for Type0 fonts
 pub type CidMap = HashMap<u32, u16>;
 
+/// Metrics needed to draw underline/strikethrough decoration and measure string
+/// width without a round-trip into Python. Distances are in the same 1000-unit
+/// em space as the CIDFont `/W` widths array built below, so scaling to a given
+/// font size is just `value * font_size / 1000.0`.
+pub struct FontMetrics {
+    /// Baseline offset of the underline, from the `post` table (negative = below baseline)
+    pub underline_position: f32,
+    /// Underline stroke thickness, from the `post` table
+    pub underline_thickness: f32,
+    /// Baseline offset of the strikeout line, from the OS/2 table
+    pub strikeout_position: f32,
+    /// Strikeout stroke thickness, from the OS/2 table
+    pub strikeout_thickness: f32,
+    /// Font ascender above the baseline
+    pub ascender: f32,
+    /// Font descender below the baseline (negative)
+    pub descender: f32,
+    /// Per-codepoint advance width, keyed the same as the font's `CidMap`
+    pub widths: HashMap<u32, i32>,
+}
+
 /// Load TTF/OTF font from file path
 pub fn load_font_file(path: &str) -> PyResult<Vec<u8>> {
     let path_obj = Path::new(path);
@@ -58,7 +79,7 @@ pub fn add_truetype_font(
     font_data: &[u8],
     font_id: Ref,
     next_ref_id: &mut i32,
-) -> PyResult<(Name<'static>, CidMap)> {
+) -> PyResult<(Name<'static>, CidMap, FontMetrics)> {
     // Validate font using ttf-parser
     let face = Face::parse(font_data, 0).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid font file: {}", e))
@@ -74,6 +95,17 @@ pub fn add_truetype_font(
     let pdf_ascender = (ascender * scale) as i32;
     let pdf_descender = (descender * scale) as i32;
 
+    // Underline comes from the `post` table, strikeout from OS/2; fall back to
+    // reasonable defaults (matching common TrueType conventions) when a font omits them.
+    let (underline_position, underline_thickness) = face
+        .underline_metrics()
+        .map(|m| (m.position as f32 * scale, m.thickness.max(1) as f32 * scale))
+        .unwrap_or((-100.0, 50.0));
+    let (strikeout_position, strikeout_thickness) = face
+        .strikeout_metrics()
+        .map(|m| (m.position as f32 * scale, m.thickness.max(1) as f32 * scale))
+        .unwrap_or((pdf_ascender as f32 * 0.4, 50.0));
+
     // Get font bounding box
     let bbox = face.global_bounding_box();
     let pdf_bbox = [
@@ -97,6 +129,7 @@ pub fn add_truetype_font(
     let mut cid_map = HashMap::new();
     let mut cid_to_gid_map = Vec::new();
     let mut cid_widths: BTreeMap<u16, i32> = BTreeMap::new();
+    let mut codepoint_widths: HashMap<u32, i32> = HashMap::new();
 
     // Default width (half of 1000 units) used as fallback
     let default_width = 500_i32;
@@ -118,15 +151,14 @@ pub fn add_truetype_font(
                 }
                 cid_to_gid_map[cid as usize] = gid;
 
-                // Capture advance width for this CID once
-                if !cid_widths.contains_key(&cid) {
-                    let width_pdf = face
-                        .glyph_hor_advance(glyph_id)
-                        .map(|adv| ((adv as f32) * scale).round() as i32)
-                        .unwrap_or(default_width)
-                        .max(0);
-                    cid_widths.insert(cid, width_pdf);
-                }
+                // Capture advance width for this CID/codepoint once
+                let width_pdf = face
+                    .glyph_hor_advance(glyph_id)
+                    .map(|adv| ((adv as f32) * scale).round() as i32)
+                    .unwrap_or(default_width)
+                    .max(0);
+                cid_widths.entry(cid).or_insert(width_pdf);
+                codepoint_widths.insert(code_point, width_pdf);
             }
         }
     }
@@ -293,7 +325,17 @@ end",
     let font_name_static = Box::leak(font_name_boxed);
     let font_name_bytes = font_name_static.as_bytes();
 
-    Ok((Name(font_name_bytes), cid_map))
+    let metrics = FontMetrics {
+        underline_position,
+        underline_thickness,
+        strikeout_position,
+        strikeout_thickness,
+        ascender: pdf_ascender as f32,
+        descender: pdf_descender as f32,
+        widths: codepoint_widths,
+    };
+
+    Ok((Name(font_name_bytes), cid_map, metrics))
 }
 
 /// Helper function to find font in assets/fonts directory
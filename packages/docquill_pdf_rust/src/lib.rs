@@ -4,8 +4,10 @@
 //! All business logic stays in Python - Rust only handles low-level PDF operations.
 
 mod canvas;
+mod error;
 mod font_utils;
 mod image_utils;
+mod pdf_import;
 mod types;
 
 use pdf_writer::{Finish, Name, Pdf, Ref};
@@ -14,6 +16,11 @@ use pyo3::types::{PyDict, PyList};
 use std::collections::HashMap;
 
 use canvas::PdfCanvas;
+use error::{
+    FontNotFoundError, ImageDecodeError, InvalidMatrixError, NoCurrentPageError, PdfImportError,
+    RenderError, UnbalancedContentStreamError,
+};
+use font_utils::FontMetrics;
 use types::{Color, Rect};
 
 /// Map Unicode code point to CID (Character ID) for Type0 fonts
@@ -22,6 +29,22 @@ pub type CidMap = HashMap<u32, u16>;
 // CanvasCommand is now parsed directly from Python dicts in canvas_run_batch
 // This avoids pyo3 enum parsing complexity while maintaining zero-copy performance
 
+/// A vector Form XObject being captured via `begin_form_xobject`/
+/// `end_form_xobject`. While one is active, canvas operators target `canvas`
+/// here instead of the current page, and the resources they reference are
+/// collected into the `*_used` maps instead of `*_used_on_current_page`, so
+/// the finished form gets its own self-contained `/Resources` dictionary --
+/// the vector analogue of the image registry.
+struct FormRecording {
+    form_id: Ref,
+    bbox: (f64, f64, f64, f64),
+    canvas: PdfCanvas,
+    fonts_used: HashMap<Name<'static>, Ref>,
+    images_used: HashMap<Name<'static>, Ref>,
+    ext_graphics_states_used: HashMap<Name<'static>, Ref>,
+    patterns_used: HashMap<Name<'static>, Ref>,
+}
+
 /// Main PDF renderer class - minimal implementation
 #[pyclass]
 pub struct PdfCanvasRenderer {
@@ -39,19 +62,50 @@ pub struct PdfCanvasRenderer {
     next_font_id: u32,
     // CID maps for Type0 fonts: font_name -> Unicode -> CID mapping
     type0_cid_maps: HashMap<Name<'static>, CidMap>, // Maps font Name to Unicode->CID mapping
+    // Decoration/width metrics for Type0 fonts: font_name -> FontMetrics
+    font_metrics: HashMap<Name<'static>, FontMetrics>,
+    // Underline/strikethrough flags applied to subsequently drawn strings
+    text_decoration: (bool, bool),
+    // Glyph fallback chain, in priority order, consulted when the active font
+    // lacks a code point. Populated via register_fallback_fonts().
+    fallback_fonts: Vec<(Name<'static>, Ref)>,
     // ExtGState registry (opacity, etc.)
     ext_graphics_states: HashMap<u32, (Name<'static>, Ref)>, // alpha_key -> (Name, Ref)
     ext_graphics_states_used_on_current_page: HashMap<Name<'static>, Ref>,
     // Image registry
+    // `/XObject` resources used on the current page. Holds both raster
+    // images and imported-PDF Form XObjects -- the PDF resource dictionary
+    // doesn't distinguish between them, so they share one pool.
     images_used_on_current_page: HashMap<Name<'static>, Ref>,
     images_registry: HashMap<String, (Ref, Name<'static>)>,
-    next_image_id: i32,
+    // Imported PDF pages, embedded as Form XObjects: handle -> (name, ref,
+    // BBox). Their referenced resources (fonts, images, ...) live directly
+    // in `self.pdf` under freshly allocated refs from `remap_resources`.
+    xobjects_registry: HashMap<String, (Name<'static>, Ref, (f64, f64, f64, f64))>,
+    // Gradient fill patterns (`canvas_set_linear_gradient`) used on the
+    // current page. Not deduplicated -- a Shading Pattern's coordinates and
+    // colors make each call's pattern effectively unique.
+    patterns_used_on_current_page: HashMap<Name<'static>, Ref>,
+    // ExtGState registry for blend modes, keyed by mode name -- mirrors
+    // `ext_graphics_states`, which only caches by alpha.
+    blend_mode_states: HashMap<String, (Name<'static>, Ref)>,
+    // Active `begin_form_xobject`/`end_form_xobject` capture, if any. Canvas
+    // pymethods target this instead of `current_page` while it's set.
+    recording_form: Option<FormRecording>,
+    // When enabled, each page's `PdfCanvas` tracks q/Q, BT/ET, and cm balance
+    // and `new_page`/`save` raise `UnbalancedContentStreamError` on a mismatch
+    // instead of silently writing a malformed content stream.
+    debug_validate: bool,
+    // Multiplier from `set_units`'s unit to points, applied to incoming
+    // coordinates/dimensions before they reach the canvas. 1.0 (points) by
+    // default.
+    unit_scale: f64,
 }
 
 #[pymethods]
 impl PdfCanvasRenderer {
     #[new]
-    fn new(output_path: String, _page_width: f64, _page_height: f64) -> Self {
+    fn new(output_path: String, _page_width: f64, _page_height: f64) -> PyResult<Self> {
         let mut pdf = Pdf::new();
 
         // Create references
@@ -68,11 +122,12 @@ impl PdfCanvasRenderer {
 
         // CID maps for Type0 fonts
         let mut type0_cid_maps = HashMap::new();
+        let mut font_metrics = HashMap::new();
 
         // Try common DejaVu Sans paths
         if let Some(dejavu_path) = font_utils::find_dejavu_sans() {
             if let Ok(font_data) = font_utils::load_font_file(&dejavu_path) {
-                if let Ok((font_name, cid_map)) = font_utils::add_truetype_font(
+                if let Ok((font_name, cid_map, metrics)) = font_utils::add_truetype_font(
                     &mut pdf,
                     &font_data,
                     default_font_id,
@@ -81,15 +136,16 @@ impl PdfCanvasRenderer {
                     font_registry.insert("DejaVu Sans".to_string(), (font_name, default_font_id));
                     font_registry.insert("DejaVuSans".to_string(), (font_name, default_font_id));
                     type0_cid_maps.insert(font_name, cid_map);
+                    font_metrics.insert(font_name, metrics);
                 }
             }
         }
 
         if font_registry.is_empty() {
-            panic!(
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
                 "DejaVu Sans TTF not found. Place DejaVuSans.ttf in assets/fonts/ \
-or install it system-wide so Unicode text can be rendered."
-            );
+or install it system-wide so Unicode text can be rendered.",
+            ));
         }
 
         // Register DejaVu Sans variants if available
@@ -98,12 +154,13 @@ or install it system-wide so Unicode text can be rendered."
             if let Ok(font_data) = font_utils::load_font_file(&bold_path) {
                 let bold_font_id = Ref::new(next_ref);
                 next_ref += 1;
-                if let Ok((font_name, cid_map)) =
+                if let Ok((font_name, cid_map, metrics)) =
                     font_utils::add_truetype_font(&mut pdf, &font_data, bold_font_id, &mut next_ref)
                 {
                     font_registry.insert("DejaVu Sans-Bold".to_string(), (font_name, bold_font_id));
                     font_registry.insert("DejaVuSans-Bold".to_string(), (font_name, bold_font_id));
                     type0_cid_maps.insert(font_name, cid_map);
+                    font_metrics.insert(font_name, metrics);
                 }
             }
         }
@@ -113,7 +170,7 @@ or install it system-wide so Unicode text can be rendered."
             if let Ok(font_data) = font_utils::load_font_file(&italic_path) {
                 let italic_font_id = Ref::new(next_ref);
                 next_ref += 1;
-                if let Ok((font_name, cid_map)) = font_utils::add_truetype_font(
+                if let Ok((font_name, cid_map, metrics)) = font_utils::add_truetype_font(
                     &mut pdf,
                     &font_data,
                     italic_font_id,
@@ -134,6 +191,7 @@ or install it system-wide so Unicode text can be rendered."
                     font_registry
                         .insert("DejaVuSans-Italic".to_string(), (font_name, italic_font_id));
                     type0_cid_maps.insert(font_name, cid_map);
+                    font_metrics.insert(font_name, metrics);
                 }
             }
         }
@@ -143,7 +201,7 @@ or install it system-wide so Unicode text can be rendered."
             if let Ok(font_data) = font_utils::load_font_file(&bold_italic_path) {
                 let bold_italic_font_id = Ref::new(next_ref);
                 next_ref += 1;
-                if let Ok((font_name, cid_map)) = font_utils::add_truetype_font(
+                if let Ok((font_name, cid_map, metrics)) = font_utils::add_truetype_font(
                     &mut pdf,
                     &font_data,
                     bold_italic_font_id,
@@ -166,11 +224,12 @@ or install it system-wide so Unicode text can be rendered."
                         (font_name, bold_italic_font_id),
                     );
                     type0_cid_maps.insert(font_name, cid_map);
+                    font_metrics.insert(font_name, metrics);
                 }
             }
         }
 
-        Self {
+        Ok(Self {
             pdf,
             output_path,
             current_page: None,
@@ -183,18 +242,88 @@ or install it system-wide so Unicode text can be rendered."
             fonts_used_on_current_page: HashMap::new(),
             next_font_id: 2,
             type0_cid_maps, // CID maps for Type0 fonts
+            font_metrics,
+            text_decoration: (false, false),
+            fallback_fonts: Vec::new(),
             ext_graphics_states: HashMap::new(),
             ext_graphics_states_used_on_current_page: HashMap::new(),
             images_used_on_current_page: HashMap::new(),
             images_registry: HashMap::new(),
-            next_image_id: 2000, // Start from 2000 to avoid conflicts
+            xobjects_registry: HashMap::new(),
+            patterns_used_on_current_page: HashMap::new(),
+            blend_mode_states: HashMap::new(),
+            recording_form: None,
+            debug_validate: false,
+            unit_scale: 1.0,
+        })
+    }
+
+    /// Enable content-stream balance checking: `new_page`/`save` will raise
+    /// `UnbalancedContentStreamError` if the page being finalized has an
+    /// unmatched `q`/`Q`, `BT`/`ET`, or an unscoped `cm`, naming the imbalance.
+    fn set_debug_validate(&mut self, enabled: bool) {
+        self.debug_validate = enabled;
+        if let Some(ref mut form) = self.recording_form {
+            form.canvas.set_debug_validate(enabled);
+        }
+        if let Some((_, _, ref mut canvas)) = self.current_page {
+            canvas.set_debug_validate(enabled);
         }
     }
 
+    /// Set the unit incoming coordinates and dimensions are expressed in:
+    /// `"pt"` (default), `"mm"`, or `"in"`. Every canvas call that takes a
+    /// position, size, offset, or line width is scaled to points by this
+    /// factor before it reaches the canvas -- specifically the arguments of
+    /// `new_page`, `set_page_size`, `canvas_rect`/`canvas_round_rect`
+    /// (position, size, radius), `canvas_line`, `canvas_translate`,
+    /// `canvas_set_line_width`, `canvas_set_dash`, `canvas_draw_string*`
+    /// (position and, for the justified variant, target width),
+    /// `canvas_draw_image*` (position and size), `canvas_draw_xobject`/
+    /// `canvas_draw_form` (position only, not `scale`),
+    /// `begin_form_xobject`'s `bbox`, `canvas_clip_rect`,
+    /// `canvas_path_move_to`/`canvas_path_line_to`/`canvas_path_curve_to`,
+    /// `canvas_set_linear_gradient`'s two endpoints, and the translation
+    /// component of `canvas_transform`'s matrix. Font sizes
+    /// (`canvas_set_font`) and
+    /// unitless factors/angles (`canvas_scale`, `canvas_rotate`, the linear
+    /// part of `canvas_transform`) are never scaled. Takes effect for calls
+    /// made after it -- switching units mid-document does not rescale
+    /// content already drawn.
+    fn set_units(&mut self, unit: String) -> PyResult<()> {
+        self.unit_scale = match unit.as_str() {
+            "pt" => 1.0,
+            "mm" => 72.0 / 25.4,
+            "in" => 72.0,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown unit '{}': expected 'pt', 'mm', or 'in'",
+                    other
+                )))
+            }
+        };
+        Ok(())
+    }
+
     /// Add a new page
     fn new_page(&mut self, page_width: f64, page_height: f64) -> PyResult<()> {
+        if page_width <= 0.0 || page_height <= 0.0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "page dimensions must be positive, got {}x{}",
+                page_width, page_height
+            )));
+        }
+        let page_width = self.to_pt(page_width);
+        let page_height = self.to_pt(page_height);
+
         // Save current page content and finalize page
         if let Some((_page_id, content_id, canvas)) = self.current_page.take() {
+            if self.debug_validate {
+                let errors = canvas.validation_errors();
+                if !errors.is_empty() {
+                    return Err(RenderError::UnbalancedContentStream(errors.join("; ")).into());
+                }
+            }
             let content_bytes = canvas.finish();
             self.pdf.stream(content_id, &content_bytes);
 
@@ -235,6 +364,12 @@ or install it system-wide so Unicode text can be rendered."
                             ext_states.pair(*name, *gs_ref);
                         }
                     }
+                    if !self.patterns_used_on_current_page.is_empty() {
+                        let mut patterns = resources.patterns();
+                        for (name, pattern_ref) in &self.patterns_used_on_current_page {
+                            patterns.pair(*name, *pattern_ref);
+                        }
+                    }
                 }
 
                 page.finish();
@@ -245,18 +380,18 @@ or install it system-wide so Unicode text can be rendered."
         self.images_used_on_current_page.clear();
         self.fonts_used_on_current_page.clear();
         self.ext_graphics_states_used_on_current_page.clear();
+        self.patterns_used_on_current_page.clear();
 
         // Create new page references
-        let page_id = Ref::new(self.next_ref_id);
-        self.next_ref_id += 1;
-        let content_id = Ref::new(self.next_ref_id);
-        self.next_ref_id += 1;
+        let page_id = self.next_ref();
+        let content_id = self.next_ref();
 
         // Store page info
         self.current_page_info = Some((page_id, page_width, page_height));
 
         // Create new canvas
-        let canvas = PdfCanvas::new();
+        let mut canvas = PdfCanvas::new();
+        canvas.set_debug_validate(self.debug_validate);
         self.current_page = Some((page_id, content_id, canvas));
 
         // Add to pages list
@@ -268,7 +403,11 @@ or install it system-wide so Unicode text can be rendered."
             .get("DejaVu Sans")
             .or_else(|| self.font_registry.get("DejaVuSans"))
             .copied()
-            .expect("DejaVu Sans Type0 font must be registered");
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "DejaVu Sans Type0 font is not registered",
+                )
+            })?;
         if !self
             .fonts_used_on_current_page
             .contains_key(&default_font_name)
@@ -281,85 +420,44 @@ or install it system-wide so Unicode text can be rendered."
     }
 
     /// Save PDF to file
-    fn save(&mut self) -> PyResult<()> {
-        // Save current page content and finalize page
-        if let Some((_page_id, content_id, canvas)) = self.current_page.take() {
-            let content_bytes = canvas.finish();
-            self.pdf.stream(content_id, &content_bytes);
-
-            // Create and finish the page
-            if let Some((page_info_id, page_width, page_height)) = self.current_page_info.take() {
-                let mut page = self.pdf.page(page_info_id);
-                page.media_box(pdf_writer::Rect::new(
-                    0.0,
-                    0.0,
-                    page_width as f32,
-                    page_height as f32,
-                ));
-                if let Some(page_tree_id) = self.page_tree_id {
-                    page.parent(page_tree_id);
-                }
-                page.contents(content_id);
-
-                // Add resources (fonts, images, ext graphics states)
-                {
-                    let mut resources = page.resources();
-                    if !self.fonts_used_on_current_page.is_empty() {
-                        let mut fonts = resources.fonts();
-                        for (font_name, font_id) in &self.fonts_used_on_current_page {
-                            fonts.pair(*font_name, *font_id);
-                        }
-                    }
-                    if !self.images_used_on_current_page.is_empty() {
-                        let mut xobject_dict = resources.x_objects();
-                        for (image_name, image_id) in &self.images_used_on_current_page {
-                            xobject_dict.pair(*image_name, *image_id);
-                        }
-                    }
-                    if !self.ext_graphics_states_used_on_current_page.is_empty() {
-                        let mut ext_states = resources.ext_g_states();
-                        for (name, gs_ref) in &self.ext_graphics_states_used_on_current_page {
-                            ext_states.pair(*name, *gs_ref);
-                        }
-                    }
-                }
-
-                page.finish();
-            }
-        }
-
-        // Update page tree
-        if let Some(page_tree_id) = self.page_tree_id {
-            let mut page_tree = self.pdf.pages(page_tree_id);
-            page_tree.kids(self.pages.iter().cloned());
-            page_tree.count(self.pages.len() as i32);
-        }
-
-        // Finish PDF and get bytes
-        let pdf = std::mem::replace(&mut self.pdf, Pdf::new());
-        let pdf_bytes = pdf.finish();
-
-        // Write to file
-        std::fs::write(&self.output_path, pdf_bytes).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
-                "Failed to write PDF to {}: {}",
-                self.output_path, e
-            ))
-        })?;
+    fn save(&mut self, py: Python) -> PyResult<()> {
+        // Finalizing the page tree, running `pdf.finish()`, and writing the
+        // file are all pure Rust with no `PyObject`/`PyAny` involved, so we
+        // release the GIL for the duration -- on large documents this is
+        // enough CPU/IO time that holding it would stall other Python
+        // threads in our server.
+        py.allow_threads(|| {
+            let pdf_bytes = self.finalize_pdf()?;
+            std::fs::write(&self.output_path, pdf_bytes).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+                    "Failed to write PDF to {}: {}",
+                    self.output_path, e
+                ))
+            })
+        })
+    }
 
+    /// Save PDF by handing the finished bytes to a Python file-like object's
+    /// `write` method, for callers (e.g. S3 multipart uploaders) that only
+    /// expose a stream rather than a filesystem path.
+    fn save_to_writer(&mut self, py: Python, file_obj: &PyAny) -> PyResult<()> {
+        let pdf_bytes = py.allow_threads(|| self.finalize_pdf())?;
+        file_obj.call_method1("write", (pyo3::types::PyBytes::new(py, &pdf_bytes),))?;
         Ok(())
     }
 
     // ===== Canvas Operations =====
 
+    // ===== Canvas Operations =====
+
     /// Save canvas state
     fn canvas_save_state(&mut self) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         canvas.save_state();
         Ok(())
@@ -367,12 +465,12 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Restore canvas state
     fn canvas_restore_state(&mut self) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         canvas.restore_state();
         Ok(())
@@ -380,12 +478,12 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Set fill color (RGB 0.0-1.0)
     fn canvas_set_fill_color(&mut self, r: f64, g: f64, b: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         let color = Color { r, g, b };
         canvas.set_fill_color(color);
@@ -394,12 +492,12 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Set stroke color (RGB 0.0-1.0)
     fn canvas_set_stroke_color(&mut self, r: f64, g: f64, b: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         let color = Color { r, g, b };
         canvas.set_stroke_color(color);
@@ -408,30 +506,33 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Set line width
     fn canvas_set_line_width(&mut self, width: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let width = self.to_pt(width);
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         canvas.set_line_width(width);
         Ok(())
     }
 
     /// Set dash pattern
-    fn canvas_set_dash(&mut self, pattern: Vec<f64>) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+    #[pyo3(signature = (pattern, phase=0.0))]
+    fn canvas_set_dash(&mut self, pattern: Vec<f64>, phase: f64) -> PyResult<()> {
+        let phase = self.to_pt(phase);
+        let pattern: Vec<f64> = pattern.into_iter().map(|v| self.to_pt(v)).collect();
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         if pattern.is_empty() {
             canvas.set_dash(vec![], 0.0);
         } else {
-            let phase = if pattern.len() > 0 { pattern[0] } else { 0.0 };
             canvas.set_dash(pattern, phase);
         }
         Ok(())
@@ -439,61 +540,44 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Set font name and size
     fn canvas_set_font(&mut self, name: String, size: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
-            c
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
-        };
-
-        // Get or create font
-        let (font_name, font_id) = if let Some(&(name_ref, id_ref)) = self.font_registry.get(&name)
-        {
-            (name_ref, id_ref)
-        } else {
-            // Try to find font with different name variations
-            let mut found_font = None;
+        let (font_name, font_id) = self.resolve_font(&name)?;
 
-            // Try common variations
-            let variations = vec![
-                name.replace("-", " "),
-                name.replace(" ", "-"),
-                name.replace("Bold", "-Bold"),
-                name.replace("Italic", "-Italic"),
-                name.replace("Oblique", "-Oblique"),
-            ];
-
-            for variant in variations {
-                if let Some(&font) = self.font_registry.get(&variant) {
-                    found_font = Some(font);
-                    break;
-                }
+        // Register font for the active form, if one is being recorded, else
+        // the current page.
+        if let Some(form) = self.recording_form.as_mut() {
+            if !form.fonts_used.contains_key(&font_name) {
+                form.fonts_used.insert(font_name, font_id);
             }
-
-            // Fallback to default Type0 font (DejaVu Sans family)
-            let default_font = found_font.unwrap_or_else(|| {
-                self.font_registry
-                    .get("DejaVu Sans")
-                    .or_else(|| self.font_registry.get("DejaVuSans"))
-                    .copied()
-                    .expect("DejaVu Sans Type0 font must be registered")
-            });
-
-            // Cache the mapping for future use
-            self.font_registry.insert(name.clone(), default_font);
-            default_font
-        };
-
-        // Register font for current page
-        if !self.fonts_used_on_current_page.contains_key(&font_name) {
+        } else if !self.fonts_used_on_current_page.contains_key(&font_name) {
             self.fonts_used_on_current_page.insert(font_name, font_id);
         }
 
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+
         canvas.set_font(font_name, size);
         Ok(())
     }
 
+    /// Register a chain of fallback fonts, in priority order, for glyph coverage.
+    /// When `canvas_draw_string` encounters a code point the active font can't
+    /// render, it consults these fonts (in the given order) before giving up and
+    /// drawing `.notdef`. Resolved through the same name-variation lookup as
+    /// `canvas_set_font`, so unknown names fall back to DejaVu Sans like any
+    /// other font name would.
+    fn register_fallback_fonts(&mut self, font_keys: Vec<String>) -> PyResult<()> {
+        self.fallback_fonts = font_keys
+            .iter()
+            .map(|key| self.resolve_font(key))
+            .collect::<Result<Vec<_>, RenderError>>()?;
+        Ok(())
+    }
+
     /// Set current graphics state opacity (both fill and stroke)
     fn canvas_set_opacity(&mut self, opacity: f64) -> PyResult<()> {
         let clamped = opacity.clamp(0.0, 1.0);
@@ -502,18 +586,22 @@ or install it system-wide so Unicode text can be rendered."
             self.get_or_create_ext_graphics_state(alpha_key, clamped as f32);
 
         {
-            let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            let canvas = if let Some(form) = self.recording_form.as_mut() {
+                &mut form.canvas
+            } else if let Some((_, _, ref mut c)) = self.current_page {
                 c
             } else {
-                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                    "No current page",
-                ));
+                return Err(RenderError::NoCurrentPage.into());
             };
             canvas.set_ext_graphics_state(name);
         }
 
-        self.ext_graphics_states_used_on_current_page
-            .insert(name, gs_ref);
+        if let Some(form) = self.recording_form.as_mut() {
+            form.ext_graphics_states_used.insert(name, gs_ref);
+        } else {
+            self.ext_graphics_states_used_on_current_page
+                .insert(name, gs_ref);
+        }
         Ok(())
     }
 
@@ -527,12 +615,18 @@ or install it system-wide so Unicode text can be rendered."
         fill: bool,
         stroke: bool,
     ) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let (x, y, width, height) = (
+            self.to_pt(x),
+            self.to_pt(y),
+            self.to_pt(width),
+            self.to_pt(height),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         let rect = Rect::new(x, y, width, height);
         canvas.rect(rect, fill, stroke);
@@ -550,12 +644,19 @@ or install it system-wide so Unicode text can be rendered."
         fill: bool,
         stroke: bool,
     ) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let (x, y, width, height, radius) = (
+            self.to_pt(x),
+            self.to_pt(y),
+            self.to_pt(width),
+            self.to_pt(height),
+            self.to_pt(radius),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         let rect = Rect::new(x, y, width, height);
         canvas.round_rect(rect, radius, fill, stroke);
@@ -564,199 +665,923 @@ or install it system-wide so Unicode text can be rendered."
 
     /// Draw line
     fn canvas_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+        let (x1, y1, x2, y2) = (
+            self.to_pt(x1),
+            self.to_pt(y1),
+            self.to_pt(x2),
+            self.to_pt(y2),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
         canvas.line(x1, y1, x2, y2);
         Ok(())
     }
 
-    /// Draw text string
-    fn canvas_draw_string(&mut self, x: f64, y: f64, text: String) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+    /// Set fill color in CMYK (each component 0.0-1.0).
+    fn canvas_set_fill_color_cmyk(
+        &mut self,
+        cyan: f64,
+        magenta: f64,
+        yellow: f64,
+        black: f64,
+    ) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
-
-        // Get current font name from canvas
-        let current_font_name = canvas.get_font_name();
-
-        // Require CID map for every font (all fonts are Type0)
-        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                "No CID map registered for font {:?}",
-                current_font_name
-            ))
-        })?;
-
-        canvas.draw_string(x, y, &text, cid_map);
+        canvas.set_fill_color_cmyk(cyan, magenta, yellow, black);
         Ok(())
     }
 
-    /// Draw image from bytes
-    fn canvas_draw_image(
+    /// Set the fill color to a two-stop linear (axial) gradient from
+    /// `(x0, y0)` color `(r0, g0, b0)` to `(x1, y1)` color `(r1, g1, b1)`, in
+    /// the current coordinate space. Subsequent fills use the gradient, like
+    /// any other fill color, until changed again.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_set_linear_gradient(
         &mut self,
-        x: f64,
-        y: f64,
-        width: f64,
-        height: f64,
-        image_data: Vec<u8>,
+        x0: f64,
+        y0: f64,
+        r0: f64,
+        g0: f64,
+        b0: f64,
+        x1: f64,
+        y1: f64,
+        r1: f64,
+        g1: f64,
+        b1: f64,
     ) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
-            c
+        let (x0, y0, x1, y1) = (self.to_pt(x0), self.to_pt(y0), self.to_pt(x1), self.to_pt(y1));
+
+        let function_ref = self.next_ref();
+        {
+            let mut func = self.pdf.exponential_function(function_ref);
+            func.domain([0.0, 1.0]);
+            func.c0([r0 as f32, g0 as f32, b0 as f32]);
+            func.c1([r1 as f32, g1 as f32, b1 as f32]);
+            func.n(1.0);
+        }
+
+        let pattern_ref = self.next_ref();
+        {
+            let mut pattern = self.pdf.shading_pattern(pattern_ref);
+            {
+                let mut shading = pattern.function_shading();
+                shading.shading_type(pdf_writer::types::FunctionShadingType::Axial);
+                shading.color_space().device_rgb();
+                shading.coords([x0 as f32, y0 as f32, x1 as f32, y1 as f32]);
+                shading.extend([true, true]);
+                shading.function(function_ref);
+            }
+        }
+
+        let name_str = format!("P{}", pattern_ref.get());
+        let pattern_name = Name(Box::leak(name_str.into_boxed_str()).as_bytes());
+
+        if let Some(form) = self.recording_form.as_mut() {
+            form.patterns_used.insert(pattern_name, pattern_ref);
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
-        };
+            self.patterns_used_on_current_page
+                .insert(pattern_name, pattern_ref);
+        }
 
-        // Create a unique key for this image
-        // Use a simple approach: use length and first/last bytes as key
-        let key = if image_data.len() > 16 {
-            // Use first 8 and last 8 bytes for uniqueness
-            let prefix: u64 = u64::from_be_bytes([
-                image_data[0],
-                image_data[1],
-                image_data[2],
-                image_data[3],
-                image_data[4],
-                image_data[5],
-                image_data[6],
-                image_data[7],
-            ]);
-            let suffix: u64 = u64::from_be_bytes([
-                image_data[image_data.len() - 8],
-                image_data[image_data.len() - 7],
-                image_data[image_data.len() - 6],
-                image_data[image_data.len() - 5],
-                image_data[image_data.len() - 4],
-                image_data[image_data.len() - 3],
-                image_data[image_data.len() - 2],
-                image_data[image_data.len() - 1],
-            ]);
-            format!(
-                "canvas_image_{:x}_{:x}_{}",
-                prefix,
-                suffix,
-                image_data.len()
-            )
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
         } else {
-            // For small images, use all bytes
-            format!(
-                "canvas_image_{}_{}",
-                image_data.len(),
-                image_data
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<String>()
-            )
+            return Err(RenderError::NoCurrentPage.into());
         };
+        canvas.set_fill_pattern(pattern_name);
+        Ok(())
+    }
 
-        // Check if image is already registered
-        let (image_id, image_name) = if let Some(&(id, name)) = self.images_registry.get(&key) {
-            (id, name)
+    /// Intersect the clip path with a rectangle. Only takes effect for the
+    /// current `save_state`/`restore_state` scope -- callers almost always
+    /// want to wrap this (and the drawing it's meant to clip) in one.
+    fn canvas_clip_rect(&mut self, x: f64, y: f64, width: f64, height: f64) -> PyResult<()> {
+        let (x, y, width, height) = (
+            self.to_pt(x),
+            self.to_pt(y),
+            self.to_pt(width),
+            self.to_pt(height),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
         } else {
-            // Register new image
-            let image_id = Ref::new(self.next_image_id);
-            self.next_image_id += 1;
-
-            let image_name = image_utils::add_image_to_pdf(
-                &mut self.pdf,
-                &image_data,
-                image_id,
-                &mut self.next_image_id,
-            )?;
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.clip_rect(Rect::new(x, y, width, height));
+        Ok(())
+    }
 
-            self.images_registry.insert(key, (image_id, image_name));
-            (image_id, image_name)
+    /// Set the blend mode used for subsequent fills/strokes/images (e.g.
+    /// "Multiply", "Screen", "Darken"), via an ExtGState `/BM` entry -- the
+    /// same mechanism `canvas_set_opacity` uses for `/ca`/`/CA`. Persists
+    /// like opacity until changed again.
+    fn canvas_set_blend_mode(&mut self, mode: String) -> PyResult<()> {
+        let blend_mode = match mode.as_str() {
+            "Normal" => pdf_writer::types::BlendMode::Normal,
+            "Multiply" => pdf_writer::types::BlendMode::Multiply,
+            "Screen" => pdf_writer::types::BlendMode::Screen,
+            "Overlay" => pdf_writer::types::BlendMode::Overlay,
+            "Darken" => pdf_writer::types::BlendMode::Darken,
+            "Lighten" => pdf_writer::types::BlendMode::Lighten,
+            "ColorDodge" => pdf_writer::types::BlendMode::ColorDodge,
+            "ColorBurn" => pdf_writer::types::BlendMode::ColorBurn,
+            "HardLight" => pdf_writer::types::BlendMode::HardLight,
+            "SoftLight" => pdf_writer::types::BlendMode::SoftLight,
+            "Difference" => pdf_writer::types::BlendMode::Difference,
+            "Exclusion" => pdf_writer::types::BlendMode::Exclusion,
+            "Hue" => pdf_writer::types::BlendMode::Hue,
+            "Saturation" => pdf_writer::types::BlendMode::Saturation,
+            "Color" => pdf_writer::types::BlendMode::Color,
+            "Luminosity" => pdf_writer::types::BlendMode::Luminosity,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown blend mode '{}'",
+                    other
+                )))
+            }
         };
+        let (name, gs_ref) = self.get_or_create_blend_mode_state(blend_mode, &mode);
 
-        // Register image for current page
-        if !self.images_used_on_current_page.contains_key(&image_name) {
-            self.images_used_on_current_page
-                .insert(image_name, image_id);
+        {
+            let canvas = if let Some(form) = self.recording_form.as_mut() {
+                &mut form.canvas
+            } else if let Some((_, _, ref mut c)) = self.current_page {
+                c
+            } else {
+                return Err(RenderError::NoCurrentPage.into());
+            };
+            canvas.set_ext_graphics_state(name);
         }
 
-        // Draw image on canvas
-        canvas.draw_image(image_name, x, y, width, height);
+        if let Some(form) = self.recording_form.as_mut() {
+            form.ext_graphics_states_used.insert(name, gs_ref);
+        } else {
+            self.ext_graphics_states_used_on_current_page
+                .insert(name, gs_ref);
+        }
         Ok(())
     }
 
-    /// Translate coordinate system
-    fn canvas_translate(&mut self, x: f64, y: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+    /// Begin a path at `(x, y)` (`m`). Paint it with `canvas_path_paint`.
+    fn canvas_path_move_to(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let (x, y) = (self.to_pt(x), self.to_pt(y));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
-        canvas.translate(x, y);
+        canvas.path_move_to(x, y);
         Ok(())
     }
 
-    /// Rotate coordinate system (radians)
-    fn canvas_rotate(&mut self, angle: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+    /// Append a straight line to `(x, y)` (`l`) to the path begun by
+    /// `canvas_path_move_to`.
+    fn canvas_path_line_to(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let (x, y) = (self.to_pt(x), self.to_pt(y));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
-        // Convert radians to degrees (canvas.rotate expects degrees)
-        let angle_degrees = angle.to_degrees();
-        canvas.rotate(angle_degrees);
+        canvas.path_line_to(x, y);
         Ok(())
     }
 
-    /// Scale coordinate system
-    fn canvas_scale(&mut self, x: f64, y: f64) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+    /// Append a cubic Bezier segment (`c`) to `(x3, y3)` with control points
+    /// `(x1, y1)`/`(x2, y2)` to the path begun by `canvas_path_move_to`.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_path_curve_to(
+        &mut self,
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x3: f64,
+        y3: f64,
+    ) -> PyResult<()> {
+        let (x1, y1, x2, y2, x3, y3) = (
+            self.to_pt(x1),
+            self.to_pt(y1),
+            self.to_pt(x2),
+            self.to_pt(y2),
+            self.to_pt(x3),
+            self.to_pt(y3),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
+            return Err(RenderError::NoCurrentPage.into());
         };
-        canvas.scale(x, y);
+        canvas.path_curve_to(x1, y1, x2, y2, x3, y3);
         Ok(())
     }
 
-    /// Apply transformation matrix [a, b, c, d, e, f]
-    fn canvas_transform(&mut self, matrix: Vec<f64>) -> PyResult<()> {
-        if matrix.len() != 6 {
-            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "Transform matrix must have 6 elements",
-            ));
+    /// Close the current subpath with a straight line back to its start (`h`).
+    fn canvas_path_close(&mut self) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.path_close();
+        Ok(())
+    }
+
+    /// Paint the path built since the last paint with `canvas_path_move_to`/
+    /// `canvas_path_line_to`/`canvas_path_curve_to`/`canvas_path_close`. Same
+    /// fill/stroke flags as `canvas_rect`; `even_odd` picks the fill rule
+    /// when `fill` is set.
+    fn canvas_path_paint(&mut self, fill: bool, stroke: bool, even_odd: bool) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.path_paint(fill, stroke, even_odd);
+        Ok(())
+    }
+
+    /// Enable or disable underline/strikethrough decoration for strings drawn via
+    /// `canvas_draw_string` from this point on. Persists across draw calls (like the
+    /// current font or fill color) until changed again.
+    fn canvas_set_text_decoration(&mut self, underline: bool, strikethrough: bool) -> PyResult<()> {
+        self.text_decoration = (underline, strikethrough);
+        Ok(())
+    }
+
+    /// Draw text string
+    fn canvas_draw_string(&mut self, x: f64, y: f64, text: String) -> PyResult<()> {
+        let (x, y) = (self.to_pt(x), self.to_pt(y));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+
+        // Get current font name from canvas
+        let current_font_name = canvas.get_font_name();
+
+        // Require CID map for every font (all fonts are Type0)
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+
+        let needs_fallback = !self.fallback_fonts.is_empty()
+            && text.chars().any(|ch| !cid_map.contains_key(&(ch as u32)));
+        let mut fallback_fonts_used: Vec<(Name<'static>, Ref)> = Vec::new();
+
+        if !needs_fallback {
+            canvas.draw_string(x, y, &text, cid_map);
+        } else {
+            // Split into runs by which registered font (current font first, then
+            // each fallback in registration order) actually covers each code
+            // point. A code point none of them cover renders as `.notdef` (CID 0)
+            // under the current font rather than silently dropping.
+            let mut runs: Vec<(Name<'static>, Vec<u8>)> = Vec::new();
+
+            for ch in text.chars() {
+                let code_point = ch as u32;
+                let (font_name, fallback_id, cid) = if let Some(&cid) = cid_map.get(&code_point) {
+                    (current_font_name, None, cid)
+                } else {
+                    self.fallback_fonts
+                        .iter()
+                        .find_map(|&(fb_name, fb_id)| {
+                            self.type0_cid_maps
+                                .get(&fb_name)
+                                .and_then(|fb_map| fb_map.get(&code_point))
+                                .map(|&cid| (fb_name, Some(fb_id), cid))
+                        })
+                        .unwrap_or((current_font_name, None, 0))
+                };
+
+                if let Some(font_id) = fallback_id {
+                    if !fallback_fonts_used.iter().any(|&(n, _)| n == font_name) {
+                        fallback_fonts_used.push((font_name, font_id));
+                    }
+                }
+
+                let cid_bytes = [(cid >> 8) as u8, (cid & 0xFF) as u8];
+                match runs.last_mut() {
+                    Some(last) if last.0 == font_name => last.1.extend_from_slice(&cid_bytes),
+                    _ => runs.push((font_name, cid_bytes.to_vec())),
+                }
+            }
+
+            canvas.draw_string_multi_font(x, y, &runs);
+        }
+
+        let (underline, strikethrough) = self.text_decoration;
+        if (underline || strikethrough) && !text.is_empty() {
+            let font_size = canvas.get_font_size();
+            let metrics = self.font_metrics.get(&current_font_name);
+            let width_1000: i32 = text
+                .chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum();
+            let width = width_1000 as f64 * font_size / 1000.0;
+            let underline_metrics = metrics
+                .map(|m| (m.underline_position, m.underline_thickness))
+                .unwrap_or((-100.0, 50.0));
+            let strikeout_metrics = metrics
+                .map(|m| (m.strikeout_position, m.strikeout_thickness))
+                .unwrap_or((200.0, 50.0));
+
+            if underline {
+                let (position, thickness) = underline_metrics;
+                self.draw_decoration_line(x, y, width, font_size, position, thickness)?;
+            }
+            if strikethrough {
+                let (position, thickness) = strikeout_metrics;
+                self.draw_decoration_line(x, y, width, font_size, position, thickness)?;
+            }
+        }
+
+        // Deferred until here, after every use of `canvas` above: while a
+        // form is being recorded, `canvas` borrows `self.recording_form`, so
+        // this can't touch that same field until the borrow ends.
+        if let Some(form) = self.recording_form.as_mut() {
+            for (font_name, font_id) in fallback_fonts_used {
+                form.fonts_used.entry(font_name).or_insert(font_id);
+            }
+        } else {
+            for (font_name, font_id) in fallback_fonts_used {
+                self.fonts_used_on_current_page
+                    .entry(font_name)
+                    .or_insert(font_id);
+            }
         }
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+
+        Ok(())
+    }
+
+    /// Draw a text string with a solid background behind it (Word-style highlighting).
+    /// Measures the string via the font's own per-codepoint widths, fills a rectangle
+    /// spanning descender to ascender in the highlight color, then draws the text on
+    /// top using the normal `canvas_draw_string` path (so decoration still applies).
+    fn canvas_draw_string_highlighted(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        r: f64,
+        g: f64,
+        b: f64,
+    ) -> PyResult<()> {
+        if !text.is_empty() {
+            // Scaled locally for the highlight rect's own math; `x`/`y`
+            // themselves are left in the caller's unit so the delegated
+            // `canvas_draw_string` call below can scale them itself.
+            let (x_pt, y_pt) = (self.to_pt(x), self.to_pt(y));
+            let canvas = if let Some(form) = self.recording_form.as_mut() {
+                &mut form.canvas
+            } else if let Some((_, _, ref mut c)) = self.current_page {
+                c
+            } else {
+                return Err(RenderError::NoCurrentPage.into());
+            };
+
+            let current_font_name = canvas.get_font_name();
+            let font_size = canvas.get_font_size();
+            let metrics = self.font_metrics.get(&current_font_name);
+            let width_1000: i32 = text
+                .chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum();
+            let width = width_1000 as f64 * font_size / 1000.0;
+            let (ascender, descender) = metrics
+                .map(|m| (m.ascender, m.descender))
+                .unwrap_or((700.0, -200.0));
+            let top = y_pt + ascender as f64 * font_size / 1000.0;
+            let bottom = y_pt + descender as f64 * font_size / 1000.0;
+
+            canvas.save_state();
+            canvas.set_fill_color(Color { r, g, b });
+            canvas.rect(Rect::new(x_pt, bottom, width, top - bottom), true, false);
+            canvas.restore_state();
+        }
+
+        self.canvas_draw_string(x, y, text)
+    }
+
+    /// Draw a line of text stretched (or compressed) to exactly `target_width` points
+    /// by distributing the slack between its words as `TJ` adjustments, instead of
+    /// relying on the caller to have pre-padded the string with guessed spacing.
+    fn canvas_draw_string_justified(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        target_width: f64,
+    ) -> PyResult<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let (x, y, target_width) = (self.to_pt(x), self.to_pt(y), self.to_pt(target_width));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+
+        let current_font_name = canvas.get_font_name();
+        let font_size = canvas.get_font_size();
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+        let metrics = self.font_metrics.get(&current_font_name);
+        let width_of_1000 = |s: &str| -> i32 {
+            s.chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum()
+        };
+
+        let words: Vec<&str> = text.split(' ').collect();
+        let gap_count = words.len().saturating_sub(1);
+        if gap_count == 0 {
+            // No word boundary to distribute slack across.
+            canvas.draw_string(x, y, &text, cid_map);
+            return Ok(());
+        }
+
+        let space_width_1000 = width_of_1000(" ").max(1);
+        let natural_width_1000: i32 = words.iter().map(|w| width_of_1000(w)).sum::<i32>()
+            + space_width_1000 * gap_count as i32;
+        let natural_width = natural_width_1000 as f64 * font_size / 1000.0;
+        let slack_1000_per_gap = ((target_width - natural_width) * 1000.0 / font_size) / gap_count as f64;
+        // TJ amounts are subtracted from the advance, so widening a gap takes a negative number.
+        let adjustment = -(slack_1000_per_gap as f32);
+
+        let segments: Vec<String> = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i < gap_count {
+                    format!("{} ", word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect();
+        let adjustments = vec![adjustment; gap_count];
+
+        canvas.draw_string_positioned(x, y, &segments, &adjustments, cid_map);
+        Ok(())
+    }
+
+    /// Draw image from bytes
+    fn canvas_draw_image(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        image_data: Vec<u8>,
+    ) -> PyResult<()> {
+        let (x, y, width, height) = (
+            self.to_pt(x),
+            self.to_pt(y),
+            self.to_pt(width),
+            self.to_pt(height),
+        );
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+
+        // Create a unique key for this image
+        // Use a simple approach: use length and first/last bytes as key
+        let key = if image_data.len() > 16 {
+            // Use first 8 and last 8 bytes for uniqueness
+            let prefix: u64 = u64::from_be_bytes([
+                image_data[0],
+                image_data[1],
+                image_data[2],
+                image_data[3],
+                image_data[4],
+                image_data[5],
+                image_data[6],
+                image_data[7],
+            ]);
+            let suffix: u64 = u64::from_be_bytes([
+                image_data[image_data.len() - 8],
+                image_data[image_data.len() - 7],
+                image_data[image_data.len() - 6],
+                image_data[image_data.len() - 5],
+                image_data[image_data.len() - 4],
+                image_data[image_data.len() - 3],
+                image_data[image_data.len() - 2],
+                image_data[image_data.len() - 1],
+            ]);
+            format!(
+                "canvas_image_{:x}_{:x}_{}",
+                prefix,
+                suffix,
+                image_data.len()
+            )
+        } else {
+            // For small images, use all bytes
+            format!(
+                "canvas_image_{}_{}",
+                image_data.len(),
+                image_data
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>()
+            )
+        };
+
+        // Check if image is already registered
+        let (image_id, image_name) = if let Some(&(id, name)) = self.images_registry.get(&key) {
+            (id, name)
+        } else {
+            // Register new image (inline ref allocation: `canvas` above already
+            // holds a mutable borrow of `self.current_page`, so `self.next_ref()`
+            // can't be called here without the borrow checker treating it as a
+            // conflicting whole-`self` borrow).
+            let image_id = Ref::new(self.next_ref_id);
+            self.next_ref_id += 1;
+
+            let image_name = image_utils::add_image_to_pdf(
+                &mut self.pdf,
+                &image_data,
+                image_id,
+                &mut self.next_ref_id,
+            )?;
+
+            self.images_registry.insert(key, (image_id, image_name));
+            (image_id, image_name)
+        };
+
+        // Draw image on canvas
+        canvas.draw_image(image_name, x, y, width, height);
+
+        // Deferred until after `canvas`'s last use above: while a form is
+        // being recorded, `canvas` borrows `self.recording_form`, so this
+        // can't touch that same field until the borrow ends.
+        if let Some(form) = self.recording_form.as_mut() {
+            if !form.images_used.contains_key(&image_name) {
+                form.images_used.insert(image_name, image_id);
+            }
+        } else if !self.images_used_on_current_page.contains_key(&image_name) {
+            self.images_used_on_current_page
+                .insert(image_name, image_id);
+        }
+        Ok(())
+    }
+
+    /// Embed `image_data` as an XObject under `key`, without drawing it. Call
+    /// once per distinct image (e.g. a logo reused across many pages), then
+    /// draw it repeatedly with `canvas_draw_image_key` -- this avoids resending
+    /// the same bytes across the Python/Rust boundary for every page. A no-op
+    /// if `key` is already registered.
+    fn register_image(&mut self, key: String, image_data: Vec<u8>) -> PyResult<()> {
+        if self.images_registry.contains_key(&key) {
+            return Ok(());
+        }
+
+        let image_id = self.next_ref();
+        let image_name =
+            image_utils::add_image_to_pdf(&mut self.pdf, &image_data, image_id, &mut self.next_ref_id)?;
+
+        self.images_registry.insert(key, (image_id, image_name));
+        Ok(())
+    }
+
+    /// Draw an image previously embedded with `register_image`, by key.
+    fn canvas_draw_image_key(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        key: String,
+    ) -> PyResult<()> {
+        let (x, y, width, height) = (
+            self.to_pt(x),
+            self.to_pt(y),
+            self.to_pt(width),
+            self.to_pt(height),
+        );
+        let (image_id, image_name) = self
+            .images_registry
+            .get(&key)
+            .copied()
+            .ok_or_else(|| RenderError::ImageDecode(format!("no image registered under key '{}'", key)))?;
+
+        if let Some(form) = self.recording_form.as_mut() {
+            if !form.images_used.contains_key(&image_name) {
+                form.images_used.insert(image_name, image_id);
+            }
+        } else if !self.images_used_on_current_page.contains_key(&image_name) {
+            self.images_used_on_current_page
+                .insert(image_name, image_id);
+        }
+
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.draw_image(image_name, x, y, width, height);
+        Ok(())
+    }
+
+    /// Parse `pdf_bytes`, extract page `page_index` (0-based), and embed its
+    /// content and resources as a reusable Form XObject. Returns a handle to
+    /// pass to `canvas_draw_xobject`.
+    fn import_pdf_page(&mut self, pdf_bytes: Vec<u8>, page_index: usize) -> PyResult<String> {
+        let (doc, page) = pdf_import::extract_page(&pdf_bytes, page_index)?;
+
+        let remap = pdf_import::remap_resources(&doc, &page.resources, &mut || self.next_ref());
+        pdf_import::write_remapped_objects(&doc, &mut self.pdf, &remap);
+
+        let form_id = self.next_ref();
+        let (x0, y0, x1, y1) = page.media_box;
+        {
+            let mut form = self.pdf.form_xobject(form_id, &page.content);
+            form.bbox(pdf_writer::Rect::new(x0 as f32, y0 as f32, x1 as f32, y1 as f32));
+            pdf_import::write_resources_dict(
+                form.insert(Name(b"Resources")),
+                &page.resources,
+                &remap,
+            );
+        }
+
+        let xobject_name_str = format!("X{}", form_id.get());
+        let xobject_name = Name(Box::leak(xobject_name_str.into_boxed_str()).as_bytes());
+        let handle = format!("pdfpage_{}", form_id.get());
+        self.xobjects_registry
+            .insert(handle.clone(), (xobject_name, form_id, page.media_box));
+
+        Ok(handle)
+    }
+
+    /// Draw a Form XObject previously embedded with `import_pdf_page`, by
+    /// handle, uniformly scaled and positioned with its BBox's lower-left
+    /// corner at `(x, y)`.
+    fn canvas_draw_xobject(&mut self, x: f64, y: f64, scale: f64, handle: String) -> PyResult<()> {
+        let (x, y) = (self.to_pt(x), self.to_pt(y));
+        let &(xobject_name, xobject_ref, bbox) =
+            self.xobjects_registry.get(&handle).ok_or_else(|| {
+                RenderError::PdfImport(format!("no imported PDF page registered under handle '{}'", handle))
+            })?;
+
+        if let Some(form) = self.recording_form.as_mut() {
+            if !form.images_used.contains_key(&xobject_name) {
+                form.images_used.insert(xobject_name, xobject_ref);
+            }
+        } else if !self.images_used_on_current_page.contains_key(&xobject_name) {
+            self.images_used_on_current_page
+                .insert(xobject_name, xobject_ref);
+        }
+
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.draw_xobject(xobject_name, x, y, scale, bbox);
+        Ok(())
+    }
+
+    /// Begin capturing subsequent canvas operators into a reusable Form
+    /// XObject instead of the current page, for vector content (e.g. a logo
+    /// built from paths) that would otherwise be redrawn identically on every
+    /// page. `bbox` is `(x0, y0, x1, y1)` in the form's own coordinate space.
+    /// Finish with `end_form_xobject` and place the result with
+    /// `canvas_draw_form`. Forms can't be nested.
+    fn begin_form_xobject(&mut self, bbox: (f64, f64, f64, f64)) -> PyResult<()> {
+        if self.recording_form.is_some() {
             return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
+                "a Form XObject is already being recorded; call end_form_xobject first",
+            ));
+        }
+
+        let (x0, y0, x1, y1) = bbox;
+        let bbox = (self.to_pt(x0), self.to_pt(y0), self.to_pt(x1), self.to_pt(y1));
+
+        let form_id = self.next_ref();
+        let mut canvas = PdfCanvas::new();
+        canvas.set_debug_validate(self.debug_validate);
+        self.recording_form = Some(FormRecording {
+            form_id,
+            bbox,
+            canvas,
+            fonts_used: HashMap::new(),
+            images_used: HashMap::new(),
+            ext_graphics_states_used: HashMap::new(),
+            patterns_used: HashMap::new(),
+        });
+        Ok(())
+    }
+
+    /// Finish the capture started by `begin_form_xobject`, embed the recorded
+    /// operators and the resources they used as a Form XObject, and return a
+    /// handle to pass to `canvas_draw_form`.
+    fn end_form_xobject(&mut self) -> PyResult<String> {
+        let form = self.recording_form.take().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("no Form XObject is being recorded")
+        })?;
+
+        if self.debug_validate {
+            let errors = form.canvas.validation_errors();
+            if !errors.is_empty() {
+                return Err(RenderError::UnbalancedContentStream(errors.join("; ")).into());
+            }
+        }
+
+        let content_bytes = form.canvas.finish();
+        let (x0, y0, x1, y1) = form.bbox;
+        {
+            let mut xobject = self.pdf.form_xobject(form.form_id, &content_bytes);
+            xobject.bbox(pdf_writer::Rect::new(
+                x0 as f32, y0 as f32, x1 as f32, y1 as f32,
             ));
+            let mut resources = xobject.resources();
+            if !form.fonts_used.is_empty() {
+                let mut fonts = resources.fonts();
+                for (font_name, font_id) in &form.fonts_used {
+                    fonts.pair(*font_name, *font_id);
+                }
+            }
+            if !form.images_used.is_empty() {
+                let mut xobjects = resources.x_objects();
+                for (image_name, image_id) in &form.images_used {
+                    xobjects.pair(*image_name, *image_id);
+                }
+            }
+            if !form.ext_graphics_states_used.is_empty() {
+                let mut ext_states = resources.ext_g_states();
+                for (name, gs_ref) in &form.ext_graphics_states_used {
+                    ext_states.pair(*name, *gs_ref);
+                }
+            }
+            if !form.patterns_used.is_empty() {
+                let mut patterns = resources.patterns();
+                for (name, pattern_ref) in &form.patterns_used {
+                    patterns.pair(*name, *pattern_ref);
+                }
+            }
+        }
+
+        let xobject_name_str = format!("X{}", form.form_id.get());
+        let xobject_name = Name(Box::leak(xobject_name_str.into_boxed_str()).as_bytes());
+        let handle = format!("form_{}", form.form_id.get());
+        self.xobjects_registry
+            .insert(handle.clone(), (xobject_name, form.form_id, form.bbox));
+
+        Ok(handle)
+    }
+
+    /// Draw a Form XObject previously recorded with `begin_form_xobject`/
+    /// `end_form_xobject` (or imported with `import_pdf_page`), by handle,
+    /// with its BBox's lower-left corner placed at `(x, y)` and no additional
+    /// scale.
+    fn canvas_draw_form(&mut self, handle: String, x: f64, y: f64) -> PyResult<()> {
+        self.canvas_draw_xobject(x, y, 1.0, handle)
+    }
+
+    /// Translate coordinate system
+    fn canvas_translate(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let (x, y) = (self.to_pt(x), self.to_pt(y));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.translate(x, y);
+        Ok(())
+    }
+
+    /// Rotate coordinate system (radians)
+    fn canvas_rotate(&mut self, angle: f64) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        // Convert radians to degrees (canvas.rotate expects degrees)
+        let angle_degrees = angle.to_degrees();
+        canvas.rotate(angle_degrees);
+        Ok(())
+    }
+
+    /// Scale coordinate system
+    fn canvas_scale(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        canvas.scale(x, y);
+        Ok(())
+    }
+
+    /// Apply transformation matrix [a, b, c, d, e, f]
+    fn canvas_transform(&mut self, matrix: Vec<f64>) -> PyResult<()> {
+        if matrix.len() != 6 {
+            return Err(RenderError::InvalidMatrix(format!(
+                "expected 6 elements, got {}",
+                matrix.len()
+            ))
+            .into());
+        }
+        let determinant = matrix[0] * matrix[3] - matrix[1] * matrix[2];
+        if determinant.abs() < 1e-9 {
+            return Err(RenderError::InvalidMatrix(format!(
+                "matrix [{}, {}, {}, {}, {}, {}] is singular (determinant {})",
+                matrix[0], matrix[1], matrix[2], matrix[3], matrix[4], matrix[5], determinant
+            ))
+            .into());
+        }
+        // Only the translation part of the matrix is a length; a-d are
+        // unitless scale/rotation factors and stay as given.
+        let (e, f) = (self.to_pt(matrix[4]), self.to_pt(matrix[5]));
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
         };
         canvas.transform([
             matrix[0] as f32,
             matrix[1] as f32,
             matrix[2] as f32,
             matrix[3] as f32,
-            matrix[4] as f32,
-            matrix[5] as f32,
+            e as f32,
+            f as f32,
         ]);
         Ok(())
     }
 
     /// Set page size (for current page)
     fn set_page_size(&mut self, width: f64, height: f64) -> PyResult<()> {
+        let (width, height) = (self.to_pt(width), self.to_pt(height));
         // Update current page info if exists
         if let Some((page_id, _, _)) = self.current_page_info {
             self.current_page_info = Some((page_id, width, height));
@@ -764,6 +1589,30 @@ or install it system-wide so Unicode text can be rendered."
         Ok(())
     }
 
+    /// Report how many resources the current page references and how many
+    /// content-stream operators have been written to it, for diagnosing
+    /// unexpectedly large PDFs. Purely informational -- doesn't affect output.
+    ///
+    /// Returns:
+    ///     dict with "fonts", "images", "ext_graphics_states", and "operators"
+    fn current_page_stats(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let canvas = self
+            .current_page
+            .as_ref()
+            .map(|(_, _, c)| c)
+            .ok_or(RenderError::NoCurrentPage)?;
+
+        let result = PyDict::new(py);
+        result.set_item("fonts", self.fonts_used_on_current_page.len())?;
+        result.set_item("images", self.images_used_on_current_page.len())?;
+        result.set_item(
+            "ext_graphics_states",
+            self.ext_graphics_states_used_on_current_page.len(),
+        )?;
+        result.set_item("operators", canvas.operator_count())?;
+        Ok(result.into())
+    }
+
     /// Execute a batch of canvas commands in a single Python↔Rust call
     /// This dramatically reduces overhead compared to individual method calls
     /// Commands should be passed as a list of dicts, each with a "type" key and corresponding fields
@@ -802,7 +1651,11 @@ or install it system-wide so Unicode text can be rendered."
                 }
                 "SetDash" => {
                     let pattern: Vec<f64> = cmd_dict.get_item("pattern")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'pattern'"))?.extract()?;
-                    self.canvas_set_dash(pattern)?;
+                    let phase: f64 = match cmd_dict.get_item("phase")? {
+                        Some(v) => v.extract()?,
+                        None => 0.0,
+                    };
+                    self.canvas_set_dash(pattern, phase)?;
                 }
                 "SetFont" => {
                     let name: String = cmd_dict.get_item("name")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'name'"))?.extract()?;
@@ -813,6 +1666,15 @@ or install it system-wide so Unicode text can be rendered."
                     let opacity: f64 = cmd_dict.get_item("opacity")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'opacity'"))?.extract()?;
                     self.canvas_set_opacity(opacity)?;
                 }
+                "RegisterFallbackFonts" => {
+                    let font_keys: Vec<String> = cmd_dict.get_item("font_keys")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'font_keys'"))?.extract()?;
+                    self.register_fallback_fonts(font_keys)?;
+                }
+                "SetTextDecoration" => {
+                    let underline: bool = cmd_dict.get_item("underline")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'underline'"))?.extract()?;
+                    let strikethrough: bool = cmd_dict.get_item("strikethrough")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'strikethrough'"))?.extract()?;
+                    self.canvas_set_text_decoration(underline, strikethrough)?;
+                }
                 "Rect" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
                     let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
@@ -845,6 +1707,22 @@ or install it system-wide so Unicode text can be rendered."
                     let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
                     self.canvas_draw_string(x, y, text)?;
                 }
+                "DrawStringHighlighted" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
+                    let r: f64 = cmd_dict.get_item("r")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'r'"))?.extract()?;
+                    let g: f64 = cmd_dict.get_item("g")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'g'"))?.extract()?;
+                    let b: f64 = cmd_dict.get_item("b")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'b'"))?.extract()?;
+                    self.canvas_draw_string_highlighted(x, y, text, r, g, b)?;
+                }
+                "DrawStringJustified" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
+                    let target_width: f64 = cmd_dict.get_item("target_width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'target_width'"))?.extract()?;
+                    self.canvas_draw_string_justified(x, y, text, target_width)?;
+                }
                 "DrawImage" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
                     let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
@@ -871,6 +1749,68 @@ or install it system-wide so Unicode text can be rendered."
                     let matrix: Vec<f64> = cmd_dict.get_item("matrix")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'matrix'"))?.extract()?;
                     self.canvas_transform(matrix)?;
                 }
+                "SetFillColorCMYK" => {
+                    let c: f64 = cmd_dict.get_item("c")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'c'"))?.extract()?;
+                    let m: f64 = cmd_dict.get_item("m")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'm'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let k: f64 = cmd_dict.get_item("k")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'k'"))?.extract()?;
+                    self.canvas_set_fill_color_cmyk(c, m, y, k)?;
+                }
+                "SetLinearGradient" => {
+                    let x0: f64 = cmd_dict.get_item("x0")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x0'"))?.extract()?;
+                    let y0: f64 = cmd_dict.get_item("y0")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y0'"))?.extract()?;
+                    let r0: f64 = cmd_dict.get_item("r0")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'r0'"))?.extract()?;
+                    let g0: f64 = cmd_dict.get_item("g0")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'g0'"))?.extract()?;
+                    let b0: f64 = cmd_dict.get_item("b0")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'b0'"))?.extract()?;
+                    let x1: f64 = cmd_dict.get_item("x1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x1'"))?.extract()?;
+                    let y1: f64 = cmd_dict.get_item("y1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y1'"))?.extract()?;
+                    let r1: f64 = cmd_dict.get_item("r1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'r1'"))?.extract()?;
+                    let g1: f64 = cmd_dict.get_item("g1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'g1'"))?.extract()?;
+                    let b1: f64 = cmd_dict.get_item("b1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'b1'"))?.extract()?;
+                    self.canvas_set_linear_gradient(x0, y0, r0, g0, b0, x1, y1, r1, g1, b1)?;
+                }
+                "ClipRect" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let width: f64 = cmd_dict.get_item("width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'width'"))?.extract()?;
+                    let height: f64 = cmd_dict.get_item("height")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'height'"))?.extract()?;
+                    self.canvas_clip_rect(x, y, width, height)?;
+                }
+                "SetBlendMode" => {
+                    let mode: String = cmd_dict.get_item("mode")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'mode'"))?.extract()?;
+                    self.canvas_set_blend_mode(mode)?;
+                }
+                "PathMoveTo" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    self.canvas_path_move_to(x, y)?;
+                }
+                "PathLineTo" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    self.canvas_path_line_to(x, y)?;
+                }
+                "PathCurveTo" => {
+                    let x1: f64 = cmd_dict.get_item("x1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x1'"))?.extract()?;
+                    let y1: f64 = cmd_dict.get_item("y1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y1'"))?.extract()?;
+                    let x2: f64 = cmd_dict.get_item("x2")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x2'"))?.extract()?;
+                    let y2: f64 = cmd_dict.get_item("y2")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y2'"))?.extract()?;
+                    let x3: f64 = cmd_dict.get_item("x3")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x3'"))?.extract()?;
+                    let y3: f64 = cmd_dict.get_item("y3")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y3'"))?.extract()?;
+                    self.canvas_path_curve_to(x1, y1, x2, y2, x3, y3)?;
+                }
+                "PathClose" => {
+                    self.canvas_path_close()?;
+                }
+                "PathPaint" => {
+                    let fill: bool = cmd_dict.get_item("fill")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'fill'"))?.extract()?;
+                    let stroke: bool = cmd_dict.get_item("stroke")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'stroke'"))?.extract()?;
+                    let even_odd: bool = match cmd_dict.get_item("even_odd")? {
+                        Some(v) => v.extract()?,
+                        None => false,
+                    };
+                    self.canvas_path_paint(fill, stroke, even_odd)?;
+                }
                 _ => {
                     return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                         format!("Unknown command type: {}", cmd_type)
@@ -880,9 +1820,312 @@ or install it system-wide so Unicode text can be rendered."
         }
         Ok(())
     }
+
+    /// Like `canvas_run_batch`, but takes `[(op_code, args), ...]` instead of
+    /// `[{"type": ..., ...}, ...]`. `op_code` is one of the module-level
+    /// `OP_*` integer constants and `args` is a plain positional tuple, so
+    /// this skips the `PyDict` downcast and ~6 string-keyed lookups per
+    /// command that make `canvas_run_batch` the bottleneck on 100k-command
+    /// documents -- op-code dispatch is a single `match` on an integer and
+    /// argument extraction is a single positional tuple unpack.
+    fn canvas_run_batch_fast(&mut self, ops: &PyAny) -> PyResult<()> {
+        let ops_list: &PyList = ops.downcast()?;
+        for op_obj in ops_list.iter() {
+            let op_tuple: &pyo3::types::PyTuple = op_obj.downcast()?;
+            let op_code: i32 = op_tuple.get_item(0)?.extract()?;
+            let args: &pyo3::types::PyTuple = op_tuple.get_item(1)?.downcast()?;
+
+            match op_code {
+                OP_SAVE_STATE => self.canvas_save_state()?,
+                OP_RESTORE_STATE => self.canvas_restore_state()?,
+                OP_SET_FILL_COLOR => {
+                    let (r, g, b): (f64, f64, f64) = args.extract()?;
+                    self.canvas_set_fill_color(r, g, b)?;
+                }
+                OP_SET_STROKE_COLOR => {
+                    let (r, g, b): (f64, f64, f64) = args.extract()?;
+                    self.canvas_set_stroke_color(r, g, b)?;
+                }
+                OP_SET_LINE_WIDTH => {
+                    let (width,): (f64,) = args.extract()?;
+                    self.canvas_set_line_width(width)?;
+                }
+                OP_SET_DASH => {
+                    let (pattern, phase): (Vec<f64>, f64) = args.extract()?;
+                    self.canvas_set_dash(pattern, phase)?;
+                }
+                OP_SET_FONT => {
+                    let (name, size): (String, f64) = args.extract()?;
+                    self.canvas_set_font(name, size)?;
+                }
+                OP_SET_OPACITY => {
+                    let (opacity,): (f64,) = args.extract()?;
+                    self.canvas_set_opacity(opacity)?;
+                }
+                OP_REGISTER_FALLBACK_FONTS => {
+                    let (font_keys,): (Vec<String>,) = args.extract()?;
+                    self.register_fallback_fonts(font_keys)?;
+                }
+                OP_SET_TEXT_DECORATION => {
+                    let (underline, strikethrough): (bool, bool) = args.extract()?;
+                    self.canvas_set_text_decoration(underline, strikethrough)?;
+                }
+                OP_RECT => {
+                    let (x, y, width, height, fill, stroke): (f64, f64, f64, f64, bool, bool) =
+                        args.extract()?;
+                    self.canvas_rect(x, y, width, height, fill, stroke)?;
+                }
+                OP_ROUND_RECT => {
+                    let (x, y, width, height, radius, fill, stroke): (
+                        f64, f64, f64, f64, f64, bool, bool,
+                    ) = args.extract()?;
+                    self.canvas_round_rect(x, y, width, height, radius, fill, stroke)?;
+                }
+                OP_LINE => {
+                    let (x1, y1, x2, y2): (f64, f64, f64, f64) = args.extract()?;
+                    self.canvas_line(x1, y1, x2, y2)?;
+                }
+                OP_DRAW_STRING => {
+                    let (x, y, text): (f64, f64, String) = args.extract()?;
+                    self.canvas_draw_string(x, y, text)?;
+                }
+                OP_DRAW_STRING_HIGHLIGHTED => {
+                    let (x, y, text, r, g, b): (f64, f64, String, f64, f64, f64) =
+                        args.extract()?;
+                    self.canvas_draw_string_highlighted(x, y, text, r, g, b)?;
+                }
+                OP_DRAW_STRING_JUSTIFIED => {
+                    let (x, y, text, target_width): (f64, f64, String, f64) = args.extract()?;
+                    self.canvas_draw_string_justified(x, y, text, target_width)?;
+                }
+                OP_DRAW_IMAGE => {
+                    let (x, y, width, height, image_data): (f64, f64, f64, f64, Vec<u8>) =
+                        args.extract()?;
+                    self.canvas_draw_image(x, y, width, height, image_data)?;
+                }
+                OP_TRANSLATE => {
+                    let (x, y): (f64, f64) = args.extract()?;
+                    self.canvas_translate(x, y)?;
+                }
+                OP_ROTATE => {
+                    let (angle,): (f64,) = args.extract()?;
+                    self.canvas_rotate(angle)?;
+                }
+                OP_SCALE => {
+                    let (x, y): (f64, f64) = args.extract()?;
+                    self.canvas_scale(x, y)?;
+                }
+                OP_TRANSFORM => {
+                    let (matrix,): (Vec<f64>,) = args.extract()?;
+                    self.canvas_transform(matrix)?;
+                }
+                OP_SET_FILL_COLOR_CMYK => {
+                    let (c, m, y, k): (f64, f64, f64, f64) = args.extract()?;
+                    self.canvas_set_fill_color_cmyk(c, m, y, k)?;
+                }
+                OP_SET_LINEAR_GRADIENT => {
+                    let (x0, y0, r0, g0, b0, x1, y1, r1, g1, b1): (
+                        f64, f64, f64, f64, f64, f64, f64, f64, f64, f64,
+                    ) = args.extract()?;
+                    self.canvas_set_linear_gradient(x0, y0, r0, g0, b0, x1, y1, r1, g1, b1)?;
+                }
+                OP_CLIP_RECT => {
+                    let (x, y, width, height): (f64, f64, f64, f64) = args.extract()?;
+                    self.canvas_clip_rect(x, y, width, height)?;
+                }
+                OP_SET_BLEND_MODE => {
+                    let (mode,): (String,) = args.extract()?;
+                    self.canvas_set_blend_mode(mode)?;
+                }
+                OP_PATH_MOVE_TO => {
+                    let (x, y): (f64, f64) = args.extract()?;
+                    self.canvas_path_move_to(x, y)?;
+                }
+                OP_PATH_LINE_TO => {
+                    let (x, y): (f64, f64) = args.extract()?;
+                    self.canvas_path_line_to(x, y)?;
+                }
+                OP_PATH_CURVE_TO => {
+                    let (x1, y1, x2, y2, x3, y3): (f64, f64, f64, f64, f64, f64) =
+                        args.extract()?;
+                    self.canvas_path_curve_to(x1, y1, x2, y2, x3, y3)?;
+                }
+                OP_PATH_CLOSE => self.canvas_path_close()?,
+                OP_PATH_PAINT => {
+                    let (fill, stroke, even_odd): (bool, bool, bool) = args.extract()?;
+                    self.canvas_path_paint(fill, stroke, even_odd)?;
+                }
+                other => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Unknown op code: {}",
+                        other
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PdfCanvasRenderer {
+    /// Convert a coordinate or dimension from `set_units`'s unit to points.
+    fn to_pt(&self, value: f64) -> f64 {
+        value * self.unit_scale
+    }
+
+    /// Finish the current page, the page tree, and `self.pdf`, returning the
+    /// serialized PDF bytes. Shared by `save` and `save_to_writer` -- pure
+    /// Rust with no `PyObject`/`PyAny` touched, so callers can run it inside
+    /// `Python::allow_threads`.
+    fn finalize_pdf(&mut self) -> PyResult<Vec<u8>> {
+        // Save current page content and finalize page
+        if let Some((_page_id, content_id, canvas)) = self.current_page.take() {
+            if self.debug_validate {
+                let errors = canvas.validation_errors();
+                if !errors.is_empty() {
+                    return Err(RenderError::UnbalancedContentStream(errors.join("; ")).into());
+                }
+            }
+            let content_bytes = canvas.finish();
+            self.pdf.stream(content_id, &content_bytes);
+
+            // Create and finish the page
+            if let Some((page_info_id, page_width, page_height)) = self.current_page_info.take() {
+                let mut page = self.pdf.page(page_info_id);
+                page.media_box(pdf_writer::Rect::new(
+                    0.0,
+                    0.0,
+                    page_width as f32,
+                    page_height as f32,
+                ));
+                if let Some(page_tree_id) = self.page_tree_id {
+                    page.parent(page_tree_id);
+                }
+                page.contents(content_id);
+
+                // Add resources (fonts, images, ext graphics states)
+                {
+                    let mut resources = page.resources();
+                    if !self.fonts_used_on_current_page.is_empty() {
+                        let mut fonts = resources.fonts();
+                        for (font_name, font_id) in &self.fonts_used_on_current_page {
+                            fonts.pair(*font_name, *font_id);
+                        }
+                    }
+                    if !self.images_used_on_current_page.is_empty() {
+                        let mut xobject_dict = resources.x_objects();
+                        for (image_name, image_id) in &self.images_used_on_current_page {
+                            xobject_dict.pair(*image_name, *image_id);
+                        }
+                    }
+                    if !self.ext_graphics_states_used_on_current_page.is_empty() {
+                        let mut ext_states = resources.ext_g_states();
+                        for (name, gs_ref) in &self.ext_graphics_states_used_on_current_page {
+                            ext_states.pair(*name, *gs_ref);
+                        }
+                    }
+                    if !self.patterns_used_on_current_page.is_empty() {
+                        let mut patterns = resources.patterns();
+                        for (name, pattern_ref) in &self.patterns_used_on_current_page {
+                            patterns.pair(*name, *pattern_ref);
+                        }
+                    }
+                }
+
+                page.finish();
+            }
+        }
+
+        // Update page tree
+        if let Some(page_tree_id) = self.page_tree_id {
+            let mut page_tree = self.pdf.pages(page_tree_id);
+            page_tree.kids(self.pages.iter().cloned());
+            page_tree.count(self.pages.len() as i32);
+        }
+
+        // Finish PDF and get bytes
+        let pdf = std::mem::replace(&mut self.pdf, Pdf::new());
+        Ok(pdf.finish())
+    }
+
+    /// Allocate the next indirect object reference. All PDF objects (pages,
+    /// fonts, images, ExtGStates) share this single counter so refs never
+    /// collide, regardless of how many of each kind a document ends up with.
+    fn next_ref(&mut self) -> Ref {
+        let r = Ref::new(self.next_ref_id);
+        self.next_ref_id += 1;
+        r
+    }
+
+    /// Resolve a font name to its registered PDF resource, via the same
+    /// name-variation lookup `canvas_set_font` already used inline, falling
+    /// back to DejaVu Sans and caching the result for next time. Shared by
+    /// `canvas_set_font` and `register_fallback_fonts`.
+    fn resolve_font(&mut self, name: &str) -> Result<(Name<'static>, Ref), RenderError> {
+        if let Some(&(name_ref, id_ref)) = self.font_registry.get(name) {
+            return Ok((name_ref, id_ref));
+        }
+
+        let variations = vec![
+            name.replace("-", " "),
+            name.replace(" ", "-"),
+            name.replace("Bold", "-Bold"),
+            name.replace("Italic", "-Italic"),
+            name.replace("Oblique", "-Oblique"),
+        ];
+
+        let mut found_font = None;
+        for variant in variations {
+            if let Some(&font) = self.font_registry.get(&variant) {
+                found_font = Some(font);
+                break;
+            }
+        }
+
+        let default_font = match found_font {
+            Some(font) => font,
+            None => self
+                .font_registry
+                .get("DejaVu Sans")
+                .or_else(|| self.font_registry.get("DejaVuSans"))
+                .copied()
+                .ok_or_else(|| RenderError::FontNotFound(name.to_string()))?,
+        };
+
+        self.font_registry.insert(name.to_string(), default_font);
+        Ok(default_font)
+    }
+
+    /// Draw a single decoration line (underline or strikethrough) below/through a
+    /// just-drawn string, using the current fill color so it matches the text.
+    /// `position`/`thickness` are in 1000-unit em space, scaled here by `font_size`.
+    fn draw_decoration_line(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        font_size: f64,
+        position: f32,
+        thickness: f32,
+    ) -> PyResult<()> {
+        let canvas = if let Some(form) = self.recording_form.as_mut() {
+            &mut form.canvas
+        } else if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(RenderError::NoCurrentPage.into());
+        };
+        let line_width = (thickness as f64 * font_size / 1000.0).max(0.4);
+        let line_y = y + position as f64 * font_size / 1000.0;
+
+        canvas.save_state();
+        canvas.set_stroke_color(canvas.get_fill_color());
+        canvas.set_line_width(line_width);
+        canvas.line(x, line_y, x + width, line_y);
+        canvas.restore_state();
+        Ok(())
+    }
+
     fn get_or_create_ext_graphics_state(
         &mut self,
         alpha_key: u32,
@@ -892,8 +2135,7 @@ impl PdfCanvasRenderer {
             return (name, ref_id);
         }
 
-        let gs_ref = Ref::new(self.next_ref_id);
-        self.next_ref_id += 1;
+        let gs_ref = self.next_ref();
 
         let name_str = format!("GS{}", gs_ref.get());
         let name_boxed = name_str.into_boxed_str();
@@ -908,11 +2150,143 @@ impl PdfCanvasRenderer {
         self.ext_graphics_states.insert(alpha_key, (name, gs_ref));
         (name, gs_ref)
     }
+
+    /// Same idea as `get_or_create_ext_graphics_state`, but for a blend-mode
+    /// ExtGState, cached by mode name instead of alpha.
+    fn get_or_create_blend_mode_state(
+        &mut self,
+        mode: pdf_writer::types::BlendMode,
+        mode_name: &str,
+    ) -> (Name<'static>, Ref) {
+        if let Some(&(name, ref_id)) = self.blend_mode_states.get(mode_name) {
+            return (name, ref_id);
+        }
+
+        let gs_ref = self.next_ref();
+
+        let name_str = format!("GS{}", gs_ref.get());
+        let name_boxed = name_str.into_boxed_str();
+        let name_static = Box::leak(name_boxed);
+        let name = Name(name_static.as_bytes());
+
+        {
+            let mut ext = self.pdf.ext_graphics(gs_ref);
+            ext.blend_mode(mode);
+        }
+
+        self.blend_mode_states
+            .insert(mode_name.to_string(), (name, gs_ref));
+        (name, gs_ref)
+    }
 }
 
+// Op codes for `canvas_run_batch_fast`, exposed as `rust_pdf_canvas.OP_*`.
+// Values are an implementation detail (assignment order, not the command
+// names) -- callers should always go through the module constants rather
+// than hardcoding integers.
+const OP_SAVE_STATE: i32 = 0;
+const OP_RESTORE_STATE: i32 = 1;
+const OP_SET_FILL_COLOR: i32 = 2;
+const OP_SET_STROKE_COLOR: i32 = 3;
+const OP_SET_LINE_WIDTH: i32 = 4;
+const OP_SET_DASH: i32 = 5;
+const OP_SET_FONT: i32 = 6;
+const OP_SET_OPACITY: i32 = 7;
+const OP_REGISTER_FALLBACK_FONTS: i32 = 8;
+const OP_SET_TEXT_DECORATION: i32 = 9;
+const OP_RECT: i32 = 10;
+const OP_ROUND_RECT: i32 = 11;
+const OP_LINE: i32 = 12;
+const OP_DRAW_STRING: i32 = 13;
+const OP_DRAW_STRING_HIGHLIGHTED: i32 = 14;
+const OP_DRAW_STRING_JUSTIFIED: i32 = 15;
+const OP_DRAW_IMAGE: i32 = 16;
+const OP_TRANSLATE: i32 = 17;
+const OP_ROTATE: i32 = 18;
+const OP_SCALE: i32 = 19;
+const OP_TRANSFORM: i32 = 20;
+const OP_SET_FILL_COLOR_CMYK: i32 = 21;
+const OP_SET_LINEAR_GRADIENT: i32 = 22;
+const OP_CLIP_RECT: i32 = 23;
+const OP_SET_BLEND_MODE: i32 = 24;
+const OP_PATH_MOVE_TO: i32 = 25;
+const OP_PATH_LINE_TO: i32 = 26;
+const OP_PATH_CURVE_TO: i32 = 27;
+const OP_PATH_CLOSE: i32 = 28;
+const OP_PATH_PAINT: i32 = 29;
+
 /// Python module for PDF canvas rendering
 #[pymodule]
-fn rust_pdf_canvas(_py: Python, m: &PyModule) -> PyResult<()> {
+fn rust_pdf_canvas(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PdfCanvasRenderer>()?;
+    m.add("NoCurrentPageError", py.get_type::<NoCurrentPageError>())?;
+    m.add("FontNotFoundError", py.get_type::<FontNotFoundError>())?;
+    m.add("InvalidMatrixError", py.get_type::<InvalidMatrixError>())?;
+    m.add("ImageDecodeError", py.get_type::<ImageDecodeError>())?;
+    m.add(
+        "UnbalancedContentStreamError",
+        py.get_type::<UnbalancedContentStreamError>(),
+    )?;
+    m.add("PdfImportError", py.get_type::<PdfImportError>())?;
+    m.add("OP_SAVE_STATE", OP_SAVE_STATE)?;
+    m.add("OP_RESTORE_STATE", OP_RESTORE_STATE)?;
+    m.add("OP_SET_FILL_COLOR", OP_SET_FILL_COLOR)?;
+    m.add("OP_SET_STROKE_COLOR", OP_SET_STROKE_COLOR)?;
+    m.add("OP_SET_LINE_WIDTH", OP_SET_LINE_WIDTH)?;
+    m.add("OP_SET_DASH", OP_SET_DASH)?;
+    m.add("OP_SET_FONT", OP_SET_FONT)?;
+    m.add("OP_SET_OPACITY", OP_SET_OPACITY)?;
+    m.add("OP_REGISTER_FALLBACK_FONTS", OP_REGISTER_FALLBACK_FONTS)?;
+    m.add("OP_SET_TEXT_DECORATION", OP_SET_TEXT_DECORATION)?;
+    m.add("OP_RECT", OP_RECT)?;
+    m.add("OP_ROUND_RECT", OP_ROUND_RECT)?;
+    m.add("OP_LINE", OP_LINE)?;
+    m.add("OP_DRAW_STRING", OP_DRAW_STRING)?;
+    m.add("OP_DRAW_STRING_HIGHLIGHTED", OP_DRAW_STRING_HIGHLIGHTED)?;
+    m.add("OP_DRAW_STRING_JUSTIFIED", OP_DRAW_STRING_JUSTIFIED)?;
+    m.add("OP_DRAW_IMAGE", OP_DRAW_IMAGE)?;
+    m.add("OP_TRANSLATE", OP_TRANSLATE)?;
+    m.add("OP_ROTATE", OP_ROTATE)?;
+    m.add("OP_SCALE", OP_SCALE)?;
+    m.add("OP_TRANSFORM", OP_TRANSFORM)?;
+    m.add("OP_SET_FILL_COLOR_CMYK", OP_SET_FILL_COLOR_CMYK)?;
+    m.add("OP_SET_LINEAR_GRADIENT", OP_SET_LINEAR_GRADIENT)?;
+    m.add("OP_CLIP_RECT", OP_CLIP_RECT)?;
+    m.add("OP_SET_BLEND_MODE", OP_SET_BLEND_MODE)?;
+    m.add("OP_PATH_MOVE_TO", OP_PATH_MOVE_TO)?;
+    m.add("OP_PATH_LINE_TO", OP_PATH_LINE_TO)?;
+    m.add("OP_PATH_CURVE_TO", OP_PATH_CURVE_TO)?;
+    m.add("OP_PATH_CLOSE", OP_PATH_CLOSE)?;
+    m.add("OP_PATH_PAINT", OP_PATH_PAINT)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regression test for synth-335: `PdfCanvasRenderer::new()` used to
+    /// `panic!()` outright when DejaVu Sans couldn't be found anywhere, which
+    /// would abort the whole Python interpreter instead of raising a
+    /// catchable exception. This sandbox has no system-installed DejaVu Sans
+    /// and this crate bundles no `assets/fonts` fallback, so font lookup is
+    /// expected to come up empty here -- exactly the condition that used to
+    /// panic. Calls the real compiled constructor (not a mock) and asserts
+    /// it returns an `Err` instead of unwinding.
+    #[test]
+    fn missing_default_font_returns_error_instead_of_panicking() {
+        let outcome = std::panic::catch_unwind(|| {
+            PdfCanvasRenderer::new("/tmp/synth_335_test_output.pdf".to_string(), 612.0, 792.0)
+        });
+        let result = match outcome {
+            Ok(result) => result,
+            Err(_) => panic!("PdfCanvasRenderer::new() panicked instead of returning a PyResult"),
+        };
+        if font_utils::find_dejavu_sans().is_none() {
+            assert!(
+                result.is_err(),
+                "expected an Err when DejaVu Sans isn't available anywhere, got Ok"
+            );
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Import a page from an existing PDF as a reusable Form XObject.
+//!
+//! `pdf-writer` only writes PDFs, so pulling a page's content stream and
+//! resources out of a customer-supplied template needs a reader; `lopdf`
+//! fills that role here. Extracted objects are re-serialized into the output
+//! document with `pdf-writer`, like everything else in this crate, so the two
+//! libraries never touch the same document at once -- we only ever read from
+//! `lopdf::Document` and write into `pdf_writer::Pdf`.
+
+use std::collections::HashMap;
+
+use lopdf::{Dictionary as LoDictionary, Document, Object as LoObject, ObjectId};
+use pdf_writer::{Dict, Name, Null, Obj, Pdf, Ref, Str};
+
+use crate::error::RenderError;
+
+/// A page's content stream, resource dictionary, and MediaBox pulled out of
+/// a source PDF, ready to be embedded as a Form XObject.
+pub struct ImportedPage {
+    pub content: Vec<u8>,
+    pub resources: LoDictionary,
+    pub media_box: (f64, f64, f64, f64),
+}
+
+/// Parse `pdf_bytes` and pull out page `page_index` (0-based). The parsed
+/// `Document` is returned alongside the page so the caller can pass it to
+/// `remap_resources`/`write_remapped_objects` to copy the page's resources
+/// (fonts, images, ...) into the output PDF.
+pub fn extract_page(
+    pdf_bytes: &[u8],
+    page_index: usize,
+) -> Result<(Document, ImportedPage), RenderError> {
+    let doc = Document::load_mem(pdf_bytes)
+        .map_err(|e| RenderError::PdfImport(format!("failed to parse PDF: {}", e)))?;
+
+    let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    let page_id = *page_ids.get(page_index).ok_or_else(|| {
+        RenderError::PdfImport(format!(
+            "page index {} out of range ({} page(s) in source PDF)",
+            page_index,
+            page_ids.len()
+        ))
+    })?;
+
+    let media_box = media_box(&doc, page_id)?;
+
+    let content = doc.get_page_content(page_id);
+
+    let (primary, extra_ids) = doc
+        .get_page_resources(page_id)
+        .map_err(|e| RenderError::PdfImport(format!("failed to read page resources: {}", e)))?;
+    let mut resources = primary.cloned().unwrap_or_default();
+    for extra_id in extra_ids {
+        if let Ok(extra) = doc.get_dictionary(extra_id) {
+            for (key, value) in extra.iter() {
+                if !resources.has(key) {
+                    resources.set(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+
+    Ok((doc, ImportedPage { content, resources, media_box }))
+}
+
+/// Walk the page's `/Parent` chain (as lopdf leaves `MediaBox` inherited
+/// rather than copied onto every page) to find the effective MediaBox.
+fn media_box(doc: &Document, page_id: ObjectId) -> Result<(f64, f64, f64, f64), RenderError> {
+    let mut current = Some(page_id);
+    while let Some(id) = current {
+        let dict = doc
+            .get_dictionary(id)
+            .map_err(|e| RenderError::PdfImport(format!("malformed page tree: {}", e)))?;
+        if let Ok(array) = dict.get(b"MediaBox").and_then(LoObject::as_array) {
+            if let [x0, y0, x1, y1] = array.as_slice() {
+                if let (Some(x0), Some(y0), Some(x1), Some(y1)) =
+                    (as_f64(x0), as_f64(y0), as_f64(x1), as_f64(y1))
+                {
+                    return Ok((x0, y0, x1, y1));
+                }
+            }
+        }
+        current = dict.get(b"Parent").and_then(LoObject::as_reference).ok();
+    }
+    // No MediaBox anywhere in the chain: fall back to US Letter, matching
+    // the PDF spec's own default when a viewer can't otherwise determine it.
+    Ok((0.0, 0.0, 612.0, 792.0))
+}
+
+fn as_f64(obj: &LoObject) -> Option<f64> {
+    match obj {
+        LoObject::Integer(i) => Some(*i as f64),
+        LoObject::Real(f) => Some(*f as f64),
+        _ => None,
+    }
+}
+
+/// Collect every object id referenced (directly or transitively) from
+/// `resources`, allocating a fresh destination `Ref` for each via `next_ref`.
+pub fn remap_resources(
+    doc: &Document,
+    resources: &LoDictionary,
+    next_ref: &mut impl FnMut() -> Ref,
+) -> HashMap<ObjectId, Ref> {
+    let mut remap: HashMap<ObjectId, Ref> = HashMap::new();
+    let mut queue: Vec<ObjectId> = Vec::new();
+
+    let mut seed = Vec::new();
+    for (_, value) in resources.iter() {
+        collect_references(value, &mut seed);
+    }
+    for id in seed {
+        remap.entry(id).or_insert_with(|| {
+            queue.push(id);
+            next_ref()
+        });
+    }
+
+    let mut i = 0;
+    while i < queue.len() {
+        let id = queue[i];
+        i += 1;
+        let Ok(obj) = doc.get_object(id) else { continue };
+        let mut found = Vec::new();
+        collect_references(obj, &mut found);
+        for rid in found {
+            remap.entry(rid).or_insert_with(|| {
+                queue.push(rid);
+                next_ref()
+            });
+        }
+    }
+
+    remap
+}
+
+fn collect_references(obj: &LoObject, out: &mut Vec<ObjectId>) {
+    match obj {
+        LoObject::Reference(id) => out.push(*id),
+        LoObject::Array(items) => items.iter().for_each(|item| collect_references(item, out)),
+        LoObject::Dictionary(dict) => {
+            dict.iter().for_each(|(_, value)| collect_references(value, out))
+        }
+        LoObject::Stream(stream) => {
+            stream.dict.iter().for_each(|(_, value)| collect_references(value, out))
+        }
+        _ => {}
+    }
+}
+
+/// Write every object collected by `remap_resources` into `dst` as its own
+/// indirect object, translating nested `/Foo N 0 R` references through the
+/// same map.
+pub fn write_remapped_objects(doc: &Document, dst: &mut Pdf, remap: &HashMap<ObjectId, Ref>) {
+    for (&id, &dst_ref) in remap {
+        let Ok(obj) = doc.get_object(id) else { continue };
+        match obj {
+            LoObject::Stream(stream) => {
+                let mut out = dst.stream(dst_ref, &stream.content);
+                for (key, value) in stream.dict.iter() {
+                    // `Length` is recomputed by `Pdf::stream` from the bytes we
+                    // just passed it, which match the source stream exactly
+                    // since we copy it undecoded.
+                    if key == b"Length" {
+                        continue;
+                    }
+                    write_value(out.insert(Name(key)), value, remap);
+                }
+            }
+            other => write_value(dst.indirect(dst_ref), other, remap),
+        }
+    }
+}
+
+/// Write `resources` (translating any references via `remap`) as the value
+/// of `sink`, e.g. a Form XObject's `/Resources` entry.
+pub fn write_resources_dict(sink: Obj, resources: &LoDictionary, remap: &HashMap<ObjectId, Ref>) {
+    write_value(sink, &LoObject::Dictionary(resources.clone()), remap);
+}
+
+fn write_value(sink: Obj, value: &LoObject, remap: &HashMap<ObjectId, Ref>) {
+    match value {
+        LoObject::Null => sink.primitive(Null),
+        LoObject::Boolean(b) => sink.primitive(*b),
+        LoObject::Integer(i) => sink.primitive(*i as i32),
+        LoObject::Real(f) => sink.primitive(*f),
+        LoObject::Name(name) => sink.primitive(Name(name)),
+        LoObject::String(bytes, _) => sink.primitive(Str(bytes)),
+        LoObject::Reference(id) => match remap.get(id) {
+            Some(dst_ref) => sink.primitive(*dst_ref),
+            // Referenced object wasn't reachable from the resources we
+            // walked (shouldn't happen since remap is built transitively);
+            // drop it to `null` rather than emit a dangling reference.
+            None => sink.primitive(Null),
+        },
+        LoObject::Array(items) => {
+            let mut array = sink.array();
+            for item in items {
+                write_value(array.push(), item, remap);
+            }
+        }
+        LoObject::Dictionary(dict) => {
+            let mut out: Dict = sink.dict();
+            for (key, value) in dict.iter() {
+                write_value(out.insert(Name(key)), value, remap);
+            }
+        }
+        // Streams never appear nested inside another object's value tree in
+        // a well-formed PDF -- only as values in `write_remapped_objects`.
+        LoObject::Stream(_) => {}
+    }
+}
@@ -2,20 +2,75 @@
 //!
 //! Provides a ReportLab-like interface for canvas operations
 
+use pdf_writer::types::{ColorSpaceOperand, TextRenderingMode};
 use pdf_writer::{Content, Name, Str};
 use std::collections::HashMap;
 
+use crate::font_utils::LigatureTable;
 use crate::types::{Color, Rect};
 
 /// Map Unicode code point to CID (Character ID) for Type0 fonts
 pub type CidMap = HashMap<u32, u16>;
 
+/// Greedily replace runs of CIDs in `cids` with GSUB ligature CIDs from
+/// `ligatures`, matching the longest candidate sequence first at each
+/// position (candidates are already sorted longest-first).
+fn substitute_ligatures(cids: &[u16], ligatures: &LigatureTable) -> Vec<u16> {
+    let mut out = Vec::with_capacity(cids.len());
+    let mut i = 0;
+    while i < cids.len() {
+        let matched = ligatures.get(&cids[i]).and_then(|candidates| {
+            candidates
+                .iter()
+                .find(|(sequence, _)| cids[i..].starts_with(sequence))
+        });
+        match matched {
+            Some((sequence, ligature_cid)) => {
+                out.push(*ligature_cid);
+                i += sequence.len();
+            }
+            None => {
+                out.push(cids[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Extra stroke width, as a fraction of the font size, applied to glyphs when
+/// synthesizing bold for a family that has no real bold variant.
+const SYNTHETIC_BOLD_STROKE_RATIO: f32 = 0.018;
+
+/// Horizontal shear applied to the text matrix when synthesizing italic for a
+/// family that has no real italic/oblique variant (a ~12 degree slant).
+const SYNTHETIC_ITALIC_SHEAR: f32 = 0.21;
+
+/// Largest payload `PdfCanvas::draw_inline_image` will emit as a `BI`/`ID`/`EI`
+/// inline image. The PDF spec recommends inline images stay small (they're
+/// meant for tiny stencil masks and icons); anything bigger belongs in a
+/// shared XObject via `draw_image`, which also gets deduped across uses.
+const MAX_INLINE_IMAGE_BYTES: usize = 4096;
+
+/// Where a stroked rectangle's border sits relative to the path PDF actually
+/// strokes (which always centers the line on the path). `Inside`/`Outside`
+/// are emulated by stroking a path inset/outset by half the line width
+/// instead of the path's own bounds, so e.g. a 2pt border on a 100pt box
+/// drawn `Inside` still occupies exactly 100pt, not 101pt.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StrokeAlign {
+    Center,
+    Inside,
+    Outside,
+}
+
 /// Canvas state for graphics operations
 #[derive(Clone)]
 struct CanvasState {
     fill_color: Color,
     stroke_color: Color,
     line_width: f64,
+    stroke_align: StrokeAlign,
     font_name: Name<'static>,
     font_size: f64,
     dash_pattern: Option<(Vec<f64>, f64)>,
@@ -27,6 +82,7 @@ impl Default for CanvasState {
             fill_color: Color::black(),
             stroke_color: Color::black(),
             line_width: 1.0,
+            stroke_align: StrokeAlign::Center,
             font_name: Name(b"F1"), // Default font (Helvetica)
             font_size: 12.0,
             dash_pattern: None,
@@ -39,9 +95,18 @@ pub struct PdfCanvas {
     content: Content,
     state: CanvasState,
     state_stack: Vec<CanvasState>,
-    // Cache for CID bytes: (code_point) -> [u8; 2]
-    cid_cache: HashMap<u32, [u8; 2]>,
+    // Cache for CID lookups: code_point -> CID
+    cid_cache: HashMap<u32, u16>,
     cached_font: Option<Name<'static>>,
+    // Whether a path under construction has a current point (set by
+    // `op_moveto`/`op_rectangle`, cleared by the painting operators below).
+    // Tracked separately from `CanvasState` since it isn't graphics state
+    // and isn't affected by `save_state`/`restore_state`.
+    has_current_point: bool,
+    // Content-stream bytes already finalized by `append_raw`, with any raw
+    // (non pdf-writer-typed) segments already spliced in; `content` holds
+    // the typed suffix still being built on top of them.
+    flushed: Vec<u8>,
 }
 
 impl PdfCanvas {
@@ -52,6 +117,8 @@ impl PdfCanvas {
             state_stack: Vec::new(),
             cid_cache: HashMap::new(),
             cached_font: None,
+            has_current_point: false,
+            flushed: Vec::new(),
         }
     }
 
@@ -60,9 +127,24 @@ impl PdfCanvas {
         &mut self.content
     }
 
+    /// Open a tagged-PDF marked-content span for `role` (e.g. "P", "H1",
+    /// "Figure"), identified by `mcid` so it can be referenced from the
+    /// document's structure tree via `/ParentTree`.
+    pub fn begin_tag(&mut self, role: Name, mcid: i32) {
+        let mut marked = self.content.begin_marked_content_with_properties(role);
+        marked.properties().identify(mcid);
+    }
+
+    /// Close the marked-content span opened by the matching `begin_tag`.
+    pub fn end_tag(&mut self) {
+        self.content.end_marked_content();
+    }
+
     /// Get content (for finalizing)
     pub fn finish(self) -> Vec<u8> {
-        self.content.finish()
+        let mut out = self.flushed;
+        out.extend(self.content.finish());
+        out
     }
 
     /// Get current font name
@@ -70,6 +152,11 @@ impl PdfCanvas {
         self.state.font_name
     }
 
+    /// Get current font size
+    pub fn get_font_size(&self) -> f64 {
+        self.state.font_size
+    }
+
     // ===== State Management =====
 
     pub fn save_state(&mut self) {
@@ -93,6 +180,12 @@ impl PdfCanvas {
         self.content.set_fill_rgb(r, g, b);
     }
 
+    /// Get current fill color
+    #[inline]
+    pub fn get_fill_color(&self) -> Color {
+        self.state.fill_color
+    }
+
     #[inline]
     pub fn set_stroke_color(&mut self, color: Color) {
         self.state.stroke_color = color;
@@ -106,6 +199,19 @@ impl PdfCanvas {
         self.content.set_line_width(width as f32);
     }
 
+    /// Set where a subsequently-stroked `rect()`'s border sits relative to
+    /// the rectangle's own bounds. Affects `rect()` only.
+    #[inline]
+    pub fn set_stroke_align(&mut self, align: StrokeAlign) {
+        self.state.stroke_align = align;
+    }
+
+    /// Get current line width
+    #[inline]
+    pub fn get_line_width(&self) -> f64 {
+        self.state.line_width
+    }
+
     pub fn set_dash(&mut self, pattern: Vec<f64>, offset: f64) {
         self.state.dash_pattern = Some((pattern.clone(), offset));
         let pattern_f32: Vec<f32> = pattern.iter().map(|&x| x as f32).collect();
@@ -128,16 +234,110 @@ impl PdfCanvas {
         self.content.set_parameters(name);
     }
 
+    /// Emit the `ri` operator, setting the color rendering intent for
+    /// subsequent painting operations.
+    #[inline]
+    pub fn set_rendering_intent(&mut self, intent: pdf_writer::types::RenderingIntent) {
+        self.content.set_rendering_intent(intent);
+    }
+
+    /// Select `name` (a colored tiling pattern already present in the
+    /// current page's `/Pattern` resource dictionary) as the fill color, via
+    /// the `cs`/`scn` operator pair. Subsequent fills (e.g. `rect(...,
+    /// fill: true, ...)`) paint with the pattern's tiled content instead of
+    /// a flat color, until the next fill color/pattern change or `Q`.
+    #[inline]
+    pub fn set_fill_pattern(&mut self, name: Name<'static>) {
+        self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+        self.content.set_fill_pattern(std::iter::empty(), name);
+    }
+
+    /// Switch to fill+stroke text rendering with a thin stroke in the fill color,
+    /// to fatten glyphs when the active font has no real bold variant.
+    fn apply_synthetic_bold(&mut self, r: f32, g: f32, b: f32) {
+        self.content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+        self.content.set_stroke_rgb(r, g, b);
+        self.content
+            .set_line_width(self.state.font_size as f32 * SYNTHETIC_BOLD_STROKE_RATIO);
+    }
+
+    /// Undo `apply_synthetic_bold`'s graphics-state changes so later stroked
+    /// shapes (rects, lines) don't inherit the faux-bold stroke color/width.
+    fn clear_synthetic_bold(&mut self) {
+        self.content.set_text_rendering_mode(TextRenderingMode::Fill);
+        let (r, g, b) = (
+            self.state.stroke_color.r as f32,
+            self.state.stroke_color.g as f32,
+            self.state.stroke_color.b as f32,
+        );
+        self.content.set_stroke_rgb(r, g, b);
+        self.content.set_line_width(self.state.line_width as f32);
+    }
+
+    /// Position the text line at `(x, y)`, shearing it when synthesizing italic
+    /// for a font with no real italic/oblique variant.
+    fn begin_text_at(&mut self, x: f64, y: f64, synth_italic: bool) {
+        if synth_italic {
+            self.content
+                .set_text_matrix([1.0, 0.0, SYNTHETIC_ITALIC_SHEAR, 1.0, x as f32, y as f32]);
+        } else {
+            self.content.next_line(x as f32, y as f32);
+        }
+    }
+
     // ===== Drawing =====
 
     #[inline]
     pub fn rect(&mut self, rect: Rect, fill: bool, stroke: bool) {
+        if self.state.stroke_align == StrokeAlign::Center || !stroke {
+            self.content.rect(
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+            );
+            if fill {
+                self.content.fill_nonzero();
+            }
+            if stroke {
+                self.content.stroke();
+            }
+            return;
+        }
+
+        // Inside/Outside: the fill (if any) uses the rectangle's own bounds,
+        // but the stroke needs a different path, inset/outset by half the
+        // line width, since PDF always centers a stroke on the path it's
+        // given.
+        if fill {
+            self.content.rect(
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+            );
+            self.content.fill_nonzero();
+        }
+
+        let half_width = self.state.line_width / 2.0;
+        let inset = match self.state.stroke_align {
+            StrokeAlign::Inside => half_width,
+            StrokeAlign::Outside => -half_width,
+            StrokeAlign::Center => unreachable!(),
+        };
+        let stroke_width = (rect.width - 2.0 * inset).max(0.0);
+        let stroke_height = (rect.height - 2.0 * inset).max(0.0);
         self.content.rect(
-            rect.x as f32,
-            rect.y as f32,
-            rect.width as f32,
-            rect.height as f32,
+            (rect.x + inset) as f32,
+            (rect.y + inset) as f32,
+            stroke_width as f32,
+            stroke_height as f32,
         );
+        self.content.stroke();
+    }
+
+    pub fn round_rect(&mut self, rect: Rect, radius: f64, fill: bool, stroke: bool) {
+        self.build_round_rect_path(rect, radius);
         if fill {
             self.content.fill_nonzero();
         }
@@ -146,14 +346,42 @@ impl PdfCanvas {
         }
     }
 
-    pub fn round_rect(&mut self, rect: Rect, radius: f64, fill: bool, stroke: bool) {
+    /// Same path as `round_rect()`, but uses the combined `B`/`B*` operator
+    /// when both filling and stroking instead of two separate terminal
+    /// operators, so a single path traversal does both. Used by
+    /// `canvas_round_rect_styled()` to keep the operator count down.
+    pub fn round_rect_combined(&mut self, rect: Rect, radius: f64, fill: bool, stroke: bool) {
+        self.build_round_rect_path(rect, radius);
+        match (fill, stroke) {
+            (true, true) => {
+                self.content.fill_nonzero_and_stroke();
+            }
+            (true, false) => {
+                self.content.fill_nonzero();
+            }
+            (false, true) => {
+                self.content.stroke();
+            }
+            (false, false) => {}
+        }
+    }
+
+    /// Build a rounded-rectangle path (clamping `radius` to half the smaller
+    /// dimension), without a terminal fill/stroke operator. Shared by
+    /// `round_rect()` and `round_rect_combined()`.
+    fn build_round_rect_path(&mut self, rect: Rect, radius: f64) {
         // Clamp radius to half of the smaller dimension
         let max_radius = rect.width.min(rect.height) / 2.0;
         let r = radius.min(max_radius).max(0.0);
 
         if r <= 0.0 {
             // No rounding, use regular rectangle
-            self.rect(rect, fill, stroke);
+            self.content.rect(
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+            );
             return;
         }
 
@@ -222,7 +450,23 @@ impl PdfCanvas {
 
         // Close path
         self.content.close_path();
+    }
 
+    /// Like `round_rect()`, but each corner has its own radius (`0.0` gives a
+    /// square corner), e.g. for a card with only its top two corners rounded.
+    /// Each radius is independently clamped to half of the smaller dimension.
+    #[allow(clippy::too_many_arguments)]
+    pub fn round_rect_corners(
+        &mut self,
+        rect: Rect,
+        top_left: f64,
+        top_right: f64,
+        bottom_right: f64,
+        bottom_left: f64,
+        fill: bool,
+        stroke: bool,
+    ) {
+        self.build_round_rect_corners_path(rect, top_left, top_right, bottom_right, bottom_left);
         if fill {
             self.content.fill_nonzero();
         }
@@ -231,6 +475,104 @@ impl PdfCanvas {
         }
     }
 
+    /// Build a rounded-rectangle path with independent per-corner radii,
+    /// clamping each to half of the smaller dimension (with a warning if
+    /// clamping was necessary), without a terminal fill/stroke operator.
+    fn build_round_rect_corners_path(
+        &mut self,
+        rect: Rect,
+        top_left: f64,
+        top_right: f64,
+        bottom_right: f64,
+        bottom_left: f64,
+    ) {
+        let max_radius = rect.width.min(rect.height) / 2.0;
+        let clamp_corner = |label: &str, r: f64| -> f64 {
+            let clamped = r.clamp(0.0, max_radius);
+            if r > max_radius {
+                log::warn!(
+                    "round_rect_corners: {} radius {} exceeds half the smaller \
+                     dimension ({}); clamping to {}",
+                    label,
+                    r,
+                    max_radius,
+                    clamped
+                );
+            }
+            clamped
+        };
+        let tl = clamp_corner("top-left", top_left);
+        let tr = clamp_corner("top-right", top_right);
+        let br = clamp_corner("bottom-right", bottom_right);
+        let bl = clamp_corner("bottom-left", bottom_left);
+
+        let x = rect.x;
+        let y = rect.y;
+        let w = rect.width;
+        let h = rect.height;
+
+        // Control point offset for bezier curves (approximation for circular arc)
+        let c = |r: f64| r * 0.55228475;
+
+        // Start from top-left corner (after rounded corner)
+        self.content.move_to((x + tl) as f32, (y + h) as f32);
+
+        // Top edge
+        self.content.line_to((x + w - tr) as f32, (y + h) as f32);
+
+        // Top-right rounded corner
+        self.content.cubic_to(
+            (x + w - tr + c(tr)) as f32,
+            (y + h) as f32,
+            (x + w) as f32,
+            (y + h - tr + c(tr)) as f32,
+            (x + w) as f32,
+            (y + h - tr) as f32,
+        );
+
+        // Right edge
+        self.content.line_to((x + w) as f32, (y + br) as f32);
+
+        // Bottom-right rounded corner
+        self.content.cubic_to(
+            (x + w) as f32,
+            (y + br - c(br)) as f32,
+            (x + w - br + c(br)) as f32,
+            y as f32,
+            (x + w - br) as f32,
+            y as f32,
+        );
+
+        // Bottom edge
+        self.content.line_to((x + bl) as f32, y as f32);
+
+        // Bottom-left rounded corner
+        self.content.cubic_to(
+            (x + bl - c(bl)) as f32,
+            y as f32,
+            x as f32,
+            (y + bl - c(bl)) as f32,
+            x as f32,
+            (y + bl) as f32,
+        );
+
+        // Left edge
+        self.content.line_to(x as f32, (y + h - tl) as f32);
+
+        // Top-left rounded corner
+        self.content.cubic_to(
+            x as f32,
+            (y + h - tl + c(tl)) as f32,
+            (x + tl - c(tl)) as f32,
+            (y + h) as f32,
+            (x + tl) as f32,
+            (y + h) as f32,
+        );
+
+        // Close path
+        self.content.close_path();
+    }
+
     #[inline]
     pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
         self.content.move_to(x1 as f32, y1 as f32);
@@ -238,12 +580,120 @@ impl PdfCanvas {
         self.content.stroke();
     }
 
+    // ===== Low-level path construction (PostScript-style operators) =====
+    //
+    // These map 1:1 to PDF path operators (`m`, `l`, `c`, `h`, `re`, `f`/`f*`,
+    // `S`, `B`/`B*`, `W`/`W*`, `n`) for callers that need to build arbitrary
+    // paths the higher-level shape helpers above don't cover. `op_lineto`,
+    // `op_curveto` and `op_closepath` require a current point (established by
+    // a prior `op_moveto` or `op_rectangle`) and return an error instead of
+    // emitting a malformed path when there isn't one.
+
+    pub fn op_moveto(&mut self, x: f64, y: f64) {
+        self.content.move_to(x as f32, y as f32);
+        self.has_current_point = true;
+    }
+
+    pub fn op_lineto(&mut self, x: f64, y: f64) -> Result<(), &'static str> {
+        if !self.has_current_point {
+            return Err("lineto without a prior moveto");
+        }
+        self.content.line_to(x as f32, y as f32);
+        Ok(())
+    }
+
+    pub fn op_curveto(
+        &mut self,
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x3: f64,
+        y3: f64,
+    ) -> Result<(), &'static str> {
+        if !self.has_current_point {
+            return Err("curveto without a prior moveto");
+        }
+        self.content.cubic_to(
+            x1 as f32, y1 as f32, x2 as f32, y2 as f32, x3 as f32, y3 as f32,
+        );
+        Ok(())
+    }
+
+    pub fn op_closepath(&mut self) -> Result<(), &'static str> {
+        if !self.has_current_point {
+            return Err("closepath without a prior moveto");
+        }
+        self.content.close_path();
+        Ok(())
+    }
+
+    pub fn op_rectangle(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.content
+            .rect(x as f32, y as f32, width as f32, height as f32);
+        self.has_current_point = true;
+    }
+
+    pub fn op_fill(&mut self, even_odd: bool) {
+        if even_odd {
+            self.content.fill_even_odd();
+        } else {
+            self.content.fill_nonzero();
+        }
+        self.has_current_point = false;
+    }
+
+    pub fn op_stroke(&mut self) {
+        self.content.stroke();
+        self.has_current_point = false;
+    }
+
+    pub fn op_fill_stroke(&mut self, even_odd: bool) {
+        if even_odd {
+            self.content.fill_even_odd_and_stroke();
+        } else {
+            self.content.fill_nonzero_and_stroke();
+        }
+        self.has_current_point = false;
+    }
+
+    /// Mark the current path as a clipping path (`W`/`W*`). Per the PDF
+    /// spec this doesn't end the path on its own -- it must be followed by
+    /// a path-painting operator, typically `op_end_path` for a clip with no
+    /// visible fill/stroke.
+    pub fn op_clip(&mut self, even_odd: bool) {
+        if even_odd {
+            self.content.clip_even_odd();
+        } else {
+            self.content.clip_nonzero();
+        }
+    }
+
+    pub fn op_end_path(&mut self) {
+        self.content.end_path();
+        self.has_current_point = false;
+    }
+
     // ===== Text =====
 
     /// Draw text string with a Type0 font (Identity-H).
     /// All fonts are expected to have a CID map; we panic otherwise.
+    /// `synth_bold`/`synth_italic` fatten/slant the glyphs via stroke and shear
+    /// when the active font has no real bold/italic variant of its own.
+    /// `ligatures`, when given, is consulted after the cmap lookup to merge
+    /// CID sequences (e.g. "f" + "i") into a single ligature CID, greedily
+    /// matching the longest candidate first.
     #[inline]
-    pub fn draw_string(&mut self, x: f64, y: f64, text: &str, cid_map: &CidMap) {
+    pub fn draw_string(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: &str,
+        cid_map: &CidMap,
+        synth: (bool, bool),
+        ligatures: Option<&LigatureTable>,
+    ) {
+        let (synth_bold, synth_italic) = synth;
         // Set fill color for text (text uses fill color, not stroke color)
         // Note: We don't check if color changed here because text rendering
         // typically uses the same color for multiple strings, and the check
@@ -254,45 +704,188 @@ impl PdfCanvas {
             self.state.fill_color.b as f32,
         );
         self.content.set_fill_rgb(r, g, b);
+        if synth_bold {
+            self.apply_synthetic_bold(r, g, b);
+        }
 
         self.content.begin_text();
         self.content
             .set_font(self.state.font_name, self.state.font_size as f32);
-        self.content.next_line(x as f32, y as f32);
+        self.begin_text_at(x, y, synth_italic);
 
         // Type0 font: convert Unicode code points to CIDs using the map
         // Use cache to avoid repeated lookups for the same characters
-        let mut cid_bytes = Vec::with_capacity(text.len() * 2);
-        
-        // Pre-cache space CID for fallback
-        let space_cid_bytes = *self.cid_cache.entry(0x0020).or_insert_with(|| {
-            if let Some(&space_cid) = cid_map.get(&0x0020) {
-                [(space_cid >> 8) as u8, (space_cid & 0xFF) as u8]
-            } else {
-                [0, 0]
-            }
-        });
-        
-        for ch in text.chars() {
-            let code_point = ch as u32;
-            let cid_byte_pair = *self.cid_cache.entry(code_point).or_insert_with(|| {
-                if let Some(&cid) = cid_map.get(&code_point) {
-                    // Convert CID to 2-byte big-endian
-                    [(cid >> 8) as u8, (cid & 0xFF) as u8]
-                } else {
-                    // Fallback to space or .notdef
-                    space_cid_bytes
-                }
-            });
-            cid_bytes.extend_from_slice(&cid_byte_pair);
+        let space_cid = *self
+            .cid_cache
+            .entry(0x0020)
+            .or_insert_with(|| cid_map.get(&0x0020).copied().unwrap_or(0));
+
+        let mut cids: Vec<u16> = text
+            .chars()
+            .map(|ch| {
+                let code_point = ch as u32;
+                *self
+                    .cid_cache
+                    .entry(code_point)
+                    .or_insert_with(|| cid_map.get(&code_point).copied().unwrap_or(space_cid))
+            })
+            .collect();
+
+        if let Some(ligatures) = ligatures {
+            cids = substitute_ligatures(&cids, ligatures);
+        }
+
+        let mut cid_bytes = Vec::with_capacity(cids.len() * 2);
+        for cid in cids {
+            cid_bytes.extend_from_slice(&[(cid >> 8) as u8, (cid & 0xFF) as u8]);
         }
         // Use show_text if available (more efficient), otherwise fall back to show
         // Note: pdf-writer may not have show_text, so we use show
         self.content.show(Str(&cid_bytes));
         self.content.end_text();
+        if synth_bold {
+            self.clear_synthetic_bold();
+        }
+    }
+
+    /// Draw text in fill+stroke mode (`Tr 2`) with a stroke color and width
+    /// independent of the current fill color, for outlined display text.
+    /// Sets the render mode and stroke paint around the normal `draw_string`
+    /// path, then restores `Tr 0` and the previously-set stroke color/width
+    /// the same way `apply_synthetic_bold`/`clear_synthetic_bold` do.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_string_outlined(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: &str,
+        cid_map: &CidMap,
+        synth: (bool, bool),
+        ligatures: Option<&LigatureTable>,
+        stroke: Color,
+        stroke_width: f64,
+    ) {
+        self.content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+        self.content
+            .set_stroke_rgb(stroke.r as f32, stroke.g as f32, stroke.b as f32);
+        self.content.set_line_width(stroke_width as f32);
+
+        self.draw_string(x, y, text, cid_map, synth, ligatures);
+
+        self.content.set_text_rendering_mode(TextRenderingMode::Fill);
+        let (r, g, b) = (
+            self.state.stroke_color.r as f32,
+            self.state.stroke_color.g as f32,
+            self.state.stroke_color.b as f32,
+        );
+        self.content.set_stroke_rgb(r, g, b);
+        self.content.set_line_width(self.state.line_width as f32);
+    }
+
+    /// Draw text as a sequence of segments joined by `TJ` adjustments, one per gap
+    /// between consecutive segments (`adjustments.len() == segments.len() - 1`).
+    /// Used for justification: widening the gaps between words to hit an exact
+    /// target line width with a single text-showing operator.
+    pub fn draw_string_positioned(
+        &mut self,
+        x: f64,
+        y: f64,
+        segments: &[String],
+        adjustments: &[f32],
+        cid_map: &CidMap,
+        synth: (bool, bool),
+    ) {
+        let (synth_bold, synth_italic) = synth;
+        let (r, g, b) = (
+            self.state.fill_color.r as f32,
+            self.state.fill_color.g as f32,
+            self.state.fill_color.b as f32,
+        );
+        self.content.set_fill_rgb(r, g, b);
+        if synth_bold {
+            self.apply_synthetic_bold(r, g, b);
+        }
+
+        self.content.begin_text();
+        self.content
+            .set_font(self.state.font_name, self.state.font_size as f32);
+        self.begin_text_at(x, y, synth_italic);
+
+        let mut positioned = self.content.show_positioned();
+        let mut items = positioned.items();
+        for (i, segment) in segments.iter().enumerate() {
+            let mut cid_bytes = Vec::with_capacity(segment.len() * 2);
+            for ch in segment.chars() {
+                let code_point = ch as u32;
+                let cid = *self
+                    .cid_cache
+                    .entry(code_point)
+                    .or_insert_with(|| cid_map.get(&code_point).copied().unwrap_or(0));
+                cid_bytes.extend_from_slice(&[(cid >> 8) as u8, (cid & 0xFF) as u8]);
+            }
+            items.show(Str(&cid_bytes));
+            if let Some(&adjustment) = adjustments.get(i) {
+                items.adjust(adjustment);
+            }
+        }
+        drop(items);
+        drop(positioned);
+        self.content.end_text();
+        if synth_bold {
+            self.clear_synthetic_bold();
+        }
+    }
+
+    /// Draw text as a sequence of runs, switching the active font (`Tf`) between
+    /// runs within a single text object. Used for glyph fallback: the caller has
+    /// already split the string by which registered font covers each code point
+    /// and pre-encoded each run's CID bytes, so this just emits `Tf`/`Tj` pairs
+    /// back to back — the text position advances naturally between them.
+    /// `synth_bold`/`synth_italic` apply to the whole run sequence, matching the
+    /// primary font's resolved style (fallback glyphs are drawn with the same
+    /// faux styling rather than tracked per fallback font).
+    pub fn draw_string_multi_font(
+        &mut self,
+        x: f64,
+        y: f64,
+        runs: &[(Name<'static>, Vec<u8>)],
+        synth: (bool, bool),
+    ) {
+        let (synth_bold, synth_italic) = synth;
+        let (r, g, b) = (
+            self.state.fill_color.r as f32,
+            self.state.fill_color.g as f32,
+            self.state.fill_color.b as f32,
+        );
+        self.content.set_fill_rgb(r, g, b);
+        if synth_bold {
+            self.apply_synthetic_bold(r, g, b);
+        }
+
+        self.content.begin_text();
+        self.begin_text_at(x, y, synth_italic);
+        for (font_name, cid_bytes) in runs {
+            self.content.set_font(*font_name, self.state.font_size as f32);
+            self.content.show(Str(cid_bytes));
+        }
+        self.content.end_text();
+        if synth_bold {
+            self.clear_synthetic_bold();
+        }
     }
 
     // ===== Transformations =====
+    //
+    // Each of these emits its own `cm` operator, which PDF viewers concatenate
+    // as CTM_new = M x CTM_old (row-vector points, M on the left). That makes
+    // the LAST `cm` issued before a draw the one applied to content
+    // coordinates FIRST, and the FIRST `cm` issued the one applied last (i.e.
+    // outermost) -- so calling translate() then rotate() then scale() composes
+    // exactly like the conventional world = T * R * S: content is scaled,
+    // then rotated, then translated. No reordering is needed here; composing
+    // a non-uniform scale with a rotation is inherently non-conformal (it
+    // will look skewed next to a pure rotation), which is correct PDF
+    // behavior, not a bug in this concatenation.
 
     #[inline]
     pub fn translate(&mut self, x: f64, y: f64) {
@@ -315,6 +908,16 @@ impl PdfCanvas {
             .transform([sx as f32, 0.0, 0.0, sy as f32, 0.0, 0.0]);
     }
 
+    /// Skew the coordinate system: `ax_degrees` shears x along y, `ay_degrees`
+    /// shears y along x (both measured from the respective axis, PDF/PostScript
+    /// style).
+    #[inline]
+    pub fn skew(&mut self, ax_degrees: f64, ay_degrees: f64) {
+        let tan_ax = ax_degrees.to_radians().tan() as f32;
+        let tan_ay = ay_degrees.to_radians().tan() as f32;
+        self.content.transform([1.0, tan_ay, tan_ax, 1.0, 0.0, 0.0]);
+    }
+
     pub fn transform(&mut self, matrix: [f32; 6]) {
         self.content.transform(matrix);
     }
@@ -352,6 +955,111 @@ impl PdfCanvas {
         self.content.x_object(image_name);
         self.content.restore_state();
     }
+
+    /// Finish the in-progress typed `Content` builder and splice `raw` bytes
+    /// directly into the content stream after it, then resume typed
+    /// building in a fresh `Content`. This is the escape hatch for inline
+    /// images: pdf-writer's typed API has no way to emit unescaped binary
+    /// data -- every operand it writes goes through PDF string/number
+    /// encoding, which would corrupt raw image bytes.
+    fn append_raw(&mut self, raw: &[u8]) {
+        let finished = std::mem::replace(&mut self.content, Content::new()).finish();
+        self.flushed.extend(finished);
+        self.flushed.extend(raw);
+    }
+
+    /// Draw a small bitmap as an inline image (`BI`/`ID`/`EI`) directly in
+    /// the content stream, instead of registering it as an XObject. Meant
+    /// for tiny 1-bit stencil masks and icons where the XObject bookkeeping
+    /// overhead outweighs the image itself.
+    ///
+    /// `width`/`height` double as both the image's pixel dimensions and the
+    /// size (in points) it's drawn at -- inline images are for small,
+    /// unscaled icons, not general-purpose scaled raster content.
+    /// `color_space` is `"DeviceGray"`, `"DeviceRGB"`, or `"DeviceCMYK"`
+    /// (abbreviations `"G"`/`"RGB"`/`"CMYK"` also accepted); `bits` is the
+    /// per-component bit depth (1, 2, 4, or 8). `data` must be exactly the
+    /// image's packed scanlines (rows padded to a byte boundary, per the
+    /// PDF spec) and no larger than `MAX_INLINE_IMAGE_BYTES`; larger images
+    /// should go through `draw_image` instead, which shares a single
+    /// XObject across repeated uses.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_inline_image(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        data: &[u8],
+        color_space: &str,
+        bits: u8,
+    ) -> Result<(), String> {
+        if data.len() > MAX_INLINE_IMAGE_BYTES {
+            return Err(format!(
+                "inline image data is {} bytes, over the {}-byte inline limit; use draw_image (XObject) instead",
+                data.len(),
+                MAX_INLINE_IMAGE_BYTES
+            ));
+        }
+        let (cs_abbrev, components) = inline_image_color_space(color_space)?;
+        if !matches!(bits, 1 | 2 | 4 | 8) {
+            return Err(format!(
+                "unsupported inline image bit depth: {} (expected 1, 2, 4, or 8)",
+                bits
+            ));
+        }
+
+        let px_width = width.round().max(0.0) as u32;
+        let px_height = height.round().max(0.0) as u32;
+        let row_bytes = (px_width as u64 * components as u64 * bits as u64).div_ceil(8) as usize;
+        let expected_len = row_bytes * px_height as usize;
+        if data.len() != expected_len {
+            return Err(format!(
+                "inline image data is {} bytes, expected {} for a {}x{} {}-bit {} image",
+                data.len(),
+                expected_len,
+                px_width,
+                px_height,
+                bits,
+                color_space
+            ));
+        }
+
+        self.content.save_state();
+        self.content
+            .transform([width as f32, 0.0, 0.0, height as f32, x as f32, y as f32]);
+
+        let mut raw = Vec::with_capacity(data.len() + 64);
+        raw.extend_from_slice(b"\nBI\n");
+        raw.extend_from_slice(
+            format!(
+                "/W {}\n/H {}\n/CS /{}\n/BPC {}\nID\n",
+                px_width, px_height, cs_abbrev, bits
+            )
+            .as_bytes(),
+        );
+        raw.extend_from_slice(data);
+        raw.extend_from_slice(b"\nEI\n");
+        self.append_raw(&raw);
+
+        self.content.restore_state();
+        Ok(())
+    }
+}
+
+/// Resolve an inline image `/CS` abbreviation and component count for a
+/// color space name. Accepts both the full device color space names and
+/// their inline-image abbreviations.
+fn inline_image_color_space(name: &str) -> Result<(&'static str, u32), String> {
+    match name {
+        "DeviceGray" | "G" | "Gray" => Ok(("G", 1)),
+        "DeviceRGB" | "RGB" => Ok(("RGB", 3)),
+        "DeviceCMYK" | "CMYK" => Ok(("CMYK", 4)),
+        other => Err(format!(
+            "unsupported inline image color space: {:?} (expected \"DeviceGray\", \"DeviceRGB\", or \"DeviceCMYK\")",
+            other
+        )),
+    }
 }
 
 impl Default for PdfCanvas {
@@ -359,3 +1067,94 @@ impl Default for PdfCanvas {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PdfCanvas;
+
+    #[test]
+    fn set_dash_emits_explicit_phase_not_first_pattern_entry() {
+        let mut canvas = PdfCanvas::new();
+        // If `offset` were mistakenly derived from `pattern[0]` (the old
+        // bug), this would emit `2` as the phase instead of `5`.
+        canvas.set_dash(vec![2.0, 1.0], 5.0);
+        let bytes = canvas.finish();
+        let content = String::from_utf8(bytes).unwrap();
+        assert!(
+            content.contains("[2 1] 5 d"),
+            "expected dash operator with phase 5, got: {}",
+            content
+        );
+    }
+
+    /// Parse the operands of every `cm` operator in a content stream, in
+    /// emission order.
+    fn parse_cm_matrices(content: &str) -> Vec<[f32; 6]> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut tokens = line.split_whitespace();
+                let operands: Vec<f32> = (&mut tokens)
+                    .take(6)
+                    .map(|t| t.parse().unwrap())
+                    .collect();
+                if tokens.next() == Some("cm") && operands.len() == 6 {
+                    Some([
+                        operands[0], operands[1], operands[2],
+                        operands[3], operands[4], operands[5],
+                    ])
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Compose two PDF-style affine matrices (`[a b c d e f]`, applied to a
+    /// row vector as `[x y 1] * [[a b 0][c d 0][e f 1]]`) into the single
+    /// matrix that applies `m1` first, then `m2`.
+    fn compose(m1: [f32; 6], m2: [f32; 6]) -> [f32; 6] {
+        let (a1, b1, c1, d1, e1, f1) = (m1[0], m1[1], m1[2], m1[3], m1[4], m1[5]);
+        let (a2, b2, c2, d2, e2, f2) = (m2[0], m2[1], m2[2], m2[3], m2[4], m2[5]);
+        [
+            a1 * a2 + b1 * c2,
+            a1 * b2 + b1 * d2,
+            c1 * a2 + d1 * c2,
+            c1 * b2 + d1 * d2,
+            e1 * a2 + f1 * c2 + e2,
+            e1 * b2 + f1 * d2 + f2,
+        ]
+    }
+
+    #[test]
+    fn skew_composes_with_a_prior_transform_in_last_issued_first_order() {
+        let mut canvas = PdfCanvas::new();
+        canvas.translate(10.0, 20.0);
+        canvas.skew(45.0, 0.0);
+        let bytes = canvas.finish();
+        let content = String::from_utf8(bytes).unwrap();
+
+        let matrices = parse_cm_matrices(&content);
+        assert_eq!(matrices.len(), 2, "expected one `cm` per call, got: {}", content);
+        let [translate_matrix, skew_matrix] = [matrices[0], matrices[1]];
+
+        // Per the composition-order comment above: the *last* `cm` issued
+        // (skew) is applied to content coordinates first, and the *first*
+        // issued (translate) last/outermost -- so the effective transform is
+        // compose(skew, translate), not compose(translate, skew).
+        let composed = compose(skew_matrix, translate_matrix);
+        // Hand-computed: skew(45, 0) is [1 0 1 1 0 0], translate(10, 20) is
+        // [1 0 0 1 10 20]; composing skew-then-translate leaves the shear
+        // term (`c`) untouched by the translation and just carries the
+        // translation's `e`/`f` through unchanged.
+        let expected = [1.0, 0.0, 1.0, 1.0, 10.0, 20.0];
+        for (got, want) in composed.iter().zip(expected.iter()) {
+            assert!(
+                (got - want).abs() < 1e-4,
+                "composed matrix {:?} != expected {:?}",
+                composed,
+                expected
+            );
+        }
+    }
+}
@@ -1,9 +1,25 @@
 //! EMF format parser and converter
+//!
+//! `packages/docquill_core/docquill/media/converter/rust/emf-converter/src/emf.rs`
+//! is a real, independent fork of this file (see `converters.py`'s
+//! `import docquill_rust as emf_converter` / `import emf_converter` fallback
+//! chain) -- not just the unrelated `docquill_pdf_rust` PDF canvas that
+//! synth-350 was about. It's kept as a separate crate deliberately: it's the
+//! fallback used when this crate's heavier PDF/image/resvg dependency stack
+//! fails to build, so it can't depend on this crate directly without losing
+//! that property. The two copies have since diverged in real capability
+//! (coordinate precision, EMF+ dual-stream detection, lenient/meta parsing
+//! variants, `log` integration) rather than just accumulating incidental
+//! drift, so folding them behind one shared module -- without either
+//! regressing this file's feature set or dragging this crate's dependency
+//! tree into the fallback -- is a bigger structural change than fits in one
+//! change request. Left as a follow-up.
 
-use crate::svg_writer::SvgWriter;
+use crate::svg_writer::{SvgWriter, FontStyle, DEFAULT_COORD_PRECISION};
 use crate::emfplus::EmfPlusParser;
 use crate::emf_records;
-use std::io::Cursor;
+use image::ImageEncoder;
+use std::io::{Cursor, Read};
 
 /// Check if data is EMF format
 pub fn is_emf_format(data: &[u8]) -> bool {
@@ -14,15 +30,24 @@ pub fn is_emf_format(data: &[u8]) -> bool {
         && data[3] == 0x00
 }
 
-/// Convert EMF data to SVG string
+/// Convert EMF data to SVG string, using the default coordinate precision.
 pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    convert_emf_to_svg_opts(data, DEFAULT_COORD_PRECISION)
+}
+
+/// Convert EMF data to SVG string, rounding coordinates and lengths to `precision`
+/// decimal places (0 emits integer coordinates). Lower precision keeps output
+/// smaller and more diff-friendly; it's rounded per-number rather than accumulated
+/// across a path, so it doesn't visibly misalign strokes at the 2-3 decimal places
+/// this is meant for.
+pub fn convert_emf_to_svg_opts(data: &[u8], precision: u8) -> Result<String, Box<dyn std::error::Error>> {
     if !is_emf_format(data) {
         return Err("Invalid EMF format".into());
     }
 
     // Parse EMF header to get dimensions, frame size (physical size), header size, and initial view transform
     let (_bounds_width, _bounds_height, frame_width_mm, frame_height_mm, header_size, initial_view_transform) = parse_emf_header(data)?;
-    
+
     // Create temporary SVG writer to parse records and get final view transform
     let mut temp_svg = SvgWriter::new(100, 100);
     let final_view_transform = match parse_emf_records(data, header_size, &mut temp_svg, initial_view_transform) {
@@ -58,12 +83,12 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     let max_possible_x = (final_view_transform.window_ext_x as f64 * scale_x).max(svg_width_logical);
     let max_possible_y = (final_view_transform.window_ext_y as f64 * scale_y).max(svg_height_logical);
     
-    eprintln!("EMF Header - rclFrame (physical size): {:.2}mm x {:.2}mm ({:.2}px x {:.2}px)", 
+    log::debug!("EMF Header - rclFrame (physical size): {:.2}mm x {:.2}mm ({:.2}px x {:.2}px)",
               frame_width_mm, frame_height_mm, frame_width_px, frame_height_px);
-    eprintln!("EMF Header - rclBounds (logical units): {:.2} x {:.2}", _bounds_width, _bounds_height);
-    eprintln!("Final viewport extents: ({}, {})", final_view_transform.viewport_ext_x, final_view_transform.viewport_ext_y);
-    eprintln!("Final window extents: ({}, {})", final_view_transform.window_ext_x, final_view_transform.window_ext_y);
-    eprintln!("Scale: ({}, {})", scale_x, scale_y);
+    log::debug!("EMF Header - rclBounds (logical units): {:.2} x {:.2}", _bounds_width, _bounds_height);
+    log::debug!("Final viewport extents: ({}, {})", final_view_transform.viewport_ext_x, final_view_transform.viewport_ext_y);
+    log::debug!("Final window extents: ({}, {})", final_view_transform.window_ext_x, final_view_transform.window_ext_y);
+    log::debug!("Scale: ({}, {})", scale_x, scale_y);
     
     // Use frame size (physical size) as the SVG dimensions if valid
     // This ensures the SVG has the correct default size as intended by the EMF file
@@ -80,7 +105,7 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
         max_possible_y.max(2000.0)
     };
     
-    eprintln!("SVG dimensions (using rclFrame): {:.2}x{:.2}", svg_width, svg_height);
+    log::debug!("SVG dimensions (using rclFrame): {:.2}x{:.2}", svg_width, svg_height);
     
     // Normalize dimensions
     let width = normalize_dimension(svg_width);
@@ -89,16 +114,23 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     // Create SVG writer with correct dimensions (using rclFrame from EMF header)
     // Note: EMF+ uses the same EMF header (rclFrame/rclBounds) for size information,
     // so it doesn't need its own size handling - it uses the SVG writer created here
-    let mut svg = SvgWriter::new(width, height);
-    
+    let mut svg = SvgWriter::with_precision(width, height, precision);
+
+    // A "dual" EMF/EMF+ metafile carries its real drawing as EMR_COMMENT_EMFPLUS records,
+    // with the plain GDI records around them only a fallback rendering for viewers that
+    // don't understand EMF+; rendering both would draw the picture twice, so prefer EMF+.
+    let has_emf_plus = scan_has_emf_plus(data, header_size);
+
     // Try to parse EMF records
     let mut rendered_gdi = false;
-    
-    // Parse EMF GDI records (basic implementation) - reset state with final transform
-    match parse_emf_records(data, header_size, &mut svg, initial_view_transform) {
-        Ok(_) => rendered_gdi = true,
-        Err(e) => {
-            eprintln!("EMF GDI parsing failed: {}", e);
+
+    if !has_emf_plus {
+        // Parse EMF GDI records (basic implementation) - reset state with final transform
+        match parse_emf_records(data, header_size, &mut svg, initial_view_transform) {
+            Ok(_) => rendered_gdi = true,
+            Err(e) => {
+                log::warn!("EMF GDI parsing failed: {}", e);
+            }
         }
     }
 
@@ -109,16 +141,432 @@ pub fn convert_emf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     emfplus_parser.parse();
 
     if !rendered_gdi && !emfplus_parser.has_detected_records() {
-        eprintln!("Warning: EMF rendering failed and no EMF+ records detected; output may be empty.");
+        log::warn!("EMF rendering failed and no EMF+ records detected; output may be empty.");
     }
 
     Ok(svg.finish())
 }
 
+/// Error returned by [`convert`] (and [`crate::wmf::convert`]), the plain-Rust
+/// conversion entry point used outside of the PyO3 boundary -- e.g. from a
+/// standalone Rust binary embedding this crate, or from unit tests that don't
+/// want to link a Python interpreter. The `#[pyfunction]` wrappers in `lib.rs`
+/// still take the `Box<dyn std::error::Error>`-returning functions above when
+/// they need format-specific options; this is the no-frills, always-available
+/// path.
+#[derive(Debug)]
+pub enum ConvertError {
+    /// `data` isn't recognized as the format this function converts.
+    UnsupportedFormat,
+    /// `data` (or a record header's claimed size) ends before a required field
+    /// could be read.
+    Truncated,
+    /// A record type this converter doesn't know how to draw.
+    UnsupportedRecord(u32),
+    /// An I/O error surfaced while decoding embedded image data.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::UnsupportedFormat => write!(f, "unsupported metafile format"),
+            ConvertError::Truncated => write!(f, "metafile data is truncated"),
+            ConvertError::UnsupportedRecord(record_type) => {
+                write!(f, "unsupported record type {}", record_type)
+            }
+            ConvertError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvertError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConvertError {
+    fn from(e: std::io::Error) -> Self {
+        ConvertError::Io(e)
+    }
+}
+
+/// Classify a `Box<dyn std::error::Error>` raised by the parsing/conversion
+/// functions above into a [`ConvertError`], for callers that need a matchable
+/// error type instead of a display string.
+fn classify_error(err: Box<dyn std::error::Error>) -> ConvertError {
+    match err.downcast::<std::io::Error>() {
+        Ok(io_err) => ConvertError::Io(*io_err),
+        Err(_) => ConvertError::Truncated,
+    }
+}
+
+/// Convert EMF data to SVG string, at the default coordinate precision, using
+/// a matchable [`ConvertError`] instead of `Box<dyn std::error::Error>`. This
+/// is the entry point to reach for from outside the PyO3 boundary.
+pub fn convert(data: &[u8]) -> Result<String, ConvertError> {
+    if !is_emf_format(data) {
+        return Err(ConvertError::UnsupportedFormat);
+    }
+    convert_emf_to_svg(data).map_err(classify_error)
+}
+
+/// Metadata returned alongside the SVG by `convert_emf_to_svg_with_meta`, for callers
+/// that need to size a placement box or flag overly complex graphics for rasterization
+/// without re-parsing the SVG they were just handed.
+pub struct ConversionMeta {
+    pub view_box: String,
+    pub width_mm: f64,
+    pub height_mm: f64,
+    /// Total number of emitted `<path>`/`<text>`/`<image>` elements.
+    pub element_count: usize,
+    /// Distinct EMF record type names encountered while parsing, sorted.
+    pub record_types: Vec<String>,
+}
+
+/// Convert EMF data to SVG, additionally returning `ConversionMeta` (viewBox, physical
+/// size in mm, emitted element count, and the distinct record types encountered) from
+/// the same parsing pass instead of a second one. Used by the document assembler to
+/// size the placement box and flag overly complex graphics for rasterization.
+pub fn convert_emf_to_svg_with_meta(data: &[u8]) -> Result<(String, ConversionMeta), Box<dyn std::error::Error>> {
+    if !is_emf_format(data) {
+        return Err("Invalid EMF format".into());
+    }
+
+    let (_bounds_width, _bounds_height, frame_width_mm, frame_height_mm, header_size, initial_view_transform) = parse_emf_header(data)?;
+
+    let mut temp_svg = SvgWriter::new(100, 100);
+    let final_view_transform = match parse_emf_records(data, header_size, &mut temp_svg, initial_view_transform) {
+        Ok(transform) => transform,
+        Err(_) => initial_view_transform,
+    };
+
+    const MM_TO_PX: f64 = 3.779527559;
+    let frame_width_px = frame_width_mm * MM_TO_PX;
+    let frame_height_px = frame_height_mm * MM_TO_PX;
+    let svg_width_logical = final_view_transform.viewport_ext_x.max(1) as f64;
+    let svg_height_logical = final_view_transform.viewport_ext_y.max(1) as f64;
+    let scale_x = if final_view_transform.window_ext_x != 0 {
+        final_view_transform.viewport_ext_x as f64 / final_view_transform.window_ext_x as f64
+    } else {
+        1.0
+    };
+    let scale_y = if final_view_transform.window_ext_y != 0 {
+        final_view_transform.viewport_ext_y as f64 / final_view_transform.window_ext_y as f64
+    } else {
+        1.0
+    };
+    let max_possible_x = (final_view_transform.window_ext_x as f64 * scale_x).max(svg_width_logical);
+    let max_possible_y = (final_view_transform.window_ext_y as f64 * scale_y).max(svg_height_logical);
+    let svg_width = if frame_width_px > 0.0 && frame_width_px.is_finite() {
+        frame_width_px
+    } else {
+        max_possible_x.max(2000.0)
+    };
+    let svg_height = if frame_height_px > 0.0 && frame_height_px.is_finite() {
+        frame_height_px
+    } else {
+        max_possible_y.max(2000.0)
+    };
+    let width = normalize_dimension(svg_width);
+    let height = normalize_dimension(svg_height);
+
+    let mut svg = SvgWriter::with_precision(width, height, DEFAULT_COORD_PRECISION);
+
+    // Prefer EMF+ over the plain GDI fallback in a dual EMF/EMF+ metafile; see
+    // `scan_has_emf_plus`.
+    let has_emf_plus = scan_has_emf_plus(data, header_size);
+    let mut rendered_gdi = false;
+    if !has_emf_plus {
+        match parse_emf_records(data, header_size, &mut svg, initial_view_transform) {
+            Ok(_) => rendered_gdi = true,
+            Err(e) => log::warn!("EMF GDI parsing failed: {}", e),
+        }
+    }
+
+    let mut emfplus_parser = EmfPlusParser::new(data, &mut svg);
+    emfplus_parser.parse();
+
+    if !rendered_gdi && !emfplus_parser.has_detected_records() {
+        log::warn!("EMF rendering failed and no EMF+ records detected; output may be empty.");
+    }
+
+    let record_types = scan_record_types(data, header_size);
+    let (svg_content, svg_meta) = svg.finish_with_meta();
+    let (vb_x, vb_y, vb_w, vb_h) = svg_meta.view_box;
+
+    Ok((
+        svg_content,
+        ConversionMeta {
+            view_box: format!("{} {} {} {}", vb_x, vb_y, vb_w, vb_h),
+            width_mm: frame_width_mm,
+            height_mm: frame_height_mm,
+            element_count: svg_meta.path_count + svg_meta.text_count + svg_meta.image_count,
+            record_types,
+        },
+    ))
+}
+
+/// Cheap scan of record type/size fields (skipping payloads, same approach as
+/// `get_emf_info`'s EMF+ detection) to collect the distinct record type names present,
+/// without touching geometry.
+fn scan_record_types(data: &[u8], header_size: u32) -> Vec<String> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(header_size as u64);
+    let mut seen = std::collections::BTreeSet::new();
+
+    while cursor.position() + 8 <= data.len() as u64 {
+        let record_type = match cursor.read_u32::<LittleEndian>() {
+            Ok(v) => v,
+            Err(_) => break,
+        };
+        let record_size = match cursor.read_u32::<LittleEndian>() {
+            Ok(v) => v,
+            Err(_) => break,
+        };
+        if record_size < 8 {
+            break;
+        }
+        let data_size = record_size - 8;
+        if cursor.position() + data_size as u64 > data.len() as u64 {
+            break;
+        }
+        seen.insert(emf_records::get_record_type_name(record_type).to_string());
+        if record_type == emf_records::EMR_EOF {
+            break;
+        }
+        cursor.set_position(cursor.position() + data_size as u64);
+    }
+
+    seen.into_iter().collect()
+}
+
+/// Cheap scan (same approach as `get_emf_info`'s EMF+ detection) for whether the file
+/// carries an `EMR_COMMENT_EMFPLUS` record -- i.e. is a "dual" EMF/EMF+ metafile whose
+/// plain GDI records are only a fallback rendering for viewers that don't understand
+/// EMF+. Callers use this to skip that fallback and render the EMF+ content instead,
+/// per the format's own dual-mode preference, avoiding drawing both on top of each other.
+fn scan_has_emf_plus(data: &[u8], header_size: u32) -> bool {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(header_size as u64);
+
+    while cursor.position() + 8 <= data.len() as u64 {
+        let record_type = match cursor.read_u32::<LittleEndian>() {
+            Ok(v) => v,
+            Err(_) => break,
+        };
+        let record_size = match cursor.read_u32::<LittleEndian>() {
+            Ok(v) => v,
+            Err(_) => break,
+        };
+        if record_size < 8 {
+            break;
+        }
+        let data_size = record_size - 8;
+        if cursor.position() + data_size as u64 > data.len() as u64 {
+            break;
+        }
+        if record_type == emf_records::EMR_GDICOMMENT && data_size >= 8 {
+            let payload_start = cursor.position() as usize;
+            if payload_start + 8 <= data.len() && &data[payload_start + 4..payload_start + 8] == b"EMF+" {
+                return true;
+            }
+        }
+        if record_type == emf_records::EMR_EOF {
+            break;
+        }
+        cursor.set_position(cursor.position() + data_size as u64);
+    }
+
+    false
+}
+
+/// Convert EMF data to SVG, tolerating records that fail to parse or aren't supported
+/// instead of aborting the whole conversion. Returns the best-effort SVG alongside a list
+/// of human-readable warnings (one per skipped record, naming its type and byte offset) so
+/// callers can surface a diagnostics report to the user instead of an opaque failure.
+pub fn convert_emf_to_svg_lenient(data: &[u8]) -> Result<(String, Vec<String>), Box<dyn std::error::Error>> {
+    if !is_emf_format(data) {
+        return Err("Invalid EMF format".into());
+    }
+
+    let mut warnings = Vec::new();
+
+    let (_bounds_width, _bounds_height, frame_width_mm, frame_height_mm, header_size, initial_view_transform) = parse_emf_header(data)?;
+
+    let mut temp_svg = SvgWriter::new(100, 100);
+    let final_view_transform = parse_emf_records_lenient(data, header_size, &mut temp_svg, initial_view_transform, &mut warnings)
+        .unwrap_or(initial_view_transform);
+
+    const MM_TO_PX: f64 = 3.779527559;
+    let frame_width_px = frame_width_mm * MM_TO_PX;
+    let frame_height_px = frame_height_mm * MM_TO_PX;
+
+    let svg_width_logical = final_view_transform.viewport_ext_x.max(1) as f64;
+    let svg_height_logical = final_view_transform.viewport_ext_y.max(1) as f64;
+
+    let scale_x = if final_view_transform.window_ext_x != 0 {
+        final_view_transform.viewport_ext_x as f64 / final_view_transform.window_ext_x as f64
+    } else {
+        1.0
+    };
+    let scale_y = if final_view_transform.window_ext_y != 0 {
+        final_view_transform.viewport_ext_y as f64 / final_view_transform.window_ext_y as f64
+    } else {
+        1.0
+    };
+
+    let max_possible_x = (final_view_transform.window_ext_x as f64 * scale_x).max(svg_width_logical);
+    let max_possible_y = (final_view_transform.window_ext_y as f64 * scale_y).max(svg_height_logical);
+
+    let svg_width = if frame_width_px > 0.0 && frame_width_px.is_finite() {
+        frame_width_px
+    } else {
+        max_possible_x.max(2000.0)
+    };
+    let svg_height = if frame_height_px > 0.0 && frame_height_px.is_finite() {
+        frame_height_px
+    } else {
+        max_possible_y.max(2000.0)
+    };
+
+    let width = normalize_dimension(svg_width);
+    let height = normalize_dimension(svg_height);
+
+    let mut svg = SvgWriter::new(width, height);
+
+    // Reparse into the real, correctly-sized SvgWriter. Warnings are already known from the
+    // sizing pass above, so this second pass doesn't need to collect them again. Skip it
+    // entirely in a dual EMF/EMF+ metafile, preferring the EMF+ content below over its
+    // plain-GDI fallback rendering; see `scan_has_emf_plus`.
+    if !scan_has_emf_plus(data, header_size) {
+        let mut discard_warnings = Vec::new();
+        let _ = parse_emf_records_lenient(data, header_size, &mut svg, initial_view_transform, &mut discard_warnings);
+    }
+
+    let mut emfplus_parser = EmfPlusParser::new(data, &mut svg);
+    emfplus_parser.parse();
+
+    Ok((svg.finish(), warnings))
+}
+
+/// Frame size in pixels (at 96 DPI) plus the GDI record offset, for callers that only need
+/// page dimensions (e.g. the EMF-to-PDF converter) without the full SVG rendering pipeline.
+pub(crate) fn frame_size_px(data: &[u8]) -> Result<(f64, f64, u32), Box<dyn std::error::Error>> {
+    const MM_TO_PX: f64 = 3.779527559;
+    let (_, _, frame_width_mm, frame_height_mm, header_size, _) = parse_emf_header(data)?;
+    let width = normalize_dimension(frame_width_mm * MM_TO_PX);
+    let height = normalize_dimension(frame_height_mm * MM_TO_PX);
+    Ok((width as f64, height as f64, header_size))
+}
+
+/// Cheap-to-compute EMF metadata, for callers that need to decide on placement/sizing
+/// before committing to a full conversion. See `get_emf_info`.
+pub struct EmfInfo {
+    /// rclBounds, in device (logical) units
+    pub bounds: (i32, i32, i32, i32),
+    /// rclFrame, in HIMETRIC (0.01mm) units
+    pub frame: (i32, i32, i32, i32),
+    pub dpi: f64,
+    pub record_count: u32,
+    pub has_emf_plus: bool,
+}
+
+/// Read the EMF header plus a cheap scan over record type/size fields (skipping each
+/// record's payload) to report bounds, frame, DPI, record count, and whether any
+/// EMF+ comment records are present - without parsing geometry or building an SvgWriter.
+pub fn get_emf_info(data: &[u8]) -> Result<EmfInfo, Box<dyn std::error::Error>> {
+    if !is_emf_format(data) {
+        return Err("Invalid EMF format".into());
+    }
+    if data.len() < 88 {
+        return Err("EMF header too small".into());
+    }
+
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let mut cursor = Cursor::new(data);
+
+    let _record_type = cursor.read_u32::<LittleEndian>()?;
+    let header_size = cursor.read_u32::<LittleEndian>()?;
+
+    let bounds = (
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+    );
+    let frame = (
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+        cursor.read_i32::<LittleEndian>()?,
+    );
+
+    let _signature = cursor.read_u32::<LittleEndian>()?;
+    let _version = cursor.read_u32::<LittleEndian>()?;
+    let _bytes = cursor.read_u32::<LittleEndian>()?;
+    let _records = cursor.read_u32::<LittleEndian>()?;
+    let _handles = cursor.read_u16::<LittleEndian>()?;
+    let _reserved = cursor.read_u16::<LittleEndian>()?;
+    let _description_length = cursor.read_u32::<LittleEndian>()?;
+    let _description_offset = cursor.read_u32::<LittleEndian>()?;
+
+    let pixels_x = cursor.read_u32::<LittleEndian>()?;
+    let _pixels_y = cursor.read_u32::<LittleEndian>()?;
+    let millimeters_x = cursor.read_u32::<LittleEndian>()?;
+    let _millimeters_y = cursor.read_u32::<LittleEndian>()?;
+
+    let dpi = if millimeters_x > 0 && pixels_x > 0 {
+        pixels_x as f64 / (millimeters_x as f64 / 25.4)
+    } else {
+        96.0
+    };
+
+    // Cheap scan: walk record type/size only, skipping each payload, to count records
+    // and detect an EMF+ comment signature without touching geometry.
+    let mut scan_cursor = Cursor::new(data);
+    scan_cursor.set_position(header_size as u64);
+    let mut record_count: u32 = 0;
+    let mut has_emf_plus = false;
+
+    while scan_cursor.position() + 8 <= data.len() as u64 {
+        let record_type = scan_cursor.read_u32::<LittleEndian>()?;
+        let record_size = scan_cursor.read_u32::<LittleEndian>()?;
+        if record_size < 8 {
+            break;
+        }
+        let data_size = record_size - 8;
+        if scan_cursor.position() + data_size as u64 > data.len() as u64 {
+            break;
+        }
+        record_count += 1;
+
+        if record_type == emf_records::EMR_GDICOMMENT && data_size >= 8 {
+            let payload_start = scan_cursor.position() as usize;
+            if payload_start + 8 <= data.len() && &data[payload_start + 4..payload_start + 8] == b"EMF+" {
+                has_emf_plus = true;
+            }
+        }
+
+        if record_type == emf_records::EMR_EOF {
+            break;
+        }
+        scan_cursor.set_position(scan_cursor.position() + data_size as u64);
+    }
+
+    Ok(EmfInfo { bounds, frame, dpi, record_count, has_emf_plus })
+}
+
 /// Parse EMF header to extract dimensions, header size, and initial view transform
 /// Returns: (bounds_width, bounds_height, frame_width_mm, frame_height_mm, header_size, view_transform)
 /// frame_width_mm and frame_height_mm are in millimeters (converted from HIMETRIC 0.01mm units)
-fn parse_emf_header(data: &[u8]) -> Result<(f64, f64, f64, f64, u32, ViewTransform), Box<dyn std::error::Error>> {
+pub(crate) fn parse_emf_header(data: &[u8]) -> Result<(f64, f64, f64, f64, u32, ViewTransform), Box<dyn std::error::Error>> {
     if data.len() < 40 {
         return Err("EMF header too small".into());
     }
@@ -174,14 +622,22 @@ fn parse_emf_header(data: &[u8]) -> Result<(f64, f64, f64, f64, u32, ViewTransfo
     let _description_length = cursor.read_u32::<LittleEndian>()?;
     let _description_offset = cursor.read_u32::<LittleEndian>()?;
     
-    // Read pixel dimensions
-    let _pixels_x = cursor.read_u32::<LittleEndian>()?;
+    // Read pixel dimensions (szlDevice)
+    let pixels_x = cursor.read_u32::<LittleEndian>()?;
     let _pixels_y = cursor.read_u32::<LittleEndian>()?;
-    
-    // Read millimeter dimensions
-    let _millimeters_x = cursor.read_u32::<LittleEndian>()?;
+
+    // Read millimeter dimensions (szlMillimeters)
+    let millimeters_x = cursor.read_u32::<LittleEndian>()?;
     let _millimeters_y = cursor.read_u32::<LittleEndian>()?;
-    
+
+    // Device DPI implied by szlDevice/szlMillimeters; falls back to 96 (standard screen DPI)
+    // when the header doesn't carry usable device dimensions.
+    let device_dpi = if millimeters_x > 0 && pixels_x > 0 {
+        pixels_x as f64 / (millimeters_x as f64 / 25.4)
+    } else {
+        96.0
+    };
+
     // Initialize view transform from header
     // Default: viewport extents match frame dimensions, window extents match bounds
     let view_transform = ViewTransform {
@@ -193,8 +649,10 @@ fn parse_emf_header(data: &[u8]) -> Result<(f64, f64, f64, f64, u32, ViewTransfo
         viewport_org_y: 0,
         viewport_ext_x: (right - left),
         viewport_ext_y: (bottom - top),
+        world: WorldXform::identity(),
+        device_dpi,
     };
-    
+
     Ok((width, height, frame_width_mm, frame_height_mm, header_size, view_transform))
 }
 
@@ -257,6 +715,56 @@ struct FontInfo {
     strikeout: bool,
 }
 
+/// Affine world transform matrix (XFORM from EMR_SETWORLDTRANSFORM/EMR_MODIFYWORLDTRANSFORM)
+/// Maps (x, y) -> (m11*x + m21*y + dx, m12*x + m22*y + dy)
+#[derive(Clone, Copy, Debug)]
+struct WorldXform {
+    m11: f64,
+    m12: f64,
+    m21: f64,
+    m22: f64,
+    dx: f64,
+    dy: f64,
+}
+
+impl WorldXform {
+    fn identity() -> Self {
+        Self {
+            m11: 1.0,
+            m12: 0.0,
+            m21: 0.0,
+            m22: 1.0,
+            dx: 0.0,
+            dy: 0.0,
+        }
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.m11 * x + self.m21 * y + self.dx,
+            self.m12 * x + self.m22 * y + self.dy,
+        )
+    }
+
+    /// Multiply `self` by `other`, i.e. apply `self` first then `other`.
+    fn then(&self, other: &WorldXform) -> WorldXform {
+        WorldXform {
+            m11: self.m11 * other.m11 + self.m12 * other.m21,
+            m12: self.m11 * other.m12 + self.m12 * other.m22,
+            m21: self.m21 * other.m11 + self.m22 * other.m21,
+            m22: self.m21 * other.m12 + self.m22 * other.m22,
+            dx: self.dx * other.m11 + self.dy * other.m21 + other.dx,
+            dy: self.dx * other.m12 + self.dy * other.m22 + other.dy,
+        }
+    }
+}
+
+/// EMR_MODIFYWORLDTRANSFORM modes (iMode field)
+const MWT_IDENTITY: u32 = 1;
+const MWT_LEFTMULTIPLY: u32 = 2;
+const MWT_RIGHTMULTIPLY: u32 = 3;
+const MWT_SET: u32 = 4;
+
 /// Window and viewport transformation
 #[derive(Clone, Copy)]
 struct ViewTransform {
@@ -268,6 +776,10 @@ struct ViewTransform {
     viewport_org_y: i32,
     viewport_ext_x: i32,
     viewport_ext_y: i32,
+    world: WorldXform,
+    /// Device DPI from the EMF header (szlDevice/szlMillimeters), used to convert
+    /// LOGFONT character heights to pixels. Defaults to 96 (standard screen DPI).
+    device_dpi: f64,
 }
 
 impl ViewTransform {
@@ -281,62 +793,147 @@ impl ViewTransform {
             viewport_org_y: 0,
             viewport_ext_x: 1,
             viewport_ext_y: 1,
+            world: WorldXform::identity(),
+            device_dpi: 96.0,
         }
     }
-    
+
+    /// Apply EMR_SETWORLDTRANSFORM: the XFORM record always replaces the current matrix.
+    fn set_world_transform(&mut self, xform: WorldXform) {
+        self.world = xform;
+    }
+
+    /// Apply EMR_MODIFYWORLDTRANSFORM, combining `xform` with the current matrix per `mode`.
+    fn modify_world_transform(&mut self, xform: WorldXform, mode: u32) {
+        self.world = match mode {
+            MWT_IDENTITY => WorldXform::identity(),
+            MWT_LEFTMULTIPLY => xform.then(&self.world),
+            MWT_RIGHTMULTIPLY => self.world.then(&xform),
+            MWT_SET => xform,
+            _ => self.world,
+        };
+    }
+
     /// Transform logical coordinates to device coordinates
     fn transform(&self, x: f64, y: f64) -> (f64, f64) {
+        // World transform is applied in page space, before the window/viewport mapping.
+        let (x, y) = self.world.apply(x, y);
+
         // Calculate scale factors
         let scale_x = if self.window_ext_x != 0 {
             self.viewport_ext_x as f64 / self.window_ext_x as f64
         } else {
             1.0
         };
-        
+
         let scale_y = if self.window_ext_y != 0 {
             self.viewport_ext_y as f64 / self.window_ext_y as f64
         } else {
             1.0
         };
-        
+
         // Transform coordinates
         let device_x = (x - self.window_org_x as f64) * scale_x + self.viewport_org_x as f64;
         let device_y = (y - self.window_org_y as f64) * scale_y + self.viewport_org_y as f64;
-        
-        // Debug first few transforms
-        static mut DEBUG_COUNT: u32 = 0;
-        unsafe {
-            if DEBUG_COUNT < 3 {
-                eprintln!("Transform: logical=({}, {}) -> device=({}, {}), scale=({}, {}), window_ext=({}, {}), viewport_ext=({}, {})", 
-                    x, y, device_x, device_y, scale_x, scale_y, 
-                    self.window_ext_x, self.window_ext_y, 
-                    self.viewport_ext_x, self.viewport_ext_y);
-                DEBUG_COUNT += 1;
-            }
-        }
-        
+
         (device_x, device_y)
     }
+
+    /// Average window-to-viewport scale factor, used to carry logical pen widths into
+    /// device-space stroke widths.
+    fn scale(&self) -> f64 {
+        let scale_x = if self.window_ext_x != 0 {
+            self.viewport_ext_x as f64 / self.window_ext_x as f64
+        } else {
+            1.0
+        };
+        let scale_y = if self.window_ext_y != 0 {
+            self.viewport_ext_y as f64 / self.window_ext_y as f64
+        } else {
+            1.0
+        };
+        ((scale_x.abs() + scale_y.abs()) / 2.0).max(0.001)
+    }
+}
+
+/// Subset of the DC state saved/restored by EMR_SAVEDC/EMR_RESTOREDC
+#[derive(Clone)]
+struct DcState {
+    current_pen_color: u32,
+    current_pen_width: u32,
+    current_pen_style: u32,
+    current_pen_dashes: Option<Vec<u32>>,
+    current_brush_color: u32,
+    current_text_color: u32,
+    current_bk_color: u32,
+    current_font: Option<FontInfo>,
+    current_text_align: u32,
+    view_transform: ViewTransform,
+    clip_id: Option<String>,
+    poly_fill_mode: PolyFillMode,
+}
+
+/// GDI polygon fill mode, set via EMR_SETPOLYFILLMODE. Governs how
+/// self-intersecting or nested subpaths in EMR_POLYGON/EMR_POLYPOLYGON (and
+/// their 16-bit variants) are filled.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PolyFillMode {
+    /// Windows GDI default: alternating subpaths are holes (SVG "evenodd").
+    Alternate,
+    /// Every subpath fills regardless of winding direction (SVG "nonzero").
+    Winding,
+}
+
+impl PolyFillMode {
+    /// Map the record's `iMode` value (`WINDING` = 2, anything else treated
+    /// as the default `ALTERNATE` = 1) to a `PolyFillMode`.
+    fn from_emf(mode: u32) -> Self {
+        if mode == 2 {
+            PolyFillMode::Winding
+        } else {
+            PolyFillMode::Alternate
+        }
+    }
+
+    /// The SVG `fill-rule` value implementing this mode.
+    fn svg_fill_rule(self) -> &'static str {
+        match self {
+            PolyFillMode::Alternate => "evenodd",
+            PolyFillMode::Winding => "nonzero",
+        }
+    }
 }
 
 /// Graphics state for EMF parsing
 struct GraphicsState {
     current_pen_color: u32,      // ARGB
+    current_pen_width: u32,      // Logical units; 0 means a 1-pixel-wide cosmetic pen
+    current_pen_style: u32,      // PS_* bits
+    current_pen_dashes: Option<Vec<u32>>,
     current_brush_color: u32,    // ARGB
     current_text_color: u32,     // ARGB
     current_bk_color: u32,       // ARGB
     current_font: Option<FontInfo>,
+    /// TA_* bits set via EMR_SETTEXTALIGN, governing text-anchor and baseline
+    current_text_align: u32,
     pen_table: Vec<Option<PenInfo>>,
     brush_table: Vec<Option<BrushInfo>>,
     font_table: Vec<Option<FontInfo>>,
     current_path: CurrentPath,
     view_transform: ViewTransform,
+    dc_stack: Vec<DcState>,
+    /// id of the currently active SVG <clipPath>, if any clip is in effect
+    clip_id: Option<String>,
+    poly_fill_mode: PolyFillMode,
 }
 
 impl Default for GraphicsState {
     fn default() -> Self {
         Self {
             current_pen_color: 0xFF000000,      // Black
+            current_pen_width: 0,
+            current_pen_style: PS_SOLID,
+            current_pen_dashes: None,
             current_brush_color: 0xFFFFFFFF,  // White
             current_text_color: 0xFF000000,    // Black
             current_bk_color: 0xFFFFFFFF,     // White
@@ -348,21 +945,181 @@ impl Default for GraphicsState {
                 underline: false,
                 strikeout: false,
             }),
+            current_text_align: TA_LEFT | TA_TOP,
             pen_table: vec![None; 256],
             brush_table: vec![None; 256],
             font_table: vec![None; 256],
             current_path: CurrentPath::new(),
             view_transform: ViewTransform::new(),
+            dc_stack: Vec::new(),
+            clip_id: None,
+            poly_fill_mode: PolyFillMode::Alternate,
+        }
+    }
+}
+
+impl GraphicsState {
+    /// EMR_SAVEDC: push the restorable portion of the DC state
+    fn save_dc(&mut self) {
+        self.dc_stack.push(DcState {
+            current_pen_color: self.current_pen_color,
+            current_pen_width: self.current_pen_width,
+            current_pen_style: self.current_pen_style,
+            current_pen_dashes: self.current_pen_dashes.clone(),
+            current_brush_color: self.current_brush_color,
+            current_text_color: self.current_text_color,
+            current_bk_color: self.current_bk_color,
+            current_font: self.current_font.clone(),
+            current_text_align: self.current_text_align,
+            view_transform: self.view_transform,
+            clip_id: self.clip_id.clone(),
+            poly_fill_mode: self.poly_fill_mode,
+        });
+    }
+
+    /// EMR_RESTOREDC: pop the most recent saved DC state, if any.
+    /// `relative` is iRelative from the record; a value of -1 (the common case)
+    /// restores the last save. Negative values beyond the stack size clamp to
+    /// the oldest saved state, matching GDI's "restore to that state" behavior.
+    fn restore_dc(&mut self, relative: i32) {
+        if self.dc_stack.is_empty() {
+            return;
+        }
+        let keep = if relative < 0 {
+            let pop_count = relative.unsigned_abs() as usize;
+            self.dc_stack.len().saturating_sub(pop_count)
+        } else {
+            self.dc_stack.len()
+        };
+        if let Some(state) = self.dc_stack.get(keep).cloned() {
+            self.dc_stack.truncate(keep);
+            self.current_pen_color = state.current_pen_color;
+            self.current_pen_width = state.current_pen_width;
+            self.current_pen_style = state.current_pen_style;
+            self.current_pen_dashes = state.current_pen_dashes;
+            self.current_brush_color = state.current_brush_color;
+            self.current_text_color = state.current_text_color;
+            self.current_bk_color = state.current_bk_color;
+            self.current_font = state.current_font;
+            self.current_text_align = state.current_text_align;
+            self.view_transform = state.view_transform;
+            self.clip_id = state.clip_id;
+            self.poly_fill_mode = state.poly_fill_mode;
+        }
+    }
+
+    /// Device-space stroke width, dasharray, join style, and cap style for the currently
+    /// selected pen, or `None` if the pen is PS_NULL (no stroke should be drawn at all).
+    fn stroke_style(&self) -> Option<(f64, Option<String>, Option<&'static str>, Option<&'static str>)> {
+        if self.current_pen_style & PS_STYLE_MASK == PS_NULL {
+            return None;
         }
+        let width = (self.current_pen_width as f64 * self.view_transform.scale()).max(1.0);
+        let dasharray = pen_dasharray(self.current_pen_style, width, &self.current_pen_dashes);
+        let (linejoin, linecap) = pen_join_cap(self.current_pen_style);
+        Some((width, dasharray, linejoin, linecap))
     }
 }
 
+/// PS_STYLE pen style values (low nibble of PenStyle/elpPenStyle)
+const PS_STYLE_MASK: u32 = 0x0000_000F;
+const PS_SOLID: u32 = 0;
+const PS_DASH: u32 = 1;
+const PS_DOT: u32 = 2;
+const PS_DASHDOT: u32 = 3;
+const PS_DASHDOTDOT: u32 = 4;
+const PS_NULL: u32 = 5;
+const PS_USERSTYLE: u32 = 7;
+
+/// PS_TYPE bit (elpPenStyle bit 16): set for a geometric pen, which alone carries
+/// explicit join/cap styles; a cosmetic pen (the default, bit clear) always renders
+/// with GDI's flat cap and miter join, which happen to be SVG's own defaults.
+const PS_TYPE_MASK: u32 = 0x000F_0000;
+const PS_GEOMETRIC: u32 = 0x0001_0000;
+/// PS_ENDCAP bits (elpPenStyle bits 8-9)
+const PS_ENDCAP_MASK: u32 = 0x0000_0300;
+const PS_ENDCAP_SQUARE: u32 = 0x0000_0100;
+const PS_ENDCAP_FLAT: u32 = 0x0000_0200;
+/// PS_JOIN bits (elpPenStyle bits 12-13)
+const PS_JOIN_MASK: u32 = 0x0000_3000;
+const PS_JOIN_BEVEL: u32 = 0x0000_1000;
+const PS_JOIN_MITER: u32 = 0x0000_2000;
+
+/// TA_* text alignment bits set via EMR_SETTEXTALIGN (WinGDI.h). Horizontal bits
+/// (TA_LEFT/TA_RIGHT/TA_CENTER) select which edge of the text the reference point
+/// names; vertical bits (TA_TOP/TA_BOTTOM/TA_BASELINE) select which line of the
+/// text the reference point's y names.
+const TA_LEFT: u32 = 0x0000;
+const TA_RIGHT: u32 = 0x0002;
+const TA_CENTER: u32 = 0x0006;
+const TA_HORZ_MASK: u32 = 0x0006;
+const TA_TOP: u32 = 0x0000;
+const TA_BOTTOM: u32 = 0x0008;
+const TA_BASELINE: u32 = 0x0018;
+const TA_VERT_MASK: u32 = 0x0018;
+
+/// Map EMR_SETTEXTALIGN's TA_* bits to an SVG `text-anchor` and `dominant-baseline`.
+fn text_align_to_svg(align: u32) -> (Option<String>, Option<String>) {
+    let anchor = match align & TA_HORZ_MASK {
+        TA_CENTER => Some("middle".to_string()),
+        TA_RIGHT => Some("end".to_string()),
+        _ => None, // TA_LEFT is the SVG default ("start")
+    };
+    let baseline = match align & TA_VERT_MASK {
+        TA_BASELINE => None, // "alphabetic" is the SVG default, i.e. y is the baseline
+        TA_BOTTOM => Some("text-after-edge".to_string()),
+        _ => Some("hanging".to_string()), // TA_TOP: y names the top of the text
+    };
+    (anchor, baseline)
+}
+
 /// Pen information
 #[derive(Clone)]
 struct PenInfo {
     color: u32,  // ARGB
     width: u32,  // Width in logical units
-    style: u32,  // Pen style
+    style: u32,  // Pen style (PS_* bits, from LOGPEN/EXTLOGPEN elpPenStyle)
+    /// Explicit dash pattern (elpStyleEntry), present only for PS_USERSTYLE pens
+    /// created via EMR_EXTCREATEPEN
+    user_dashes: Option<Vec<u32>>,
+}
+
+/// Build an SVG `stroke-dasharray` value for a pen style, or `None` for a solid line.
+/// Dash/gap lengths are expressed as multiples of the stroke width, matching how GDI
+/// scales its built-in dash patterns to the pen's width.
+fn pen_dasharray(style: u32, width: f64, user_dashes: &Option<Vec<u32>>) -> Option<String> {
+    let w = width.max(1.0);
+    match style & PS_STYLE_MASK {
+        PS_DASH => Some(format!("{} {}", w * 3.0, w)),
+        PS_DOT => Some(format!("{} {}", w, w)),
+        PS_DASHDOT => Some(format!("{} {} {} {}", w * 3.0, w, w, w)),
+        PS_DASHDOTDOT => Some(format!("{} {} {} {} {} {}", w * 3.0, w, w, w, w, w)),
+        PS_USERSTYLE => user_dashes.as_ref().map(|dashes| {
+            dashes.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(" ")
+        }),
+        _ => None,
+    }
+}
+
+/// Map a geometric pen's PS_JOIN/PS_ENDCAP bits to SVG `stroke-linejoin`/`stroke-linecap`
+/// values. Cosmetic pens (PS_GEOMETRIC bit clear) ignore these bits in GDI and always
+/// render flat/miter, matching SVG's own defaults, so this returns `None` for them and
+/// lets the attributes go unset.
+fn pen_join_cap(style: u32) -> (Option<&'static str>, Option<&'static str>) {
+    if style & PS_TYPE_MASK != PS_GEOMETRIC {
+        return (None, None);
+    }
+    let linejoin = match style & PS_JOIN_MASK {
+        PS_JOIN_BEVEL => Some("bevel"),
+        PS_JOIN_MITER => Some("miter"),
+        _ => Some("round"), // PS_JOIN_ROUND (0)
+    };
+    let linecap = match style & PS_ENDCAP_MASK {
+        PS_ENDCAP_SQUARE => Some("square"),
+        PS_ENDCAP_FLAT => Some("butt"),
+        _ => Some("round"), // PS_ENDCAP_ROUND (0)
+    };
+    (linejoin, linecap)
 }
 
 /// Brush information
@@ -419,168 +1176,370 @@ fn parse_emf_records(data: &[u8], header_size: u32, svg: &mut SvgWriter, initial
         
         record_count += 1;
         
-        // Debug: log record types (first 20 records)
+        // Log record types (first 20 records)
         if record_count <= 20 {
-            eprintln!("Record {}: type={} ({}) size={}", 
-                     record_count, 
+            log::debug!("Record {}: type={} ({}) size={}",
+                     record_count,
                      record_type,
                      emf_records::get_record_type_name(record_type),
                      record_size);
         }
         
         // Parse record based on type
-        match record_type {
-            emf_records::EMR_SETWINDOWORGEX => {
-                if data_size >= 8 {
-                    state.view_transform.window_org_x = cursor.read_i32::<LittleEndian>()?;
-                    state.view_transform.window_org_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETWINDOWORGEX: ({}, {})", state.view_transform.window_org_x, state.view_transform.window_org_y);
-                }
-            }
-            emf_records::EMR_SETWINDOWEXTEX => {
-                if data_size >= 8 {
-                    state.view_transform.window_ext_x = cursor.read_i32::<LittleEndian>()?;
-                    state.view_transform.window_ext_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETWINDOWEXTEX: ({}, {})", state.view_transform.window_ext_x, state.view_transform.window_ext_y);
-                }
-            }
-            emf_records::EMR_SETVIEWPORTORGEX => {
-                if data_size >= 8 {
-                    state.view_transform.viewport_org_x = cursor.read_i32::<LittleEndian>()?;
-                    state.view_transform.viewport_org_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETVIEWPORTORGEX: ({}, {})", state.view_transform.viewport_org_x, state.view_transform.viewport_org_y);
-                }
-            }
-            emf_records::EMR_SETVIEWPORTEXTEX => {
-                if data_size >= 8 {
-                    state.view_transform.viewport_ext_x = cursor.read_i32::<LittleEndian>()?;
-                    state.view_transform.viewport_ext_y = cursor.read_i32::<LittleEndian>()?;
-                    eprintln!("SETVIEWPORTEXTEX: ({}, {})", state.view_transform.viewport_ext_x, state.view_transform.viewport_ext_y);
-                }
-            }
-            emf_records::EMR_SETTEXTCOLOR => {
-                if data_size >= 4 {
-                    state.current_text_color = cursor.read_u32::<LittleEndian>()?;
-                }
-            }
-            emf_records::EMR_SETBKCOLOR => {
-                if data_size >= 4 {
-                    state.current_bk_color = cursor.read_u32::<LittleEndian>()?;
-                }
-            }
-            emf_records::EMR_CREATEPEN => {
-                handle_createpen(&mut cursor, &mut state, data_size)?;
-            }
-            emf_records::EMR_CREATEBRUSHINDIRECT => {
-                handle_createbrushindirect(&mut cursor, &mut state, data_size)?;
-            }
-            emf_records::EMR_SELECTOBJECT => {
-                handle_selectobject(&mut cursor, &mut state, data_size)?;
-            }
-            emf_records::EMR_SELECTPALETTE => {
-                // Palette selection - skip for now
-            }
-            emf_records::EMR_DELETEOBJECT => {
-                handle_deleteobject(&mut cursor, &mut state, data_size)?;
-            }
-            emf_records::EMR_RECTANGLE => {
-                handle_rectangle(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_ELLIPSE => {
-                handle_ellipse(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYLINE => {
-                handle_polyline(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYGON => {
-                handle_polygon(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYLINE16 => {
-                handle_polyline16(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYGON16 => {
-                handle_polygon16(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYPOLYLINE => {
-                handle_polypolyline(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYPOLYGON => {
-                handle_polypolygon(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYPOLYLINE16 => {
-                handle_polypolyline16(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_POLYPOLYGON16 => {
-                handle_polypolygon16(&mut cursor, svg, &state, data_size)?;
-            }
-            emf_records::EMR_BEGINPATH => {
-                state.current_path.begin();
-            }
-            emf_records::EMR_ENDPATH => {
-                // Path ended - don't render yet, wait for FILLPATH or STROKEPATH
-            }
-            emf_records::EMR_CLOSEFIGURE => {
-                state.current_path.close();
+        dispatch_emf_record(record_type, data_size, &mut cursor, &mut state, svg)?;
+
+        // Ensure cursor is at the end of the record
+        cursor.set_position(record_end as u64);
+    }
+
+    if record_count > 0 {
+        log::debug!("Parsed {} EMF records", record_count);
+    }
+
+    Ok(state.view_transform)
+}
+
+/// Parse EMF records like `parse_emf_records`, but never aborts on a single bad or
+/// unsupported record: failures and unrecognized record types are recorded as warnings
+/// (record type name plus byte offset) and parsing continues with the next record. Used
+/// by `convert_emf_to_svg_lenient` to produce a best-effort SVG alongside a diagnostics list.
+fn parse_emf_records_lenient(
+    data: &[u8],
+    header_size: u32,
+    svg: &mut SvgWriter,
+    initial_view_transform: ViewTransform,
+    warnings: &mut Vec<String>,
+) -> Result<ViewTransform, Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(header_size as u64);
+
+    let mut state = GraphicsState::default();
+    state.view_transform = initial_view_transform;
+
+    while cursor.position() < data.len() as u64 {
+        if cursor.position() + 8 > data.len() as u64 {
+            break;
+        }
+
+        let record_offset = cursor.position();
+        let record_type = cursor.read_u32::<LittleEndian>()?;
+        let record_size = cursor.read_u32::<LittleEndian>()?;
+
+        if record_size < 8 {
+            break;
+        }
+
+        let data_size = record_size - 8;
+        if cursor.position() + data_size as u64 > data.len() as u64 {
+            break;
+        }
+
+        let record_end = cursor.position() as usize + data_size as usize;
+
+        if record_type == emf_records::EMR_GDICOMMENT {
+            cursor.set_position(record_end as u64);
+            continue;
+        }
+        if record_type == emf_records::EMR_EOF {
+            break;
+        }
+
+        match dispatch_emf_record(record_type, data_size, &mut cursor, &mut state, svg) {
+            Ok(true) => {}
+            Ok(false) => {
+                warnings.push(format!(
+                    "Unsupported record type {} ({}) at offset {}",
+                    record_type,
+                    emf_records::get_record_type_name(record_type),
+                    record_offset
+                ));
             }
-            emf_records::EMR_MOVETOEX => {
-                handle_movetoex(&mut cursor, &mut state, data_size)?;
+            Err(e) => {
+                warnings.push(format!(
+                    "Skipped record type {} ({}) at offset {}: {}",
+                    record_type,
+                    emf_records::get_record_type_name(record_type),
+                    record_offset,
+                    e
+                ));
             }
-            emf_records::EMR_LINETO => {
-                handle_lineto(&mut cursor, &mut state, data_size)?;
+        }
+
+        cursor.set_position(record_end as u64);
+    }
+
+    Ok(state.view_transform)
+}
+
+/// Dispatch a single EMF record to its handler. Returns `Ok(true)` if the record type is
+/// recognized (even if skipped as a no-op, like EMR_BEGINPATH), `Ok(false)` if it is not
+/// handled at all, and `Err` if a recognized handler failed to parse its payload.
+fn dispatch_emf_record(
+    record_type: u32,
+    data_size: u32,
+    cursor: &mut Cursor<&[u8]>,
+    state: &mut GraphicsState,
+    svg: &mut SvgWriter,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    match record_type {
+        emf_records::EMR_SETWINDOWORGEX => {
+            if data_size >= 8 {
+                state.view_transform.window_org_x = cursor.read_i32::<LittleEndian>()?;
+                state.view_transform.window_org_y = cursor.read_i32::<LittleEndian>()?;
+                log::trace!("SETWINDOWORGEX: ({}, {})", state.view_transform.window_org_x, state.view_transform.window_org_y);
             }
-            emf_records::EMR_POLYBEZIERTO => {
-                handle_polybezierto(&mut cursor, &mut state, data_size)?;
+        }
+        emf_records::EMR_SETWINDOWEXTEX => {
+            if data_size >= 8 {
+                state.view_transform.window_ext_x = cursor.read_i32::<LittleEndian>()?;
+                state.view_transform.window_ext_y = cursor.read_i32::<LittleEndian>()?;
+                log::trace!("SETWINDOWEXTEX: ({}, {})", state.view_transform.window_ext_x, state.view_transform.window_ext_y);
             }
-            emf_records::EMR_POLYBEZIERTO16 => {
-                handle_polybezierto16(&mut cursor, &mut state, data_size)?;
+        }
+        emf_records::EMR_SETVIEWPORTORGEX => {
+            if data_size >= 8 {
+                state.view_transform.viewport_org_x = cursor.read_i32::<LittleEndian>()?;
+                state.view_transform.viewport_org_y = cursor.read_i32::<LittleEndian>()?;
+                log::trace!("SETVIEWPORTORGEX: ({}, {})", state.view_transform.viewport_org_x, state.view_transform.viewport_org_y);
             }
-            emf_records::EMR_FILLPATH => {
-                handle_fillpath(&mut cursor, svg, &mut state, data_size)?;
+        }
+        emf_records::EMR_SETVIEWPORTEXTEX => {
+            if data_size >= 8 {
+                state.view_transform.viewport_ext_x = cursor.read_i32::<LittleEndian>()?;
+                state.view_transform.viewport_ext_y = cursor.read_i32::<LittleEndian>()?;
+                log::trace!("SETVIEWPORTEXTEX: ({}, {})", state.view_transform.viewport_ext_x, state.view_transform.viewport_ext_y);
             }
-            emf_records::EMR_STROKEPATH => {
-                handle_strokepath(&mut cursor, svg, &mut state, data_size)?;
+        }
+        emf_records::EMR_SAVEDC => {
+            state.save_dc();
+        }
+        emf_records::EMR_RESTOREDC => {
+            if data_size >= 4 {
+                let relative = cursor.read_i32::<LittleEndian>()?;
+                state.restore_dc(relative);
             }
-            emf_records::EMR_STROKEANDFILLPATH => {
-                handle_strokeandfillpath(&mut cursor, svg, &mut state, data_size)?;
+        }
+        emf_records::EMR_SETWORLDTRANSFORM => {
+            if data_size >= 24 {
+                state.view_transform.set_world_transform(read_xform(cursor)?);
             }
-            emf_records::EMR_EXTCREATEFONTINDIRECTW => {
-                handle_extcreatefontindirectw(&mut cursor, &mut state, data_size)?;
+        }
+        emf_records::EMR_MODIFYWORLDTRANSFORM => {
+            if data_size >= 28 {
+                let xform = read_xform(cursor)?;
+                let mode = cursor.read_u32::<LittleEndian>()?;
+                state.view_transform.modify_world_transform(xform, mode);
             }
-            emf_records::EMR_EXTTEXTOUTA => {
-                handle_exttextouta(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_INTERSECTCLIPRECT => {
+            if data_size >= 16 {
+                let left = cursor.read_i32::<LittleEndian>()? as f64;
+                let top = cursor.read_i32::<LittleEndian>()? as f64;
+                let right = cursor.read_i32::<LittleEndian>()? as f64;
+                let bottom = cursor.read_i32::<LittleEndian>()? as f64;
+                let (x1, y1) = state.view_transform.transform(left, top);
+                let (x2, y2) = state.view_transform.transform(right, bottom);
+                let x = x1.min(x2);
+                let y = y1.min(y2);
+                let id = svg.define_clip_rect(x, y, (x2 - x1).abs(), (y2 - y1).abs());
+                // GDI intersects with the current clip region; since nested SVG
+                // clip-path references are not additive, the new clip supersedes
+                // the old one here (acceptable for the common single-level case).
+                state.clip_id = Some(id);
             }
-            emf_records::EMR_EXTTEXTOUTW => {
-                handle_exttextoutw(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_EXCLUDECLIPRECT => {
+            if data_size >= 16 {
+                let left = cursor.read_i32::<LittleEndian>()? as f64;
+                let top = cursor.read_i32::<LittleEndian>()? as f64;
+                let right = cursor.read_i32::<LittleEndian>()? as f64;
+                let bottom = cursor.read_i32::<LittleEndian>()? as f64;
+                let (x1, y1) = state.view_transform.transform(left, top);
+                let (x2, y2) = state.view_transform.transform(right, bottom);
+                // Outer bound large enough to cover any realistic device area, with the
+                // excluded rect cut out via the even-odd fill rule.
+                const OUTER: f64 = 1_000_000.0;
+                let path_d = format!(
+                    "M {} {} L {} {} L {} {} L {} {} Z M {} {} L {} {} L {} {} L {} {} Z",
+                    -OUTER, -OUTER, OUTER, -OUTER, OUTER, OUTER, -OUTER, OUTER,
+                    x1.min(x2), y1.min(y2), x1.max(x2), y1.min(y2), x1.max(x2), y1.max(y2), x1.min(x2), y1.max(y2)
+                );
+                let id = svg.define_clip_path(&path_d, true);
+                state.clip_id = Some(id);
             }
-            emf_records::EMR_POLYTEXTOUTA => {
-                handle_polytextouta(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_SELECTCLIPPATH => {
+            // iMode (RGN_AND/OR/etc.) is not modeled; the currently built path
+            // (from BEGINPATH/ENDPATH) becomes the new clip region.
+            if data_size >= 4 {
+                let _mode = cursor.read_u32::<LittleEndian>()?;
             }
-            emf_records::EMR_POLYTEXTOUTW => {
-                handle_polytextoutw(&mut cursor, svg, &state, data_size)?;
+            let path_data = state.current_path.end();
+            if !path_data.is_empty() {
+                let id = svg.define_clip_path(&path_data, false);
+                state.clip_id = Some(id);
             }
-            emf_records::EMR_BITBLT => {
-                handle_bitblt(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_SETTEXTCOLOR => {
+            if data_size >= 4 {
+                state.current_text_color = cursor.read_u32::<LittleEndian>()?;
             }
-            emf_records::EMR_STRETCHBLT => {
-                handle_stretchblt(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_SETTEXTALIGN => {
+            if data_size >= 4 {
+                state.current_text_align = cursor.read_u32::<LittleEndian>()?;
             }
-            emf_records::EMR_STRETCHDIBITS => {
-                handle_stretchdibits(&mut cursor, svg, &state, data_size)?;
+        }
+        emf_records::EMR_SETBKCOLOR => {
+            if data_size >= 4 {
+                state.current_bk_color = cursor.read_u32::<LittleEndian>()?;
             }
-            _ => {
-                // Unknown or unsupported record type - skip
+        }
+        emf_records::EMR_SETPOLYFILLMODE => {
+            if data_size >= 4 {
+                let mode = cursor.read_u32::<LittleEndian>()?;
+                state.poly_fill_mode = PolyFillMode::from_emf(mode);
             }
         }
-        
-        // Ensure cursor is at the end of the record
-        cursor.set_position(record_end as u64);
-    }
-    
-    if record_count > 0 {
-        eprintln!("Parsed {} EMF records", record_count);
+        emf_records::EMR_CREATEPEN => {
+            handle_createpen(cursor, state, data_size)?;
+        }
+        emf_records::EMR_EXTCREATEPEN => {
+            handle_extcreatepen(cursor, state, data_size)?;
+        }
+        emf_records::EMR_CREATEBRUSHINDIRECT => {
+            handle_createbrushindirect(cursor, state, data_size)?;
+        }
+        emf_records::EMR_SELECTOBJECT => {
+            handle_selectobject(cursor, state, data_size)?;
+        }
+        emf_records::EMR_SELECTPALETTE => {
+            // Palette selection - skip for now
+        }
+        emf_records::EMR_DELETEOBJECT => {
+            handle_deleteobject(cursor, state, data_size)?;
+        }
+        emf_records::EMR_RECTANGLE => {
+            handle_rectangle(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_ELLIPSE => {
+            handle_ellipse(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYLINE => {
+            handle_polyline(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYGON => {
+            handle_polygon(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYLINE16 => {
+            handle_polyline16(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYGON16 => {
+            handle_polygon16(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYPOLYLINE => {
+            handle_polypolyline(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYPOLYGON => {
+            handle_polypolygon(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYPOLYLINE16 => {
+            handle_polypolyline16(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYPOLYGON16 => {
+            handle_polypolygon16(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_BEGINPATH => {
+            state.current_path.begin();
+        }
+        emf_records::EMR_ENDPATH => {
+            // Path ended - don't render yet, wait for FILLPATH or STROKEPATH
+        }
+        emf_records::EMR_CLOSEFIGURE => {
+            state.current_path.close();
+        }
+        emf_records::EMR_MOVETOEX => {
+            handle_movetoex(cursor, state, data_size)?;
+        }
+        emf_records::EMR_LINETO => {
+            handle_lineto(cursor, state, data_size)?;
+        }
+        emf_records::EMR_POLYBEZIER => {
+            handle_polybezier(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYBEZIER16 => {
+            handle_polybezier16(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYBEZIERTO => {
+            handle_polybezierto(cursor, state, data_size)?;
+        }
+        emf_records::EMR_POLYBEZIERTO16 => {
+            handle_polybezierto16(cursor, state, data_size)?;
+        }
+        emf_records::EMR_FILLPATH => {
+            handle_fillpath(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_STROKEPATH => {
+            handle_strokepath(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_STROKEANDFILLPATH => {
+            handle_strokeandfillpath(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_EXTCREATEFONTINDIRECTW => {
+            handle_extcreatefontindirectw(cursor, state, data_size)?;
+        }
+        emf_records::EMR_EXTTEXTOUTA => {
+            handle_exttextouta(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_EXTTEXTOUTW => {
+            handle_exttextoutw(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYTEXTOUTA => {
+            handle_polytextouta(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_POLYTEXTOUTW => {
+            handle_polytextoutw(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_BITBLT => {
+            handle_bitblt(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_STRETCHBLT => {
+            handle_stretchblt(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_STRETCHDIBITS => {
+            handle_stretchdibits(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_SETDIBITSTODEVICE => {
+            handle_setdibitstodevice(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_ALPHABLEND => {
+            handle_alphablend(cursor, svg, state, data_size)?;
+        }
+        emf_records::EMR_GRADIENTFILL => {
+            handle_gradientfill(cursor, svg, state, data_size)?;
+        }
+        _ => {
+            return Ok(false);
+        }
     }
-    
-    Ok(state.view_transform)
+
+    Ok(true)
+}
+
+/// Read an XFORM structure (6 consecutive 32-bit floats: eM11, eM12, eM21, eM22, eDx, eDy)
+fn read_xform(cursor: &mut Cursor<&[u8]>) -> Result<WorldXform, Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    Ok(WorldXform {
+        m11: cursor.read_f32::<LittleEndian>()? as f64,
+        m12: cursor.read_f32::<LittleEndian>()? as f64,
+        m21: cursor.read_f32::<LittleEndian>()? as f64,
+        m22: cursor.read_f32::<LittleEndian>()? as f64,
+        dx: cursor.read_f32::<LittleEndian>()? as f64,
+        dy: cursor.read_f32::<LittleEndian>()? as f64,
+    })
 }
 
 /// Handle EMR_RECTANGLE record
@@ -611,7 +1570,7 @@ fn handle_rectangle(
     let height = (y2 - y1).abs();
     
     let color = argb_to_svg_color(state.current_brush_color);
-    svg.add_rect(x, y, width, height, Some(&color), None);
+    svg.add_rect_clipped(x, y, width, height, Some(&color), None, state.clip_id.as_deref());
     
     Ok(())
 }
@@ -653,11 +1612,152 @@ fn handle_ellipse(
                        cx, cy, rx, rx, ry, rx * 2.0, rx, ry, rx * 2.0);
     
     let color = argb_to_svg_color(state.current_brush_color);
-    svg.add_path(&path, Some(&color), None);
+    svg.add_path_clipped(&path, Some(&color), None, state.clip_id.as_deref());
     
     Ok(())
 }
 
+/// A TRIVERTEX entry from an EMR_GRADIENTFILL record: position plus a color with
+/// 16-bit channels (only the high byte is significant; the low byte is reserved/0).
+struct GradientVertex {
+    x: f64,
+    y: f64,
+    color: String,
+}
+
+fn read_color16(cursor: &mut Cursor<&[u8]>) -> Result<u8, Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    Ok((cursor.read_u16::<LittleEndian>()? >> 8) as u8)
+}
+
+/// Handle EMR_GRADIENTFILL record: rectangle mode becomes a two-stop `<linearGradient>`,
+/// triangle mode is approximated as a flat fill averaged from each triangle's vertex colors
+/// (true mesh gradients have no robust SVG equivalent, and aren't the common case here).
+fn handle_gradientfill(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 28 {
+        return Ok(());
+    }
+
+    // rclBounds - not needed beyond validating the record is well-formed
+    let _left = cursor.read_i32::<LittleEndian>()?;
+    let _top = cursor.read_i32::<LittleEndian>()?;
+    let _right = cursor.read_i32::<LittleEndian>()?;
+    let _bottom = cursor.read_i32::<LittleEndian>()?;
+
+    let vertex_count = cursor.read_u32::<LittleEndian>()?;
+    let mesh_count = cursor.read_u32::<LittleEndian>()?;
+    let mode = cursor.read_u32::<LittleEndian>()?;
+
+    if vertex_count == 0 || vertex_count > 10_000 || mesh_count > 10_000 {
+        return Ok(());
+    }
+
+    let mut vertices = Vec::with_capacity(vertex_count as usize);
+    for _ in 0..vertex_count {
+        if cursor.position() as usize + 16 > cursor.get_ref().len() {
+            return Ok(());
+        }
+        let vx = cursor.read_i32::<LittleEndian>()? as f64;
+        let vy = cursor.read_i32::<LittleEndian>()? as f64;
+        let r = read_color16(cursor)?;
+        let g = read_color16(cursor)?;
+        let b = read_color16(cursor)?;
+        let _a = read_color16(cursor)?;
+        let (tx, ty) = state.view_transform.transform(vx, vy);
+        vertices.push(GradientVertex { x: tx, y: ty, color: format!("#{:02x}{:02x}{:02x}", r, g, b) });
+    }
+
+    match mode {
+        emf_records::GRADIENT_FILL_RECT_H | emf_records::GRADIENT_FILL_RECT_V => {
+            let horizontal = mode == emf_records::GRADIENT_FILL_RECT_H;
+            for _ in 0..mesh_count {
+                if cursor.position() as usize + 8 > cursor.get_ref().len() {
+                    break;
+                }
+                let upper_left = cursor.read_u32::<LittleEndian>()? as usize;
+                let lower_right = cursor.read_u32::<LittleEndian>()? as usize;
+                if upper_left >= vertices.len() || lower_right >= vertices.len() {
+                    continue;
+                }
+                let v1 = &vertices[upper_left];
+                let v2 = &vertices[lower_right];
+                let x = v1.x.min(v2.x);
+                let y = v1.y.min(v2.y);
+                let width = (v2.x - v1.x).abs();
+                let height = (v2.y - v1.y).abs();
+                let (gx1, gy1, gx2, gy2) = if horizontal {
+                    (v1.x, y, v2.x, y)
+                } else {
+                    (x, v1.y, x, v2.y)
+                };
+                let gradient_id = svg.define_linear_gradient(
+                    gx1, gy1, gx2, gy2,
+                    vec![(0.0, v1.color.clone()), (1.0, v2.color.clone())],
+                );
+                svg.add_rect_clipped(x, y, width, height, Some(&format!("url(#{gradient_id})")), None, state.clip_id.as_deref());
+            }
+        }
+        emf_records::GRADIENT_FILL_TRIANGLE => {
+            for _ in 0..mesh_count {
+                if cursor.position() as usize + 12 > cursor.get_ref().len() {
+                    break;
+                }
+                let i1 = cursor.read_u32::<LittleEndian>()? as usize;
+                let i2 = cursor.read_u32::<LittleEndian>()? as usize;
+                let i3 = cursor.read_u32::<LittleEndian>()? as usize;
+                if i1 >= vertices.len() || i2 >= vertices.len() || i3 >= vertices.len() {
+                    continue;
+                }
+                let (v1, v2, v3) = (&vertices[i1], &vertices[i2], &vertices[i3]);
+                let avg_color = average_hex_colors(&[&v1.color, &v2.color, &v3.color]);
+                let path = format!("M {} {} L {} {} L {} {} Z", v1.x, v1.y, v2.x, v2.y, v3.x, v3.y);
+                svg.add_path_clipped(&path, Some(&avg_color), None, state.clip_id.as_deref());
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Average a set of `#rrggbb` colors channel-wise, for the triangle-gradient flat-fill fallback
+fn average_hex_colors(colors: &[&String]) -> String {
+    let mut r_sum = 0u32;
+    let mut g_sum = 0u32;
+    let mut b_sum = 0u32;
+    for color in colors {
+        let bytes = u32::from_str_radix(&color[1..], 16).unwrap_or(0);
+        r_sum += (bytes >> 16) & 0xFF;
+        g_sum += (bytes >> 8) & 0xFF;
+        b_sum += bytes & 0xFF;
+    }
+    let n = colors.len() as u32;
+    format!("#{:02x}{:02x}{:02x}", r_sum / n, g_sum / n, b_sum / n)
+}
+
+/// Stroke a path with the currently selected pen, honoring its width and dash style, or
+/// draw nothing at all for a PS_NULL pen. `fill` is passed through unchanged so this also
+/// covers EMR_STROKEANDFILLPATH, where a null pen still leaves the fill intact.
+fn stroke_path(svg: &mut SvgWriter, path_data: &str, fill: Option<&str>, pen_color: &str, state: &GraphicsState) {
+    match state.stroke_style() {
+        Some((width, dasharray, linejoin, linecap)) => {
+            svg.add_path_stroke_join_clipped(path_data, fill, Some(pen_color), Some(width), dasharray.as_deref(), linejoin, linecap, state.clip_id.as_deref(), None);
+        }
+        None => {
+            if let Some(fill_color) = fill {
+                svg.add_path_clipped(path_data, Some(fill_color), None, state.clip_id.as_deref());
+            }
+        }
+    }
+}
+
 /// Handle EMR_POLYLINE record
 fn handle_polyline(
     cursor: &mut Cursor<&[u8]>,
@@ -704,8 +1804,8 @@ fn handle_polyline(
     }
     
     let color = argb_to_svg_color(state.current_pen_color);
-    svg.add_path(&path, None, Some(&color));
-    
+    stroke_path(svg, &path, None, &color, state);
+
     Ok(())
 }
 
@@ -756,7 +1856,7 @@ fn handle_polygon(
     path.push_str(" Z");
     
     let color = argb_to_svg_color(state.current_brush_color);
-    svg.add_path(&path, Some(&color), None);
+    svg.add_path_filled_clipped(&path, Some(&color), state.clip_id.as_deref(), state.poly_fill_mode.svg_fill_rule());
     
     Ok(())
 }
@@ -807,8 +1907,8 @@ fn handle_polyline16(
     }
     
     let color = argb_to_svg_color(state.current_pen_color);
-    svg.add_path(&path, None, Some(&color));
-    
+    stroke_path(svg, &path, None, &color, state);
+
     Ok(())
 }
 
@@ -859,7 +1959,7 @@ fn handle_polygon16(
     path.push_str(" Z");
     
     let color = argb_to_svg_color(state.current_brush_color);
-    svg.add_path(&path, Some(&color), None);
+    svg.add_path_filled_clipped(&path, Some(&color), state.clip_id.as_deref(), state.poly_fill_mode.svg_fill_rule());
     
     Ok(())
 }
@@ -924,9 +2024,9 @@ fn handle_polypolyline(
                 path.push_str(&format!(" L {} {}", tx, ty));
             }
         }
-        svg.add_path(&path, None, Some(&color));
+        stroke_path(svg, &path, None, &color, state);
     }
-    
+
     Ok(())
 }
 
@@ -991,7 +2091,7 @@ fn handle_polypolygon(
             }
         }
         path.push_str(" Z");
-        svg.add_path(&path, Some(&color), None);
+        svg.add_path_filled_clipped(&path, Some(&color), state.clip_id.as_deref(), state.poly_fill_mode.svg_fill_rule());
     }
     
     Ok(())
@@ -1057,9 +2157,9 @@ fn handle_polypolyline16(
                 path.push_str(&format!(" L {} {}", tx, ty));
             }
         }
-        svg.add_path(&path, None, Some(&color));
+        stroke_path(svg, &path, None, &color, state);
     }
-    
+
     Ok(())
 }
 
@@ -1124,7 +2224,7 @@ fn handle_polypolygon16(
             }
         }
         path.push_str(" Z");
-        svg.add_path(&path, Some(&color), None);
+        svg.add_path_filled_clipped(&path, Some(&color), state.clip_id.as_deref(), state.poly_fill_mode.svg_fill_rule());
     }
     
     Ok(())
@@ -1158,8 +2258,62 @@ fn handle_createpen(
         color,
         width: width_x,
         style: pen_style,
+        user_dashes: None,
     });
-    
+
+    Ok(())
+}
+
+/// Handle EMR_EXTCREATEPEN record: an extended LOGPEN that can also carry an explicit
+/// dash pattern (elpStyleEntry) for PS_USERSTYLE pens and geometric (non-hairline) widths.
+fn handle_extcreatepen(
+    cursor: &mut Cursor<&[u8]>,
+    state: &mut GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 36 {
+        return Ok(());
+    }
+
+    let pen_index = cursor.read_u32::<LittleEndian>()? as usize;
+    let _off_bmi = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bmi = cursor.read_u32::<LittleEndian>()?;
+    let _off_bits = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bits = cursor.read_u32::<LittleEndian>()?;
+
+    // EXTLOGPEN32
+    let pen_style = cursor.read_u32::<LittleEndian>()?;
+    let width = cursor.read_u32::<LittleEndian>()?;
+    let _brush_style = cursor.read_u32::<LittleEndian>()?;
+    let color = cursor.read_u32::<LittleEndian>()?;
+    let _hatch = cursor.read_u32::<LittleEndian>()?;
+    let num_entries = cursor.read_u32::<LittleEndian>()?;
+
+    let mut user_dashes = None;
+    if pen_style & PS_STYLE_MASK == PS_USERSTYLE && num_entries > 0 {
+        let mut dashes = Vec::with_capacity(num_entries as usize);
+        for _ in 0..num_entries {
+            match cursor.read_u32::<LittleEndian>() {
+                Ok(entry) => dashes.push(entry),
+                Err(_) => break,
+            }
+        }
+        if !dashes.is_empty() {
+            user_dashes = Some(dashes);
+        }
+    }
+
+    if pen_index < 256 {
+        state.pen_table[pen_index] = Some(PenInfo {
+            color,
+            width,
+            style: pen_style,
+            user_dashes,
+        });
+    }
+
     Ok(())
 }
 
@@ -1212,6 +2366,9 @@ fn handle_selectobject(
     if object_index < 256 {
         if let Some(pen) = &state.pen_table[object_index] {
             state.current_pen_color = pen.color;
+            state.current_pen_width = pen.width;
+            state.current_pen_style = pen.style;
+            state.current_pen_dashes = pen.user_dashes.clone();
         }
         if let Some(brush) = &state.brush_table[object_index] {
             state.current_brush_color = brush.color;
@@ -1286,6 +2443,126 @@ fn handle_lineto(
     Ok(())
 }
 
+/// Handle EMR_POLYBEZIER record: a standalone cubic Bezier polycurve, stroked
+/// immediately with the current pen. Unlike EMR_POLYBEZIERTO, this isn't part
+/// of a figure being built between EMR_BEGINPATH/EMR_ENDPATH, so it doesn't
+/// touch `state.current_path` -- it renders straight to `svg`, the same way
+/// `handle_polyline` does for straight segments.
+fn handle_polybezier(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 8 {
+        return Ok(());
+    }
+
+    // Read bounding box (RECTL)
+    let _left = cursor.read_i32::<LittleEndian>()?;
+    let _top = cursor.read_i32::<LittleEndian>()?;
+    let _right = cursor.read_i32::<LittleEndian>()?;
+    let _bottom = cursor.read_i32::<LittleEndian>()?;
+
+    // Read point count: the start point followed by (control1, control2, end) triplets
+    let point_count = cursor.read_u32::<LittleEndian>()?;
+
+    if !(4..=10000).contains(&point_count) || (point_count - 1) % 3 != 0 {
+        return Ok(());
+    }
+
+    if cursor.position() as usize + 8 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let sx = cursor.read_i32::<LittleEndian>()? as f64;
+    let sy = cursor.read_i32::<LittleEndian>()? as f64;
+    let (tx, ty) = state.view_transform.transform(sx, sy);
+    let mut path = format!("M {} {}", tx, ty);
+
+    let bezier_count = (point_count - 1) / 3;
+    for _ in 0..bezier_count {
+        if cursor.position() as usize + 24 > cursor.get_ref().len() {
+            break;
+        }
+        let cx1 = cursor.read_i32::<LittleEndian>()? as f64;
+        let cy1 = cursor.read_i32::<LittleEndian>()? as f64;
+        let cx2 = cursor.read_i32::<LittleEndian>()? as f64;
+        let cy2 = cursor.read_i32::<LittleEndian>()? as f64;
+        let ex = cursor.read_i32::<LittleEndian>()? as f64;
+        let ey = cursor.read_i32::<LittleEndian>()? as f64;
+
+        let (tcx1, tcy1) = state.view_transform.transform(cx1, cy1);
+        let (tcx2, tcy2) = state.view_transform.transform(cx2, cy2);
+        let (tex, tey) = state.view_transform.transform(ex, ey);
+        path.push_str(&format!(" C {} {} {} {} {} {}", tcx1, tcy1, tcx2, tcy2, tex, tey));
+    }
+
+    let color = argb_to_svg_color(state.current_pen_color);
+    stroke_path(svg, &path, None, &color, state);
+
+    Ok(())
+}
+
+/// Handle EMR_POLYBEZIER16 record (16-bit coordinate variant of EMR_POLYBEZIER)
+fn handle_polybezier16(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 8 {
+        return Ok(());
+    }
+
+    // Read bounding box (RECTL)
+    let _left = cursor.read_i32::<LittleEndian>()?;
+    let _top = cursor.read_i32::<LittleEndian>()?;
+    let _right = cursor.read_i32::<LittleEndian>()?;
+    let _bottom = cursor.read_i32::<LittleEndian>()?;
+
+    // Read point count: the start point followed by (control1, control2, end) triplets
+    let point_count = cursor.read_u32::<LittleEndian>()?;
+
+    if !(4..=10000).contains(&point_count) || (point_count - 1) % 3 != 0 {
+        return Ok(());
+    }
+
+    if cursor.position() as usize + 4 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let sx = cursor.read_i16::<LittleEndian>()? as f64;
+    let sy = cursor.read_i16::<LittleEndian>()? as f64;
+    let (tx, ty) = state.view_transform.transform(sx, sy);
+    let mut path = format!("M {} {}", tx, ty);
+
+    let bezier_count = (point_count - 1) / 3;
+    for _ in 0..bezier_count {
+        if cursor.position() as usize + 12 > cursor.get_ref().len() {
+            break;
+        }
+        let cx1 = cursor.read_i16::<LittleEndian>()? as f64;
+        let cy1 = cursor.read_i16::<LittleEndian>()? as f64;
+        let cx2 = cursor.read_i16::<LittleEndian>()? as f64;
+        let cy2 = cursor.read_i16::<LittleEndian>()? as f64;
+        let ex = cursor.read_i16::<LittleEndian>()? as f64;
+        let ey = cursor.read_i16::<LittleEndian>()? as f64;
+
+        let (tcx1, tcy1) = state.view_transform.transform(cx1, cy1);
+        let (tcx2, tcy2) = state.view_transform.transform(cx2, cy2);
+        let (tex, tey) = state.view_transform.transform(ex, ey);
+        path.push_str(&format!(" C {} {} {} {} {} {}", tcx1, tcy1, tcx2, tcy2, tex, tey));
+    }
+
+    let color = argb_to_svg_color(state.current_pen_color);
+    stroke_path(svg, &path, None, &color, state);
+
+    Ok(())
+}
+
 /// Handle EMR_POLYBEZIERTO record
 fn handle_polybezierto(
     cursor: &mut Cursor<&[u8]>,
@@ -1395,7 +2672,7 @@ fn handle_fillpath(
     let path_data = state.current_path.end();
     if !path_data.is_empty() {
         let color = argb_to_svg_color(state.current_brush_color);
-        svg.add_path(&path_data, Some(&color), None);
+        svg.add_path_clipped(&path_data, Some(&color), None, state.clip_id.as_deref());
     }
     
     Ok(())
@@ -1422,7 +2699,7 @@ fn handle_strokepath(
     let path_data = state.current_path.end();
     if !path_data.is_empty() {
         let color = argb_to_svg_color(state.current_pen_color);
-        svg.add_path(&path_data, None, Some(&color));
+        stroke_path(svg, &path_data, None, &color, state);
     }
     
     Ok(())
@@ -1450,7 +2727,7 @@ fn handle_strokeandfillpath(
     if !path_data.is_empty() {
         let fill_color = argb_to_svg_color(state.current_brush_color);
         let stroke_color = argb_to_svg_color(state.current_pen_color);
-        svg.add_path(&path_data, Some(&fill_color), Some(&stroke_color));
+        stroke_path(svg, &path_data, Some(&fill_color), &stroke_color, state);
     }
     
     Ok(())
@@ -1504,10 +2781,21 @@ fn handle_extcreatefontindirectw(
     
     if !face_name_chars.is_empty() {
         let face_name = String::from_utf16_lossy(&face_name_chars);
-        
+
+        // A negative lfHeight specifies the character height directly (excluding internal
+        // leading) in logical units at the metafile's nominal 96 DPI; convert to device
+        // pixels using the device DPI recorded in the EMF header. A positive lfHeight is
+        // the cell height (including internal leading) and is already close enough to
+        // device units to use as-is.
+        let px_height = if height < 0 {
+            ((-height) as f64 * state.view_transform.device_dpi / 96.0).round() as i32
+        } else {
+            height
+        };
+
         state.font_table[font_index] = Some(FontInfo {
             face_name: face_name.trim().to_string(),
-            height: if height < 0 { -height } else { height },
+            height: px_height,
             weight,
             italic,
             underline,
@@ -1541,8 +2829,8 @@ fn handle_exttextouta(
     let _rcl2 = cursor.read_i32::<LittleEndian>()?;
     let _rcl3 = cursor.read_i32::<LittleEndian>()?;
     let _rcl4 = cursor.read_i32::<LittleEndian>()?;
-    let _off_dx = cursor.read_u32::<LittleEndian>()?;
-    
+    let off_dx = cursor.read_u32::<LittleEndian>()?;
+
     // Read text string (ANSI)
     let mut text_bytes = Vec::new();
     for _ in 0..n_chars.min(256) {
@@ -1555,25 +2843,23 @@ fn handle_exttextouta(
         }
         text_bytes.push(byte);
     }
-    
+
     if !text_bytes.is_empty() {
         let text = String::from_utf8_lossy(&text_bytes);
-        
-        // Get font info
-        let font_family = state.current_font.as_ref()
-            .map(|f| f.face_name.as_str())
-            .unwrap_or("Arial");
-        let font_size = state.current_font.as_ref()
-            .map(|f| f.height as f64)
-            .unwrap_or(12.0);
         let text_color = argb_to_svg_color(state.current_text_color);
-        
+
         // Position (using reference point) - transform coordinates
         let (x, y) = state.view_transform.transform(ptl_reference_x as f64, ptl_reference_y as f64);
-        
-        svg.add_text_styled(x, y, &text, Some(font_family), Some(font_size), Some(&text_color));
+
+        let mut style = font_style(state, &text_color);
+        if let Some(dx) = read_exttext_dx(cursor, off_dx, n_chars.min(256)) {
+            let total: f64 = dx.iter().map(|&d| d as f64).sum();
+            style.text_length = Some(total * state.view_transform.scale());
+        }
+
+        svg.add_text_font(x, y, &text, &style);
     }
-    
+
     Ok(())
 }
 
@@ -1600,12 +2886,12 @@ fn handle_exttextoutw(
     let _rcl2 = cursor.read_i32::<LittleEndian>()?;
     let _rcl3 = cursor.read_i32::<LittleEndian>()?;
     let _rcl4 = cursor.read_i32::<LittleEndian>()?;
-    let _off_dx = cursor.read_u32::<LittleEndian>()?;
-    
+    let off_dx = cursor.read_u32::<LittleEndian>()?;
+
     // Read text string (Unicode UTF-16LE)
     let max_chars = n_chars.min(256) as usize;
     let mut chars = Vec::new();
-    
+
     for _ in 0..max_chars {
         if cursor.position() as usize + 2 > cursor.get_ref().len() {
             break;
@@ -1616,25 +2902,23 @@ fn handle_exttextoutw(
         }
         chars.push(ch);
     }
-    
+
     if !chars.is_empty() {
         let text = String::from_utf16_lossy(&chars);
-        
-        // Get font info
-        let font_family = state.current_font.as_ref()
-            .map(|f| f.face_name.as_str())
-            .unwrap_or("Arial");
-        let font_size = state.current_font.as_ref()
-            .map(|f| f.height as f64)
-            .unwrap_or(12.0);
         let text_color = argb_to_svg_color(state.current_text_color);
-        
+
         // Position - transform coordinates
         let (x, y) = state.view_transform.transform(ptl_reference_x as f64, ptl_reference_y as f64);
-        
-        svg.add_text_styled(x, y, &text, Some(font_family), Some(font_size), Some(&text_color));
+
+        let mut style = font_style(state, &text_color);
+        if let Some(dx) = read_exttext_dx(cursor, off_dx, n_chars.min(256)) {
+            let total: f64 = dx.iter().map(|&d| d as f64).sum();
+            style.text_length = Some(total * state.view_transform.scale());
+        }
+
+        svg.add_text_font(x, y, &text, &style);
     }
-    
+
     Ok(())
 }
 
@@ -1796,7 +3080,13 @@ fn handle_polytextoutw(
     Ok(())
 }
 
-/// Extract bitmap data from EMF record and convert to PNG
+/// Extract the pixel data following a BITMAPINFOHEADER in an EMF blit record
+/// (EMR_BITBLT/EMR_STRETCHBLT/EMR_STRETCHDIBITS/EMR_SETDIBITSTODEVICE/EMR_ALPHABLEND) and re-encode
+/// it as a PNG. Supports the DIB encodings GDI actually emits for these
+/// records: 1/4/8-bit indexed (via the preceding RGBQUAD color table),
+/// 16-bit 555, 24-bit BGR, and 32-bit BGRX/BGRA. `want_alpha` reads the
+/// fourth byte of each 32-bit pixel as alpha instead of discarding it, for
+/// EMR_ALPHABLEND's AC_SRC_ALPHA case.
 fn extract_bitmap_data(
     cursor: &mut Cursor<&[u8]>,
     bi_width: i32,
@@ -1804,52 +3094,108 @@ fn extract_bitmap_data(
     bi_bit_count: u16,
     bi_size_image: u32,
     bi_clr_used: u32,
+    want_alpha: bool,
 ) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
-    use byteorder::{LittleEndian, ReadBytesExt};
-    
-    // Skip color table if present
+    use byteorder::ReadBytesExt;
+
+    // Read the color table, for indexed (<= 8 bpp) formats
     let color_table_size = if bi_bit_count <= 8 {
         let colors = if bi_clr_used > 0 { bi_clr_used } else { 1u32 << bi_bit_count };
-        colors as usize * 4 // Each color is 4 bytes (RGBQUAD)
+        colors as usize * 4 // Each color is 4 bytes (RGBQUAD: B, G, R, reserved)
     } else {
         0
     };
-    
+
     if cursor.position() as usize + color_table_size > cursor.get_ref().len() {
         return Ok(None);
     }
-    
-    // Skip color table
-    for _ in 0..color_table_size {
-        cursor.read_u8()?;
+
+    let mut color_table = Vec::with_capacity(color_table_size / 4);
+    for _ in 0..color_table_size / 4 {
+        let b = cursor.read_u8()?;
+        let g = cursor.read_u8()?;
+        let r = cursor.read_u8()?;
+        cursor.read_u8()?; // reserved
+        color_table.push([r, g, b]);
     }
-    
-    // Read bitmap data
+
+    let width = bi_width.unsigned_abs() as usize;
+    let height = bi_height.unsigned_abs() as usize;
+    let bytes_per_pixel = bi_bit_count as usize / 8;
+    let row_size = (width * bi_bit_count as usize).div_ceil(32) * 4; // DWORD-aligned
     let bitmap_size = if bi_size_image > 0 {
         bi_size_image as usize
     } else {
-        // Calculate bitmap size
-        let width = bi_width.abs() as usize;
-        let height = bi_height.abs() as usize;
-        let bytes_per_pixel = (bi_bit_count as usize + 7) / 8;
-        let row_size = ((width * bytes_per_pixel + 3) / 4) * 4; // Row size aligned to 4 bytes
         row_size * height
     };
-    
-    if bitmap_size == 0 || cursor.position() as usize + bitmap_size > cursor.get_ref().len() {
+
+    if width == 0
+        || height == 0
+        || bitmap_size == 0
+        || cursor.position() as usize + bitmap_size > cursor.get_ref().len()
+    {
         return Ok(None);
     }
-    
-    // For now, we'll skip actual bitmap conversion to PNG
-    // This would require implementing DIB to PNG conversion
-    // which is complex and may require additional dependencies
-    
-    // Skip bitmap data
-    for _ in 0..bitmap_size {
-        cursor.read_u8()?;
+
+    let mut rows = vec![0u8; bitmap_size];
+    cursor.read_exact(&mut rows)?;
+
+    // DIB rows are bottom-up when bi_height is positive, top-down otherwise.
+    let top_down = bi_height < 0;
+    let mut rgba = vec![0u8; width * height * 4];
+    for y in 0..height {
+        let src_row = if top_down { y } else { height - 1 - y };
+        let row = &rows[src_row * row_size..src_row * row_size + row_size];
+        for x in 0..width {
+            let [r, g, b, a] = match bi_bit_count {
+                1 => {
+                    let byte = row[x / 8];
+                    let idx = ((byte >> (7 - (x % 8))) & 0x01) as usize;
+                    let c = color_table.get(idx).copied().unwrap_or([0, 0, 0]);
+                    [c[0], c[1], c[2], 255]
+                }
+                4 => {
+                    let byte = row[x / 2];
+                    let idx = if x % 2 == 0 { byte >> 4 } else { byte & 0x0F } as usize;
+                    let c = color_table.get(idx).copied().unwrap_or([0, 0, 0]);
+                    [c[0], c[1], c[2], 255]
+                }
+                8 => {
+                    let idx = row[x] as usize;
+                    let c = color_table.get(idx).copied().unwrap_or([0, 0, 0]);
+                    [c[0], c[1], c[2], 255]
+                }
+                16 => {
+                    let px = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    let r = ((px >> 10) & 0x1F) as u8;
+                    let g = ((px >> 5) & 0x1F) as u8;
+                    let b = (px & 0x1F) as u8;
+                    [(r << 3) | (r >> 2), (g << 3) | (g >> 2), (b << 3) | (b >> 2), 255]
+                }
+                24 => {
+                    let off = x * bytes_per_pixel;
+                    [row[off + 2], row[off + 1], row[off], 255]
+                }
+                32 => {
+                    let off = x * bytes_per_pixel;
+                    let alpha = if want_alpha { row[off + 3] } else { 255 };
+                    [row[off + 2], row[off + 1], row[off], alpha]
+                }
+                _ => [0, 0, 0, 255],
+            };
+            let dest = (y * width + x) * 4;
+            rgba[dest] = r;
+            rgba[dest + 1] = g;
+            rgba[dest + 2] = b;
+            rgba[dest + 3] = a;
+        }
     }
-    
-    Ok(None) // Return None for now - bitmap rendering not fully implemented
+
+    let mut png_bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_bytes)
+        .write_image(&rgba, width as u32, height as u32, image::ColorType::Rgba8)?;
+
+    Ok(Some(png_bytes))
 }
 
 /// Handle EMR_BITBLT record (bitmap block transfer)
@@ -1890,16 +3236,10 @@ fn handle_bitblt(
     let bi_clr_used = cursor.read_u32::<LittleEndian>()?;
     let _bi_clr_important = cursor.read_u32::<LittleEndian>()?;
     
-    // Try to extract bitmap data
-    if let Ok(Some(_bitmap_png)) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used) {
-        // If we successfully extracted bitmap, render it
-        // For now, this is a placeholder - full bitmap rendering requires PNG encoding
-        // svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
-    } else {
-        // Skip bitmap data
-        extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used)?;
+    if let Some(bitmap_png) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used, false)? {
+        svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
     }
-    
+
     Ok(())
 }
 
@@ -1945,18 +3285,142 @@ fn handle_stretchblt(
     let bi_clr_used = cursor.read_u32::<LittleEndian>()?;
     let _bi_clr_important = cursor.read_u32::<LittleEndian>()?;
     
-    // Try to extract bitmap data
-    if let Ok(Some(_bitmap_png)) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used) {
-        // If we successfully extracted bitmap, render it
-        // svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
+    if let Some(bitmap_png) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used, false)? {
+        svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
+    }
+
+    Ok(())
+}
+
+/// AC_SRC_ALPHA: the BLENDFUNCTION's AlphaFormat flag meaning the source DIB
+/// carries its own per-pixel alpha channel (only valid for 32bpp sources),
+/// on top of (not instead of) SrcConstantAlpha.
+const AC_SRC_ALPHA: u8 = 0x01;
+
+/// Handle EMR_ALPHABLEND record (per-pixel/constant-alpha bitmap blit)
+///
+/// This reads the record's fixed fields (destination rect, BLENDFUNCTION,
+/// source rect) and then assumes the BITMAPINFOHEADER + pixel data follow
+/// immediately, the same simplification `handle_bitblt`/`handle_stretchblt`
+/// make rather than resolving `offBmiSrc`/`offBitsSrc` against the start of
+/// the record -- GDI-produced EMFs lay the bitmap out inline in practice, so
+/// this covers the files we actually see.
+fn handle_alphablend(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    _state: &GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 100 {
+        return Ok(());
+    }
+
+    // Bounds (RECTL) -- the device clip rect this blit touches, not needed
+    // since xDest/yDest/cxDest/cyDest below already give us the placement.
+    for _ in 0..4 {
+        cursor.read_i32::<LittleEndian>()?;
+    }
+
+    let x_dest = cursor.read_i32::<LittleEndian>()?;
+    let y_dest = cursor.read_i32::<LittleEndian>()?;
+    let cx_dest = cursor.read_i32::<LittleEndian>()?;
+    let cy_dest = cursor.read_i32::<LittleEndian>()?;
+
+    // BLENDFUNCTION, packed as BlendOp, BlendFlags, SrcConstantAlpha, AlphaFormat
+    let blend_function = cursor.read_u32::<LittleEndian>()?;
+    let src_constant_alpha = ((blend_function >> 16) & 0xFF) as u8;
+    let alpha_format = ((blend_function >> 24) & 0xFF) as u8;
+
+    let _x_src = cursor.read_i32::<LittleEndian>()?;
+    let _y_src = cursor.read_i32::<LittleEndian>()?;
+
+    // XformSrc (XFORM, 24 bytes) -- like the other blit handlers, the
+    // destination is already placed in device space, so the source-space
+    // transform isn't needed to just paint the bitmap.
+    for _ in 0..6 {
+        cursor.read_f32::<LittleEndian>()?;
+    }
+
+    let _bk_color_src = cursor.read_u32::<LittleEndian>()?;
+    let _usage_src = cursor.read_u32::<LittleEndian>()?;
+    let _off_bmi_src = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bmi_src = cursor.read_u32::<LittleEndian>()?;
+    let _off_bits_src = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bits_src = cursor.read_u32::<LittleEndian>()?;
+    let _cx_src = cursor.read_i32::<LittleEndian>()?;
+    let _cy_src = cursor.read_i32::<LittleEndian>()?;
+
+    // BITMAPINFOHEADER (40 bytes)
+    let _bi_size = cursor.read_u32::<LittleEndian>()?;
+    let bi_width = cursor.read_i32::<LittleEndian>()?;
+    let bi_height = cursor.read_i32::<LittleEndian>()?;
+    let _bi_planes = cursor.read_u16::<LittleEndian>()?;
+    let bi_bit_count = cursor.read_u16::<LittleEndian>()?;
+    let _bi_compression = cursor.read_u32::<LittleEndian>()?;
+    let bi_size_image = cursor.read_u32::<LittleEndian>()?;
+    let _bi_x_pels_per_meter = cursor.read_i32::<LittleEndian>()?;
+    let _bi_y_pels_per_meter = cursor.read_i32::<LittleEndian>()?;
+    let bi_clr_used = cursor.read_u32::<LittleEndian>()?;
+    let _bi_clr_important = cursor.read_u32::<LittleEndian>()?;
+
+    let has_per_pixel_alpha = alpha_format & AC_SRC_ALPHA != 0 && bi_bit_count == 32;
+    let bitmap_png = extract_bitmap_data(
+        cursor,
+        bi_width,
+        bi_height,
+        bi_bit_count,
+        bi_size_image,
+        bi_clr_used,
+        has_per_pixel_alpha,
+    )?;
+
+    let Some(bitmap_png) = bitmap_png else {
+        return Ok(());
+    };
+
+    if has_per_pixel_alpha {
+        // The per-pixel alpha in the PNG is already combined with
+        // SrcConstantAlpha below, via premultiplication against it, so the
+        // image itself can be drawn at full opacity.
+        let bitmap_png = if src_constant_alpha < 255 {
+            scale_png_alpha(&bitmap_png, src_constant_alpha)?
+        } else {
+            bitmap_png
+        };
+        svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
     } else {
-        // Skip bitmap data
-        extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used)?;
+        // No per-pixel alpha: the whole bitmap is blended uniformly by
+        // SrcConstantAlpha, so express that as the <image> element's opacity
+        // instead of re-encoding every pixel.
+        let opacity = src_constant_alpha as f64 / 255.0;
+        svg.add_image_opacity(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png", Some(opacity));
     }
-    
+
     Ok(())
 }
 
+/// Scale every pixel's alpha channel in a PNG-encoded RGBA image by
+/// `constant_alpha / 255`, for EMR_ALPHABLEND's combination of per-pixel
+/// alpha with an overall SrcConstantAlpha.
+fn scale_png_alpha(png_data: &[u8], constant_alpha: u8) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let img = image::load_from_memory_with_format(png_data, image::ImageFormat::Png)?;
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        pixel[3] = ((pixel[3] as u32 * constant_alpha as u32) / 255) as u8;
+    }
+
+    let mut out = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut out).write_image(
+        &rgba,
+        rgba.width(),
+        rgba.height(),
+        image::ColorType::Rgba8,
+    )?;
+    Ok(out)
+}
+
 /// Handle EMR_STRETCHDIBITS record (stretched DIB bitmap)
 fn handle_stretchdibits(
     cursor: &mut Cursor<&[u8]>,
@@ -2001,20 +3465,117 @@ fn handle_stretchdibits(
     let bi_clr_used = cursor.read_u32::<LittleEndian>()?;
     let _bi_clr_important = cursor.read_u32::<LittleEndian>()?;
     
-    // Try to extract bitmap data
-    if let Ok(Some(_bitmap_png)) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used) {
-        // If we successfully extracted bitmap, render it
-        // svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
-    } else {
-        // Skip bitmap data
-        extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used)?;
+    if let Some(bitmap_png) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used, false)? {
+        svg.add_image(x_dest as f64, y_dest as f64, cx_dest as f64, cy_dest as f64, &bitmap_png, "image/png");
     }
-    
+
+    Ok(())
+}
+
+/// Handle EMR_SETDIBITSTODEVICE record: an unstretched (1:1) DIB blit, GDI's analogue
+/// of EMR_STRETCHDIBITS without a stretch mode. The destination size always matches the
+/// source rectangle (cxSrc, cySrc), so unlike the other blit handlers there's no separate
+/// cxDest/cyDest field to read.
+fn handle_setdibitstodevice(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    _state: &GraphicsState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 108 {
+        return Ok(());
+    }
+
+    // Bounds (RECTL) -- not needed, xDest/yDest/cxSrc/cySrc below already place the blit.
+    for _ in 0..4 {
+        cursor.read_i32::<LittleEndian>()?;
+    }
+
+    let x_dest = cursor.read_i32::<LittleEndian>()?;
+    let y_dest = cursor.read_i32::<LittleEndian>()?;
+    let _x_src = cursor.read_i32::<LittleEndian>()?;
+    let _y_src = cursor.read_i32::<LittleEndian>()?;
+    let cx_src = cursor.read_i32::<LittleEndian>()?;
+    let cy_src = cursor.read_i32::<LittleEndian>()?;
+
+    let _off_bmi_src = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bmi_src = cursor.read_u32::<LittleEndian>()?;
+    let _off_bits_src = cursor.read_u32::<LittleEndian>()?;
+    let _cb_bits_src = cursor.read_u32::<LittleEndian>()?;
+    let _usage_src = cursor.read_u32::<LittleEndian>()?;
+    let _start_scan = cursor.read_u32::<LittleEndian>()?;
+    let _scans = cursor.read_u32::<LittleEndian>()?;
+
+    // BITMAPINFOHEADER (40 bytes)
+    let _bi_size = cursor.read_u32::<LittleEndian>()?;
+    let bi_width = cursor.read_i32::<LittleEndian>()?;
+    let bi_height = cursor.read_i32::<LittleEndian>()?;
+    let _bi_planes = cursor.read_u16::<LittleEndian>()?;
+    let bi_bit_count = cursor.read_u16::<LittleEndian>()?;
+    let _bi_compression = cursor.read_u32::<LittleEndian>()?;
+    let bi_size_image = cursor.read_u32::<LittleEndian>()?;
+    let _bi_x_pels_per_meter = cursor.read_i32::<LittleEndian>()?;
+    let _bi_y_pels_per_meter = cursor.read_i32::<LittleEndian>()?;
+    let bi_clr_used = cursor.read_u32::<LittleEndian>()?;
+    let _bi_clr_important = cursor.read_u32::<LittleEndian>()?;
+
+    if let Some(bitmap_png) = extract_bitmap_data(cursor, bi_width, bi_height, bi_bit_count, bi_size_image, bi_clr_used, false)? {
+        svg.add_image(x_dest as f64, y_dest as f64, cx_src as f64, cy_src as f64, &bitmap_png, "image/png");
+    }
+
     Ok(())
 }
 
+/// Build the SVG text style for the currently selected font
+fn font_style(state: &GraphicsState, fill_color: &str) -> FontStyle {
+    let font = state.current_font.as_ref();
+    let (anchor, dominant_baseline) = text_align_to_svg(state.current_text_align);
+    FontStyle {
+        family: Some(font.map(|f| f.face_name.as_str()).unwrap_or("Arial").to_string()),
+        size: Some(font.map(|f| f.height as f64).unwrap_or(12.0)),
+        fill_color: Some(fill_color.to_string()),
+        weight: Some(font.map(|f| f.weight).unwrap_or(400)),
+        italic: font.map(|f| f.italic).unwrap_or(false),
+        underline: font.map(|f| f.underline).unwrap_or(false),
+        strikeout: font.map(|f| f.strikeout).unwrap_or(false),
+        anchor,
+        dominant_baseline,
+        text_length: None,
+    }
+}
+
+/// Read an EMRTEXT record's optional per-character `dx` spacing array (i32 advances,
+/// in logical units), assumed to immediately follow the text string padded to a
+/// 4-byte boundary -- the same "fields are laid out sequentially" simplification
+/// already used for the rest of EMRTEXT here (offString/offDx are not resolved as
+/// absolute offsets). Returns `None` if `off_dx` is zero or the array doesn't fit
+/// in the remaining record data.
+fn read_exttext_dx(cursor: &mut Cursor<&[u8]>, off_dx: u32, n_chars: u32) -> Option<Vec<i32>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if off_dx == 0 || n_chars == 0 {
+        return None;
+    }
+    let pad = (4 - (cursor.position() % 4) as u32) % 4;
+    for _ in 0..pad {
+        if cursor.read_u8().is_err() {
+            return None;
+        }
+    }
+    let mut dx = Vec::with_capacity(n_chars as usize);
+    for _ in 0..n_chars {
+        match cursor.read_i32::<LittleEndian>() {
+            Ok(v) => dx.push(v),
+            Err(_) => return None,
+        }
+    }
+    Some(dx)
+}
+
 /// Convert ARGB color to SVG color string
-fn argb_to_svg_color(argb: u32) -> String {
+pub(crate) fn argb_to_svg_color(argb: u32) -> String {
     let a = ((argb >> 24) & 0xFF) as u8;
     let r = ((argb >> 16) & 0xFF) as u8;
     let g = ((argb >> 8) & 0xFF) as u8;
@@ -2034,13 +3595,13 @@ fn argb_to_svg_color(argb: u32) -> String {
 
 /// Normalize dimension value
 fn normalize_dimension(value: f64) -> u32 {
-    eprintln!("normalize_dimension called with: {}", value);
+    log::trace!("normalize_dimension called with: {}", value);
     if value.is_finite() && value > 0.0 && value < 20000.0 {
         let result = value.ceil() as u32;
-        eprintln!("normalize_dimension returning: {}", result);
+        log::trace!("normalize_dimension returning: {}", result);
         result
     } else {
-        eprintln!("normalize_dimension returning default: 800");
+        log::trace!("normalize_dimension returning default: 800");
         800 // Default fallback
     }
 }
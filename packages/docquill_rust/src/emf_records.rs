@@ -96,6 +96,13 @@ pub const EMR_CREATEDIBPATTERNBRUSHPT: u32 = 94;
 pub const EMR_EXTCREATEPEN: u32 = 95;
 pub const EMR_POLYTEXTOUTA: u32 = 96;
 pub const EMR_POLYTEXTOUTW: u32 = 97;
+pub const EMR_ALPHABLEND: u32 = 114;
+pub const EMR_GRADIENTFILL: u32 = 118;
+
+/// ulMode values for EMR_GRADIENTFILL
+pub const GRADIENT_FILL_RECT_H: u32 = 0x0000_0000;
+pub const GRADIENT_FILL_RECT_V: u32 = 0x0000_0001;
+pub const GRADIENT_FILL_TRIANGLE: u32 = 0x0000_0002;
 
 /// Get record type name for debugging
 pub fn get_record_type_name(record_type: u32) -> &'static str {
@@ -193,8 +200,10 @@ pub fn get_record_type_name(record_type: u32) -> &'static str {
         EMR_CREATEMONOBRUSH => "EMR_CREATEMONOBRUSH",
         EMR_CREATEDIBPATTERNBRUSHPT => "EMR_CREATEDIBPATTERNBRUSHPT",
         EMR_EXTCREATEPEN => "EMR_EXTCREATEPEN",
+        EMR_GRADIENTFILL => "EMR_GRADIENTFILL",
         EMR_POLYTEXTOUTA => "EMR_POLYTEXTOUTA",
         EMR_POLYTEXTOUTW => "EMR_POLYTEXTOUTW",
+        EMR_ALPHABLEND => "EMR_ALPHABLEND",
         _ => "UNKNOWN",
     }
 }
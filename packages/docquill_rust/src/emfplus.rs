@@ -64,6 +64,41 @@ struct Path {
     svg_path: String,
 }
 
+/// Font representation, parsed from an `EmfPlusFont` object
+#[derive(Clone)]
+struct Font {
+    family: String,
+    size: f32,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikeout: bool,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self {
+            family: "Arial".to_string(),
+            size: 12.0,
+            bold: false,
+            italic: false,
+            underline: false,
+            strikeout: false,
+        }
+    }
+}
+
+// FontStyleFlags bits (EmfPlusFont.FontStyleFlags / GDI+ FontStyle enum)
+const FONT_STYLE_BOLD: i32 = 0x1;
+const FONT_STYLE_ITALIC: i32 = 0x2;
+const FONT_STYLE_UNDERLINE: i32 = 0x4;
+const FONT_STYLE_STRIKEOUT: i32 = 0x8;
+
+// StringAlignment values read from the DrawString record's inline format field
+const STRING_ALIGNMENT_NEAR: u32 = 0;
+const STRING_ALIGNMENT_CENTER: u32 = 1;
+const STRING_ALIGNMENT_FAR: u32 = 2;
+
 /// Graphics state for save/restore
 struct GraphicsState {
     transform: Transform,
@@ -95,6 +130,7 @@ pub struct EmfPlusParser<'a> {
     brush_table: Vec<Option<Brush>>,
     pen_table: Vec<Option<Pen>>,
     path_table: Vec<Option<Path>>,
+    font_table: Vec<Option<Font>>,
     current_transform: Transform,
     state_stack: Vec<GraphicsState>,
 }
@@ -108,6 +144,7 @@ impl<'a> EmfPlusParser<'a> {
             brush_table: vec![None; OBJECT_TABLE_SIZE],
             pen_table: vec![None; OBJECT_TABLE_SIZE],
             path_table: vec![None; OBJECT_TABLE_SIZE],
+            font_table: vec![None; OBJECT_TABLE_SIZE],
             current_transform: Transform::identity(),
             state_stack: Vec::new(),
         }
@@ -331,6 +368,11 @@ impl<'a> EmfPlusParser<'a> {
                     self.path_table[object_id] = Some(path);
                 }
             }
+            EMFPLUS_OBJECT_FONT => {
+                if let Some(font) = self.parse_font(payload) {
+                    self.font_table[object_id] = Some(font);
+                }
+            }
             _ => {
                 // Other object types not yet implemented
             }
@@ -474,6 +516,42 @@ impl<'a> EmfPlusParser<'a> {
         Some(Path { svg_path })
     }
 
+    /// Parse font object (EmfPlusFont: Version, EmSize, SizeUnit, FontStyleFlags, Reserved,
+    /// Length, then Length UTF-16 code units for the family name)
+    fn parse_font(&self, payload: &[u8]) -> Option<Font> {
+        if payload.len() < 24 {
+            return None;
+        }
+
+        let mut cursor = Cursor::new(payload);
+        let _version = cursor.read_u32::<LittleEndian>().ok()?;
+        let em_size = cursor.read_f32::<LittleEndian>().ok()?;
+        let _size_unit = cursor.read_u32::<LittleEndian>().ok()?;
+        let style_flags = cursor.read_i32::<LittleEndian>().ok()?;
+        let _reserved = cursor.read_u32::<LittleEndian>().ok()?;
+        let length = cursor.read_u32::<LittleEndian>().ok()?;
+
+        let name_bytes = (length as usize) * 2;
+        if cursor.position() as usize + name_bytes > payload.len() {
+            return None;
+        }
+
+        let mut units: Vec<u16> = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            units.push(cursor.read_u16::<LittleEndian>().ok()?);
+        }
+        let family = String::from_utf16_lossy(&units).trim_end_matches('\0').to_string();
+
+        Some(Font {
+            family: if family.is_empty() { "Arial".to_string() } else { family },
+            size: em_size.max(1.0),
+            bold: (style_flags & FONT_STYLE_BOLD) != 0,
+            italic: (style_flags & FONT_STYLE_ITALIC) != 0,
+            underline: (style_flags & FONT_STYLE_UNDERLINE) != 0,
+            strikeout: (style_flags & FONT_STYLE_STRIKEOUT) != 0,
+        })
+    }
+
     /// Handle FILL_RECTS record
     fn handle_fill_rects(&mut self, flags: u16, payload: &[u8]) {
         if payload.len() < 8 {
@@ -717,9 +795,93 @@ impl<'a> EmfPlusParser<'a> {
         // TODO: Implement image drawing
     }
 
-    /// Handle DRAW_STRING record (simplified - placeholder)
-    fn handle_draw_string(&mut self, _flags: u16, _payload: &[u8]) {
-        // TODO: Implement string drawing
+    /// Handle DRAW_STRING record: BrushId/color, inline string-alignment, layout rect, and
+    /// the UTF-16 string, using the font referenced by the record's object id (low byte of
+    /// `flags`, following the same convention as the pen/brush id in DRAW_RECTS/FILL_RECTS).
+    fn handle_draw_string(&mut self, flags: u16, payload: &[u8]) {
+        if payload.len() < 28 {
+            return;
+        }
+
+        let font_id = (flags & 0xFF) as usize;
+        let brush_from_color = (flags & 0x8000) != 0;
+
+        let mut cursor = Cursor::new(payload);
+        let brush_token = match cursor.read_u32::<LittleEndian>() {
+            Ok(t) => t,
+            Err(_) => return,
+        };
+        let alignment = match cursor.read_u32::<LittleEndian>() {
+            Ok(a) => a,
+            Err(_) => return,
+        };
+        let length = match cursor.read_u32::<LittleEndian>() {
+            Ok(l) => l,
+            Err(_) => return,
+        };
+        let layout_x = match cursor.read_f32::<LittleEndian>() { Ok(v) => v, Err(_) => return };
+        let layout_y = match cursor.read_f32::<LittleEndian>() { Ok(v) => v, Err(_) => return };
+        let layout_width = match cursor.read_f32::<LittleEndian>() { Ok(v) => v, Err(_) => return };
+        let _layout_height = match cursor.read_f32::<LittleEndian>() { Ok(v) => v, Err(_) => return };
+
+        let string_bytes = (length as usize) * 2;
+        if cursor.position() as usize + string_bytes > payload.len() {
+            return;
+        }
+        let mut units: Vec<u16> = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            match cursor.read_u16::<LittleEndian>() {
+                Ok(u) => units.push(u),
+                Err(_) => return,
+            }
+        }
+        let text = String::from_utf16_lossy(&units);
+        if text.is_empty() {
+            return;
+        }
+
+        let brush = if brush_from_color {
+            Some(Brush::SolidColor(brush_token))
+        } else {
+            let brush_id = (brush_token & 0xFF) as usize;
+            if brush_id < OBJECT_TABLE_SIZE {
+                self.brush_table[brush_id].clone()
+            } else {
+                None
+            }
+        };
+        let fill_color = match &brush {
+            Some(b) => self.brush_to_color(b),
+            None => "#000000".to_string(),
+        };
+
+        let font = if font_id < OBJECT_TABLE_SIZE {
+            self.font_table[font_id].clone().unwrap_or_default()
+        } else {
+            Font::default()
+        };
+
+        let (x, anchor) = match alignment {
+            STRING_ALIGNMENT_CENTER => (layout_x + layout_width / 2.0, "middle"),
+            STRING_ALIGNMENT_FAR => (layout_x + layout_width, "end"),
+            STRING_ALIGNMENT_NEAR | _ => (layout_x, "start"),
+        };
+        // Baseline approximation: GDI+ layout rects are top-based, so drop down by ~1 em to
+        // land the first line's baseline inside the box, matching the EMF LOGFONT text path.
+        let y = (layout_y + font.size) as f64;
+
+        self.svg.add_text_font(x as f64, y, &text, &crate::svg_writer::FontStyle {
+            family: Some(font.family),
+            size: Some(font.size as f64),
+            fill_color: Some(fill_color),
+            weight: Some(if font.bold { 700 } else { 400 }),
+            italic: font.italic,
+            underline: font.underline,
+            strikeout: font.strikeout,
+            anchor: Some(anchor.to_string()),
+            dominant_baseline: None,
+            text_length: None,
+        });
     }
 
     /// Handle SET_WORLD_TRANSFORM record
@@ -3,41 +3,169 @@
 //! This module provides lazy font loading - fonts are loaded on demand when
 //! canvas_set_font() is called, not at renderer initialization.
 
+use byteorder::{BigEndian, ReadBytesExt};
 use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
 use pdf_writer::{Name, Pdf, Rect, Ref, Str};
 use pyo3::prelude::*;
 use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read};
 use std::path::{Path, PathBuf};
-use ttf_parser::Face;
+use ttf_parser::{Face, GlyphId, Tag};
 
 /// Map Unicode code point to CID (Character ID) for Type0 fonts
 pub type CidMap = HashMap<u32, u16>;
 
-/// Font style variants
+/// Candidate GSUB ligatures starting with a given glyph, as
+/// `(full component GID sequence including the first glyph, ligature GID)`,
+/// ordered longest-sequence-first so substitution can match greedily.
+pub type LigatureTable = HashMap<u16, Vec<(Vec<u16>, u16)>>;
+
+/// Metrics needed to draw underline/strikethrough decoration and measure string
+/// width without a round-trip into Python. Distances are in the same 1000-unit
+/// em space as the CIDFont `/W` widths array built below, so scaling to a given
+/// font size is just `value * font_size / 1000.0`.
+pub struct FontMetrics {
+    /// Baseline offset of the underline, from the `post` table (negative = below baseline)
+    pub underline_position: f32,
+    /// Underline stroke thickness, from the `post` table
+    pub underline_thickness: f32,
+    /// Baseline offset of the strikeout line, from the OS/2 table
+    pub strikeout_position: f32,
+    /// Strikeout stroke thickness, from the OS/2 table
+    pub strikeout_thickness: f32,
+    /// Font ascender above the baseline
+    pub ascender: f32,
+    /// Font descender below the baseline (negative)
+    pub descender: f32,
+    /// Per-codepoint advance width, keyed the same as the font's `CidMap`
+    pub widths: HashMap<u32, i32>,
+    /// GSUB `liga`/`dlig` ligature candidates, keyed by the first glyph of
+    /// the sequence they replace.
+    pub ligatures: LigatureTable,
+}
+
+/// Font weight axis, aligned to the standard OpenType `usWeightClass` scale
+/// (100-900) so it can be compared directly against values read from a
+/// font's OS/2 table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum FontStyle {
+pub enum FontWeight {
+    Thin,
+    ExtraLight,
+    Light,
     Regular,
+    Medium,
+    SemiBold,
     Bold,
-    Italic,
-    BoldItalic,
+    ExtraBold,
+    Black,
+}
+
+impl FontWeight {
+    pub fn as_u16(self) -> u16 {
+        match self {
+            FontWeight::Thin => 100,
+            FontWeight::ExtraLight => 200,
+            FontWeight::Light => 300,
+            FontWeight::Regular => 400,
+            FontWeight::Medium => 500,
+            FontWeight::SemiBold => 600,
+            FontWeight::Bold => 700,
+            FontWeight::ExtraBold => 800,
+            FontWeight::Black => 900,
+        }
+    }
+}
+
+/// Font width axis, aligned to the OpenType `usWidthClass` scale (1-9,
+/// Condensed/Normal/Expanded bucketed the same way `ttf_parser::Width` does).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontWidth {
+    Condensed,
+    Normal,
+    Expanded,
+}
+
+impl FontWidth {
+    pub fn as_u16(self) -> u16 {
+        match self {
+            FontWidth::Condensed => 3,
+            FontWidth::Normal => 5,
+            FontWidth::Expanded => 7,
+        }
+    }
+}
+
+/// Font style: weight and width axes plus italic, parsed from a font name.
+/// `FONT_MAPPINGS` and the bundled DejaVu set only carry regular/bold x
+/// upright/italic files, so callers that need the 4-slot filename arrays
+/// should index via `mapping_slot()`; the width axis (and finer weights like
+/// SemiBold) only matters once we're matching real files by their OS/2
+/// table, in `find_system_font`'s directory enumeration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontStyle {
+    pub weight: FontWeight,
+    pub width: FontWidth,
+    pub italic: bool,
 }
 
 impl FontStyle {
-    /// Parse font style from font name
+    pub const REGULAR: FontStyle = FontStyle { weight: FontWeight::Regular, width: FontWidth::Normal, italic: false };
+    pub const BOLD: FontStyle = FontStyle { weight: FontWeight::Bold, width: FontWidth::Normal, italic: false };
+    pub const ITALIC: FontStyle = FontStyle { weight: FontWeight::Regular, width: FontWidth::Normal, italic: true };
+    pub const BOLD_ITALIC: FontStyle = FontStyle { weight: FontWeight::Bold, width: FontWidth::Normal, italic: true };
+
+    /// Parse font style (weight, width, italic) from a font name, e.g.
+    /// "Calibri Light", "Arial Narrow Bold", "Segoe UI Semibold Italic".
     pub fn from_name(name: &str) -> Self {
         let lower = name.to_lowercase();
-        let is_bold = lower.contains("bold") || lower.contains("-bd") || lower.ends_with("bd");
-        let is_italic = lower.contains("italic") || lower.contains("oblique") 
+        let is_italic = lower.contains("italic") || lower.contains("oblique")
             || lower.contains("-it") || lower.ends_with("it")
             || lower.contains("-i") && !lower.contains("-in");
-        
-        match (is_bold, is_italic) {
-            (true, true) => FontStyle::BoldItalic,
-            (true, false) => FontStyle::Bold,
-            (false, true) => FontStyle::Italic,
-            (false, false) => FontStyle::Regular,
+
+        let weight = if lower.contains("extrabold") || lower.contains("extra bold") || lower.contains("ultrabold") {
+            FontWeight::ExtraBold
+        } else if lower.contains("semibold") || lower.contains("demibold") || lower.contains("semi bold") {
+            FontWeight::SemiBold
+        } else if lower.contains("bold") || lower.contains("-bd") || lower.ends_with("bd") {
+            FontWeight::Bold
+        } else if lower.contains("black") || lower.contains("heavy") {
+            FontWeight::Black
+        } else if lower.contains("medium") {
+            FontWeight::Medium
+        } else if lower.contains("extralight") || lower.contains("extra light") || lower.contains("ultralight") {
+            FontWeight::ExtraLight
+        } else if lower.contains("light") {
+            FontWeight::Light
+        } else if lower.contains("thin") {
+            FontWeight::Thin
+        } else {
+            FontWeight::Regular
+        };
+
+        let width = if lower.contains("condensed") || lower.contains("narrow") {
+            FontWidth::Condensed
+        } else if lower.contains("expanded") || lower.contains("extended") || lower.contains("wide") {
+            FontWidth::Expanded
+        } else {
+            FontWidth::Normal
+        };
+
+        FontStyle { weight, width, italic: is_italic }
+    }
+
+    /// Whether this style is visually bold enough to use a font file's "Bold" slot.
+    pub fn is_bold(&self) -> bool {
+        self.weight.as_u16() >= FontWeight::Bold.as_u16()
+    }
+
+    /// Index into a `FontFileMapping`'s 4-slot [regular, bold, italic, bold_italic] arrays.
+    fn mapping_slot(&self) -> usize {
+        match (self.is_bold(), self.italic) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (true, true) => 3,
         }
     }
 }
@@ -213,11 +341,11 @@ fn find_bundled_fonts_dir() -> Option<PathBuf> {
 fn get_bundled_dejavu_font(style: FontStyle) -> Option<PathBuf> {
     let bundled_dir = find_bundled_fonts_dir()?;
     
-    let filename = match style {
-        FontStyle::Regular => "DejaVuSans.ttf",
-        FontStyle::Bold => "DejaVuSans-Bold.ttf",
-        FontStyle::Italic => "DejaVuSans-Oblique.ttf",
-        FontStyle::BoldItalic => "DejaVuSans-BoldOblique.ttf",
+    let filename = match (style.is_bold(), style.italic) {
+        (false, false) => "DejaVuSans.ttf",
+        (true, false) => "DejaVuSans-Bold.ttf",
+        (false, true) => "DejaVuSans-Oblique.ttf",
+        (true, true) => "DejaVuSans-BoldOblique.ttf",
     };
     
     let font_path = bundled_dir.join(filename);
@@ -294,18 +422,14 @@ fn normalize_font_name(name: &str) -> String {
 /// 
 /// Returns:
 ///     Path to font file if found, None otherwise
-pub fn find_system_font(font_name: &str, style: FontStyle) -> Option<PathBuf> {
+pub fn find_system_font(font_name: &str, style: FontStyle, extra_dirs: &[PathBuf]) -> Option<PathBuf> {
     let normalized = normalize_font_name(font_name);
-    let font_dirs = get_system_font_dirs();
-    
+    let mut font_dirs = extra_dirs.to_vec();
+    font_dirs.extend(get_system_font_dirs());
+
     // Find mapping for this font family
-    let style_index = match style {
-        FontStyle::Regular => 0,
-        FontStyle::Bold => 1,
-        FontStyle::Italic => 2,
-        FontStyle::BoldItalic => 3,
-    };
-    
+    let style_index = style.mapping_slot();
+
     // Try exact match first
     for mapping in FONT_MAPPINGS {
         if normalized == mapping.family || normalized.starts_with(mapping.family) {
@@ -362,20 +486,65 @@ pub fn find_system_font(font_name: &str, style: FontStyle) -> Option<PathBuf> {
             }
         }
     }
-    
+
+    // Neither the static mapping nor a guessed filename exists. The family may
+    // still be installed under a weight/width variant we don't know the
+    // filename of (e.g. "Calibri Light" has no entry in FONT_MAPPINGS) — scan
+    // the actual font files for this family and pick the one whose OS/2
+    // usWeightClass/usWidthClass is closest to what was asked for, rather than
+    // giving up and falling back to Regular.
+    if let Some(path) = find_closest_style_match(font_name, style, extra_dirs) {
+        return Some(path);
+    }
+
     None
 }
 
+/// Among installed font files whose family matches `font_name`, return the
+/// one whose OS/2 weight/width/italic is closest to `style`. Exact weight
+/// matches win; when the exact weight isn't installed, the nearest available
+/// one is used instead of silently falling back to Regular.
+fn find_closest_style_match(font_name: &str, style: FontStyle, extra_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let normalized = normalize_font_name(font_name);
+    let mut best: Option<(i32, PathBuf)> = None;
+
+    for info in enumerate_system_fonts(extra_dirs) {
+        if normalize_font_name(&info.family) != normalized {
+            continue;
+        }
+
+        let font_data = match std::fs::read(&info.path) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        let face = match Face::parse(&font_data, 0) {
+            Ok(face) => face,
+            Err(_) => continue,
+        };
+
+        let weight_diff = (face.weight().to_number() as i32 - style.weight.as_u16() as i32).abs();
+        let width_diff = (face.width().to_number() as i32 - style.width.as_u16() as i32).abs() * 10;
+        let italic_diff = if face.is_italic() == style.italic { 0 } else { 1000 };
+        let score = weight_diff + width_diff + italic_diff;
+
+        if best.as_ref().is_none_or(|(best_score, _)| score < *best_score) {
+            best = Some((score, info.path));
+        }
+    }
+
+    best.map(|(_, path)| path)
+}
+
 /// Generate possible font filenames for a given font name and style
 fn generate_font_filenames(font_name: &str, style: FontStyle) -> Vec<String> {
     let base = font_name.replace(" ", "");
     let base_with_dash = font_name.replace(" ", "-");
-    
-    let suffix = match style {
-        FontStyle::Regular => vec!["", "-Regular"],
-        FontStyle::Bold => vec!["-Bold", "bd", "-Bd", "b"],
-        FontStyle::Italic => vec!["-Italic", "-Oblique", "i", "-It", "it"],
-        FontStyle::BoldItalic => vec!["-BoldItalic", "-BoldOblique", "bi", "z", "-BI"],
+
+    let suffix = match (style.is_bold(), style.italic) {
+        (false, false) => vec!["", "-Regular"],
+        (true, false) => vec!["-Bold", "bd", "-Bd", "b"],
+        (false, true) => vec!["-Italic", "-Oblique", "i", "-It", "it"],
+        (true, true) => vec!["-BoldItalic", "-BoldOblique", "bi", "z", "-BI"],
     };
     
     let mut filenames = Vec::new();
@@ -394,13 +563,13 @@ fn generate_font_filenames(font_name: &str, style: FontStyle) -> Vec<String> {
 /// Get fallback font for when requested font is not found
 /// PRIORITY: Bundled DejaVu Sans first (ensures consistency with Python/ReportLab)
 /// then system fonts as fallback
-pub fn get_fallback_font(style: FontStyle) -> Option<PathBuf> {
+pub fn get_fallback_font(style: FontStyle, extra_dirs: &[PathBuf]) -> Option<PathBuf> {
     // HIGHEST PRIORITY: Bundled DejaVu Sans from docquill package
     // This ensures font metrics match Python's ReportLab calculations
     if let Some(path) = get_bundled_dejavu_font(style) {
         return Some(path);
     }
-    
+
     // Fallback: Try system fonts
     let fallback_families = [
         "DejaVu Sans",  // Same font family as bundled
@@ -409,15 +578,16 @@ pub fn get_fallback_font(style: FontStyle) -> Option<PathBuf> {
         "Helvetica",    // macOS
         "Segoe UI",     // Windows
     ];
-    
+
     for family in &fallback_families {
-        if let Some(path) = find_system_font(family, style) {
+        if let Some(path) = find_system_font(family, style, extra_dirs) {
             return Some(path);
         }
     }
-    
-    // Last resort: try to find ANY .ttf file in system fonts
-    let font_dirs = get_system_font_dirs();
+
+    // Last resort: try to find ANY .ttf file in system fonts (plus caller-supplied dirs)
+    let mut font_dirs = extra_dirs.to_vec();
+    font_dirs.extend(get_system_font_dirs());
     for dir in &font_dirs {
         if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
@@ -432,7 +602,226 @@ pub fn get_fallback_font(style: FontStyle) -> Option<PathBuf> {
     None
 }
 
-/// Load TTF/OTF font from file path
+/// A font file discovered on disk, with its real family/style read from the
+/// font's own `name` table rather than guessed from the filename.
+pub struct SystemFontInfo {
+    pub family: String,
+    pub style: String,
+    pub path: PathBuf,
+}
+
+/// Enumerate every font file under the platform's system font directories
+/// (the same directories `find_system_font` searches, plus `extra_dirs`),
+/// reading each file's `name` table for its real family/subfamily.
+/// De-duplicated by canonicalized path.
+pub fn enumerate_system_fonts(extra_dirs: &[PathBuf]) -> Vec<SystemFontInfo> {
+    let mut seen = std::collections::HashSet::new();
+    let mut fonts = Vec::new();
+
+    let mut dirs = extra_dirs.to_vec();
+    dirs.extend(get_system_font_dirs());
+
+    for dir in &dirs {
+        enumerate_fonts_in_dir(dir, &mut seen, &mut fonts);
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry.path().is_dir() {
+                    enumerate_fonts_in_dir(&entry.path(), &mut seen, &mut fonts);
+                }
+            }
+        }
+    }
+
+    fonts
+}
+
+fn enumerate_fonts_in_dir(
+    dir: &Path,
+    seen: &mut std::collections::HashSet<PathBuf>,
+    fonts: &mut Vec<SystemFontInfo>,
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_font_file = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf"))
+            .unwrap_or(false);
+        if !is_font_file {
+            continue;
+        }
+
+        let resolved = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !seen.insert(resolved.clone()) {
+            continue;
+        }
+
+        let font_data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        let face = match Face::parse(&font_data, 0) {
+            Ok(face) => face,
+            Err(_) => continue,
+        };
+
+        let family = face
+            .names()
+            .into_iter()
+            .find(|name| name.name_id == 1)
+            .and_then(|name| name.to_string())
+            .unwrap_or_else(|| {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("Unknown")
+                    .to_string()
+            });
+        let style = face
+            .names()
+            .into_iter()
+            .find(|name| name.name_id == 2)
+            .and_then(|name| name.to_string())
+            .unwrap_or_else(|| "Regular".to_string());
+
+        fonts.push(SystemFontInfo {
+            family,
+            style,
+            path: resolved,
+        });
+    }
+}
+
+/// WOFF 1.0 table directory entry, as laid out in the spec (20 bytes each,
+/// immediately following the 44-byte header).
+struct WoffTableEntry {
+    tag: u32,
+    offset: u32,
+    comp_length: u32,
+    orig_length: u32,
+    orig_checksum: u32,
+}
+
+/// Decode a WOFF 1.0 font (zlib-compressed SFNT tables) into a plain SFNT
+/// that `Face::parse`/`add_truetype_font` can consume directly.
+fn decode_woff1(data: &[u8]) -> PyResult<Vec<u8>> {
+    let woff_err = |e: std::io::Error| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Malformed WOFF font: {}", e))
+    };
+
+    let mut cursor = Cursor::new(data);
+    let _signature = cursor.read_u32::<BigEndian>().map_err(woff_err)?;
+    let flavor = cursor.read_u32::<BigEndian>().map_err(woff_err)?;
+    let _length = cursor.read_u32::<BigEndian>().map_err(woff_err)?;
+    let num_tables = cursor.read_u16::<BigEndian>().map_err(woff_err)?;
+    let _reserved = cursor.read_u16::<BigEndian>().map_err(woff_err)?;
+    let _total_sfnt_size = cursor.read_u32::<BigEndian>().map_err(woff_err)?;
+    // majorVersion, minorVersion, metaOffset, metaLength, metaOrigLength,
+    // privOffset, privLength: not needed to rebuild the SFNT.
+    cursor.set_position(cursor.position() + 2 + 2 + 4 + 4 + 4 + 4 + 4);
+
+    let mut entries = Vec::with_capacity(num_tables as usize);
+    for _ in 0..num_tables {
+        entries.push(WoffTableEntry {
+            tag: cursor.read_u32::<BigEndian>().map_err(woff_err)?,
+            offset: cursor.read_u32::<BigEndian>().map_err(woff_err)?,
+            comp_length: cursor.read_u32::<BigEndian>().map_err(woff_err)?,
+            orig_length: cursor.read_u32::<BigEndian>().map_err(woff_err)?,
+            orig_checksum: cursor.read_u32::<BigEndian>().map_err(woff_err)?,
+        });
+    }
+
+    let mut tables: Vec<(u32, u32, Vec<u8>)> = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let start = entry.offset as usize;
+        let end = start
+            .checked_add(entry.comp_length as usize)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>("WOFF table offset overflow")
+            })?;
+        let compressed = data.get(start..end).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>("WOFF table data out of bounds")
+        })?;
+
+        let table_data = if entry.comp_length != entry.orig_length {
+            let mut decoder = flate2::read::ZlibDecoder::new(compressed);
+            let mut out = Vec::with_capacity(entry.orig_length as usize);
+            decoder.read_to_end(&mut out).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Failed to inflate WOFF table: {}",
+                    e
+                ))
+            })?;
+            out
+        } else {
+            compressed.to_vec()
+        };
+
+        tables.push((entry.tag, entry.orig_checksum, table_data));
+    }
+
+    Ok(build_sfnt(flavor, &tables))
+}
+
+/// Decode a WOFF2 font (Brotli-compressed, transformed SFNT) into a plain
+/// SFNT via the `woff2_patched` crate.
+fn decode_woff2(data: &[u8]) -> PyResult<Vec<u8>> {
+    woff2_patched::decode::convert_woff2_to_ttf(&mut Cursor::new(data)).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to decode WOFF2 font: {}", e))
+    })
+}
+
+/// Assemble an SFNT (TTF/OTF) binary from a flavor tag and a set of
+/// `(tag, checksum, data)` tables, per the OpenType table directory layout.
+/// WOFF table directories are already sorted by tag, so the input order is
+/// preserved rather than re-sorted.
+fn build_sfnt(flavor: u32, tables: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+
+    let mut entry_selector = 0u16;
+    let mut max_pow = 1u16;
+    while max_pow.saturating_mul(2) <= num_tables {
+        max_pow *= 2;
+        entry_selector += 1;
+    }
+    let search_range = (max_pow as u32) * 16;
+    let range_shift = (num_tables as u32) * 16 - search_range;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&(search_range as u16).to_be_bytes());
+    out.extend_from_slice(&(range_shift as u16).to_be_bytes());
+
+    let header_len = 12 + 16 * tables.len();
+    let mut data_offset = header_len;
+    let mut directory = Vec::new();
+    let mut body = Vec::new();
+    for (tag, checksum, table_data) in tables {
+        let padded_len = (table_data.len() + 3) & !3;
+        directory.extend_from_slice(&tag.to_be_bytes());
+        directory.extend_from_slice(&checksum.to_be_bytes());
+        directory.extend_from_slice(&(data_offset as u32).to_be_bytes());
+        directory.extend_from_slice(&(table_data.len() as u32).to_be_bytes());
+
+        body.extend_from_slice(table_data);
+        body.resize(body.len() + (padded_len - table_data.len()), 0);
+        data_offset += padded_len;
+    }
+
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&body);
+    out
+}
+
+/// Load TTF/OTF/WOFF/WOFF2 font from file path, decoding web font
+/// compression to a plain SFNT so the rest of the pipeline only ever deals
+/// with raw TrueType/OpenType bytes.
 pub fn load_font_file(path: &Path) -> PyResult<Vec<u8>> {
     if !path.exists() {
         return Err(PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
@@ -447,14 +836,20 @@ pub fn load_font_file(path: &Path) -> PyResult<Vec<u8>> {
         ))
     })?;
 
-    let mut font_data = Vec::new();
-    file.read_to_end(&mut font_data).map_err(|e| {
+    let mut raw_data = Vec::new();
+    file.read_to_end(&mut raw_data).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
             "Failed to read font file {}: {}",
             path.display(), e
         ))
     })?;
 
+    let font_data = match raw_data.get(0..4) {
+        Some(b"wOFF") => decode_woff1(&raw_data)?,
+        Some(b"wOF2") => decode_woff2(&raw_data)?,
+        _ => raw_data,
+    };
+
     // Validate font using ttf-parser
     Face::parse(&font_data, 0).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -471,19 +866,119 @@ pub fn load_font_file_str(path: &str) -> PyResult<Vec<u8>> {
     load_font_file(Path::new(path))
 }
 
+/// Encode a Unicode code point as a ToUnicode CMap destination string: plain
+/// 4-digit hex UTF-16BE for the BMP, or a surrogate pair for code points
+/// above it (e.g. emoji), since `beginbfchar` destinations are UTF-16BE.
+fn utf16be_hex(code_point: u32) -> String {
+    if code_point <= 0xFFFF {
+        format!("{:04X}", code_point)
+    } else {
+        let c = code_point - 0x10000;
+        let high = 0xD800 + (c >> 10);
+        let low = 0xDC00 + (c & 0x3FF);
+        format!("{:04X}{:04X}", high, low)
+    }
+}
+
+/// Enumerate the glyphs covered by a GSUB `Coverage` table as
+/// `(glyph, coverage_index)` pairs, in coverage-index order.
+fn coverage_glyphs(coverage: &ttf_parser::opentype_layout::Coverage) -> Vec<(GlyphId, u16)> {
+    use ttf_parser::opentype_layout::Coverage;
+
+    match coverage {
+        Coverage::Format1 { glyphs } => glyphs
+            .into_iter()
+            .enumerate()
+            .map(|(i, glyph)| (glyph, i as u16))
+            .collect(),
+        Coverage::Format2 { records } => {
+            let mut out = Vec::new();
+            for record in *records {
+                let mut gid = record.start.0;
+                let mut index = record.value;
+                while gid <= record.end.0 {
+                    out.push((GlyphId(gid), index));
+                    gid += 1;
+                    index += 1;
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Parse the font's GSUB `liga`/`dlig` features into a lookup from the first
+/// glyph of a sequence to its ligature candidates (longest sequence first),
+/// so `draw_string` can greedily substitute runs of CIDs with their ligature
+/// glyph before the text is shown.
+fn parse_ligatures(face: &Face) -> LigatureTable {
+    use ttf_parser::gsub::SubstitutionSubtable;
+
+    let mut table: LigatureTable = HashMap::new();
+    let Some(gsub) = face.tables().gsub else {
+        return table;
+    };
+
+    let liga = Tag::from_bytes(b"liga");
+    let dlig = Tag::from_bytes(b"dlig");
+    let lookup_indices: Vec<u16> = gsub
+        .features
+        .into_iter()
+        .filter(|feature| feature.tag == liga || feature.tag == dlig)
+        .flat_map(|feature| feature.lookup_indices.into_iter())
+        .collect();
+
+    for lookup_index in lookup_indices {
+        let Some(lookup) = gsub.lookups.get(lookup_index) else {
+            continue;
+        };
+        for subtable in lookup
+            .subtables
+            .into_iter::<SubstitutionSubtable>()
+        {
+            let SubstitutionSubtable::Ligature(ligature_sub) = subtable else {
+                continue;
+            };
+            for (first_glyph, coverage_index) in coverage_glyphs(&ligature_sub.coverage) {
+                let Some(ligature_set) = ligature_sub.ligature_sets.get(coverage_index) else {
+                    continue;
+                };
+                for ligature in ligature_set {
+                    let mut sequence = vec![first_glyph.0];
+                    sequence.extend(ligature.components.into_iter().map(|g| g.0));
+                    table
+                        .entry(first_glyph.0)
+                        .or_default()
+                        .push((sequence, ligature.glyph.0));
+                }
+            }
+        }
+    }
+
+    // Longest sequence first so substitution matches greedily (e.g. "ffl"
+    // before "ff").
+    for candidates in table.values_mut() {
+        candidates.sort_by_key(|(sequence, _)| std::cmp::Reverse(sequence.len()));
+    }
+
+    table
+}
+
 /// Add TrueType font to PDF as Type0 font (CIDFontType2)
-/// Returns the font resource name and Unicode->CID mapping
+/// Returns the font resource name, Unicode->CID mapping, and decoration/width metrics
 pub fn add_truetype_font(
     pdf: &mut Pdf,
     font_data: &[u8],
     font_id: Ref,
     next_ref_id: &mut i32,
-) -> PyResult<(Name<'static>, CidMap)> {
+) -> PyResult<(Name<'static>, CidMap, FontMetrics)> {
     // Validate font using ttf-parser
     let face = Face::parse(font_data, 0).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid font file: {}", e))
     })?;
 
+    let ligatures = parse_ligatures(&face);
+
     // Get font metrics from TTF
     let units_per_em = face.units_per_em();
     let ascender = face.ascender() as f32;
@@ -494,6 +989,17 @@ pub fn add_truetype_font(
     let pdf_ascender = (ascender * scale) as i32;
     let pdf_descender = (descender * scale) as i32;
 
+    // Underline comes from the `post` table, strikeout from OS/2; fall back to
+    // reasonable defaults (matching common TrueType conventions) when a font omits them.
+    let (underline_position, underline_thickness) = face
+        .underline_metrics()
+        .map(|m| (m.position as f32 * scale, m.thickness.max(1) as f32 * scale))
+        .unwrap_or((-100.0, 50.0));
+    let (strikeout_position, strikeout_thickness) = face
+        .strikeout_metrics()
+        .map(|m| (m.position as f32 * scale, m.thickness.max(1) as f32 * scale))
+        .unwrap_or((pdf_ascender as f32 * 0.4, 50.0));
+
     // Get font bounding box
     let bbox = face.global_bounding_box();
     let pdf_bbox = [
@@ -515,6 +1021,7 @@ pub fn add_truetype_font(
     let mut cid_map = HashMap::new();
     let mut cid_to_gid_map = Vec::new();
     let mut cid_widths: BTreeMap<u16, i32> = BTreeMap::new();
+    let mut codepoint_widths: HashMap<u32, i32> = HashMap::new();
 
     let default_width = 500_i32;
 
@@ -532,14 +1039,13 @@ pub fn add_truetype_font(
                 }
                 cid_to_gid_map[cid as usize] = gid;
 
-                if !cid_widths.contains_key(&cid) {
-                    let width_pdf = face
-                        .glyph_hor_advance(glyph_id)
-                        .map(|adv| ((adv as f32) * scale).round() as i32)
-                        .unwrap_or(default_width)
-                        .max(0);
-                    cid_widths.insert(cid, width_pdf);
-                }
+                let width_pdf = face
+                    .glyph_hor_advance(glyph_id)
+                    .map(|adv| ((adv as f32) * scale).round() as i32)
+                    .unwrap_or(default_width)
+                    .max(0);
+                cid_widths.entry(cid).or_insert(width_pdf);
+                codepoint_widths.insert(code_point, width_pdf);
             }
         }
     }
@@ -558,24 +1064,41 @@ pub fn add_truetype_font(
     let cid_font_id = Ref::new(*next_ref_id);
     *next_ref_id += 1;
 
-    // Create CIDToGIDMap stream
+    // OpenType fonts with CFF outlines (from OTF, or a decoded WOFF/WOFF2)
+    // have no `glyf` table for CIDFontType2/CIDToGIDMap to point at. They're
+    // embedded as a bare CFF program (CIDFontType0C) under a CIDFontType0
+    // descendant instead, with CID == GID (our cmap-derived CIDs already are
+    // glyph indices, so this needs no separate CID->GID table).
+    let is_cff = face.tables().cff.is_some();
+
+    // Create CIDToGIDMap stream (CIDFontType2/glyf path only)
     let cid_to_gid_map_id = Ref::new(*next_ref_id);
     *next_ref_id += 1;
 
-    let mut cid_to_gid_bytes = Vec::new();
-    for gid in &cid_to_gid_map {
-        cid_to_gid_bytes.push((gid >> 8) as u8);
-        cid_to_gid_bytes.push((gid & 0xFF) as u8);
+    if !is_cff {
+        let mut cid_to_gid_bytes = Vec::new();
+        for gid in &cid_to_gid_map {
+            cid_to_gid_bytes.push((gid >> 8) as u8);
+            cid_to_gid_bytes.push((gid & 0xFF) as u8);
+        }
+        pdf.stream(cid_to_gid_map_id, &cid_to_gid_bytes);
     }
 
-    pdf.stream(cid_to_gid_map_id, &cid_to_gid_bytes);
-
-    // Embed font file as stream
+    // Embed font file as stream.
     let font_file_id = Ref::new(*next_ref_id);
     *next_ref_id += 1;
 
-    pdf.stream(font_file_id, font_data)
-        .pair(Name(b"Length1"), font_data.len() as i32);
+    if is_cff {
+        let cff_data = face
+            .raw_face()
+            .table(ttf_parser::Tag::from_bytes(b"CFF "))
+            .unwrap_or(font_data);
+        pdf.stream(font_file_id, cff_data)
+            .pair(Name(b"Subtype"), Name(b"CIDFontType0C"));
+    } else {
+        pdf.stream(font_file_id, font_data)
+            .pair(Name(b"Length1"), font_data.len() as i32);
+    }
 
     // Create ToUnicode CMap stream
     let to_unicode_id = Ref::new(*next_ref_id);
@@ -587,11 +1110,36 @@ pub fn add_truetype_font(
         .collect();
     cid_unicode_pairs.sort_by_key(|&(cid, _)| cid);
 
+    // Ligature glyphs aren't reachable through the cmap, so copy/paste needs
+    // its own ToUnicode entries mapping the ligature CID back to the full
+    // character sequence it replaces (e.g. the "fi" ligature -> "fi").
+    let gid_to_unicode: HashMap<u16, u32> =
+        cid_unicode_pairs.iter().map(|&(cid, unicode)| (cid, unicode)).collect();
+    let mut ligature_unicode_pairs: Vec<(u16, String)> = Vec::new();
+    for candidates in ligatures.values() {
+        for (sequence, ligature_gid) in candidates {
+            let hex: Option<String> = sequence
+                .iter()
+                .map(|gid| gid_to_unicode.get(gid).map(|&u| utf16be_hex(u)))
+                .collect();
+            if let Some(hex) = hex {
+                ligature_unicode_pairs.push((*ligature_gid, hex));
+            }
+        }
+    }
+
     let mut cmap_sections = String::new();
     for chunk in cid_unicode_pairs.chunks(100) {
         cmap_sections.push_str(&format!("{} beginbfchar\n", chunk.len()));
         for (cid, unicode) in chunk {
-            cmap_sections.push_str(&format!("<{:04X}> <{:04X}>\n", cid, unicode));
+            cmap_sections.push_str(&format!("<{:04X}> <{}>\n", cid, utf16be_hex(*unicode)));
+        }
+        cmap_sections.push_str("endbfchar\n");
+    }
+    for chunk in ligature_unicode_pairs.chunks(100) {
+        cmap_sections.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (cid, hex) in chunk {
+            cmap_sections.push_str(&format!("<{:04X}> <{}>\n", cid, hex));
         }
         cmap_sections.push_str("endbfchar\n");
     }
@@ -644,15 +1192,19 @@ end",
             .ascent(pdf_ascender as f32)
             .descent(pdf_descender as f32)
             .cap_height(pdf_ascender as f32)
-            .stem_v(80.0)
-            .font_file2(font_file_id);
+            .stem_v(80.0);
+        if is_cff {
+            font_descriptor.font_file3(font_file_id);
+        } else {
+            font_descriptor.font_file2(font_file_id);
+        }
     }
 
     // Build CIDFont object with widths
     {
         let mut cid_font = pdf.cid_font(cid_font_id);
         cid_font
-            .subtype(CidFontType::Type2)
+            .subtype(if is_cff { CidFontType::Type0 } else { CidFontType::Type2 })
             .base_font(base_font_name)
             .system_info(SystemInfo {
                 registry: Str(b"Adobe"),
@@ -660,8 +1212,10 @@ end",
                 supplement: 0,
             })
             .font_descriptor(font_descriptor_id)
-            .default_width(default_width as f32)
-            .cid_to_gid_map_stream(cid_to_gid_map_id);
+            .default_width(default_width as f32);
+        if !is_cff {
+            cid_font.cid_to_gid_map_stream(cid_to_gid_map_id);
+        }
 
         {
             let mut widths_writer = cid_font.widths();
@@ -700,34 +1254,45 @@ end",
     let font_name_static = Box::leak(font_name_boxed);
     let font_name_bytes = font_name_static.as_bytes();
 
-    Ok((Name(font_name_bytes), cid_map))
+    let metrics = FontMetrics {
+        underline_position,
+        underline_thickness,
+        strikeout_position,
+        strikeout_thickness,
+        ascender: pdf_ascender as f32,
+        descender: pdf_descender as f32,
+        widths: codepoint_widths,
+        ligatures,
+    };
+
+    Ok((Name(font_name_bytes), cid_map, metrics))
 }
 
 // Keep old functions for backward compatibility but mark as deprecated
 #[deprecated(note = "Use find_system_font instead")]
 pub fn find_dejavu_sans() -> Option<String> {
-    find_system_font("DejaVu Sans", FontStyle::Regular)
-        .or_else(|| get_fallback_font(FontStyle::Regular))
+    find_system_font("DejaVu Sans", FontStyle::REGULAR, &[])
+        .or_else(|| get_fallback_font(FontStyle::REGULAR, &[]))
         .map(|p| p.to_string_lossy().to_string())
 }
 
 #[deprecated(note = "Use find_system_font instead")]
 pub fn find_dejavu_sans_bold() -> Option<String> {
-    find_system_font("DejaVu Sans", FontStyle::Bold)
-        .or_else(|| get_fallback_font(FontStyle::Bold))
+    find_system_font("DejaVu Sans", FontStyle::BOLD, &[])
+        .or_else(|| get_fallback_font(FontStyle::BOLD, &[]))
         .map(|p| p.to_string_lossy().to_string())
 }
 
 #[deprecated(note = "Use find_system_font instead")]
 pub fn find_dejavu_sans_italic() -> Option<String> {
-    find_system_font("DejaVu Sans", FontStyle::Italic)
-        .or_else(|| get_fallback_font(FontStyle::Italic))
+    find_system_font("DejaVu Sans", FontStyle::ITALIC, &[])
+        .or_else(|| get_fallback_font(FontStyle::ITALIC, &[]))
         .map(|p| p.to_string_lossy().to_string())
 }
 
 #[deprecated(note = "Use find_system_font instead")]
 pub fn find_dejavu_sans_bold_italic() -> Option<String> {
-    find_system_font("DejaVu Sans", FontStyle::BoldItalic)
-        .or_else(|| get_fallback_font(FontStyle::BoldItalic))
+    find_system_font("DejaVu Sans", FontStyle::BOLD_ITALIC, &[])
+        .or_else(|| get_fallback_font(FontStyle::BOLD_ITALIC, &[]))
         .map(|p| p.to_string_lossy().to_string())
 }
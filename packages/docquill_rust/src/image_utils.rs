@@ -1,16 +1,72 @@
 //! Image utilities for PDF rendering
 
-use image::DynamicImage;
-use pdf_writer::{Name, Pdf, Ref};
+use image::codecs::jpeg::JpegDecoder;
+use image::codecs::png::PngDecoder;
+use image::{DynamicImage, ImageDecoder, ImageFormat};
+use pdf_writer::types::{PaintType, TilingType};
+use pdf_writer::{Content, Name, Pdf, Ref};
 use pyo3::prelude::*;
+use std::io::Cursor;
+
+/// Read the embedded ICC color profile out of `image_data`, if any. Only PNG
+/// (`iCCP`) and JPEG (`APP2`) carry one in formats we commonly receive;
+/// anything else -- or a read error -- reports no profile rather than
+/// failing the whole image, since an untagged image still renders fine as
+/// DeviceRGB.
+pub fn extract_icc_profile(image_data: &[u8]) -> Option<Vec<u8>> {
+    match image::guess_format(image_data) {
+        Ok(ImageFormat::Png) => PngDecoder::new(Cursor::new(image_data))
+            .ok()
+            .and_then(|mut d| d.icc_profile()),
+        Ok(ImageFormat::Jpeg) => JpegDecoder::new(Cursor::new(image_data))
+            .ok()
+            .and_then(|mut d| d.icc_profile()),
+        _ => None,
+    }
+}
+
+/// Reduce an RGB buffer to a single gray channel, taking the red component
+/// of each pixel (caller has already established R == G == B for all of
+/// them, so any channel would do).
+fn rgb_to_gray8(rgb_data: &[u8]) -> Vec<u8> {
+    rgb_data.chunks_exact(3).map(|c| c[0]).collect()
+}
+
+/// Pack a row-major 8-bit grayscale buffer of pure black (0) / white (255)
+/// pixels into 1-bit-per-component `/DeviceGray` data: one bit per pixel,
+/// MSB first, each row padded out to a byte boundary as the PDF image
+/// stream format requires. A set bit is white (gray 1.0), matching
+/// `/DeviceGray`'s 0 = black / max = white convention.
+fn pack_bilevel_gray(gray8: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row_bytes = (width as usize).div_ceil(8);
+    let mut packed = vec![0u8; row_bytes * height as usize];
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            if gray8[y * width as usize + x] != 0 {
+                packed[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+    packed
+}
 
 /// Add image to PDF from bytes
-/// Returns the XObject name for the image
+/// Returns the XObject name for the image. `icc_profile`, if given, is
+/// written as an `/ICCBased` color space stream and referenced from the
+/// image's `/ColorSpace` instead of plain `/DeviceRGB` (grayscale images
+/// ignore it -- the profile's 3-component curve doesn't apply to them).
+/// `grayscale` forces (`Some(true)`) or forbids (`Some(false)`) emitting a
+/// single-component `/DeviceGray` image; `None` auto-detects by checking
+/// whether every pixel already has R == G == B. A grayscale image whose
+/// pixels are pure black/white packs down further to 1 bit per component.
 pub fn add_image_to_pdf(
     pdf: &mut Pdf,
     image_data: &[u8],
     image_id: Ref,
     next_ref_id: &mut i32,
+    interpolate: bool,
+    icc_profile: Option<&[u8]>,
+    grayscale: Option<bool>,
 ) -> PyResult<Name<'static>> {
     // Try to decode image
     let img = image::load_from_memory(image_data).map_err(|e| {
@@ -59,13 +115,47 @@ pub fn add_image_to_pdf(
         }
     }
 
+    let is_grayscale = grayscale
+        .unwrap_or_else(|| rgb_data.chunks_exact(3).all(|c| c[0] == c[1] && c[1] == c[2]));
+
+    let (pixel_data, bits_per_component) = if is_grayscale {
+        let gray8 = rgb_to_gray8(&rgb_data);
+        if gray8.iter().all(|&v| v == 0 || v == 255) {
+            (pack_bilevel_gray(&gray8, width, height), 1)
+        } else {
+            (gray8, 8)
+        }
+    } else {
+        (rgb_data, 8)
+    };
+
+    // If an ICC profile is present, write it as its own stream object first
+    // (avoids overlapping mutable borrows of `pdf` with the image xobject).
+    // Doesn't apply to grayscale images -- the profile describes a
+    // 3-component (RGB) transform.
+    let icc_id_opt = (!is_grayscale).then_some(icc_profile).flatten().map(|profile| {
+        let icc_id = Ref::new(*next_ref_id);
+        *next_ref_id += 1;
+        pdf.icc_profile(icc_id, profile).n(3);
+        icc_id
+    });
+
     // Create image XObject
     {
-        let mut xobject = pdf.image_xobject(image_id, &rgb_data);
+        let mut xobject = pdf.image_xobject(image_id, &pixel_data);
         xobject.width(width as i32);
         xobject.height(height as i32);
-        xobject.color_space().device_rgb();
-        xobject.bits_per_component(8);
+        if is_grayscale {
+            xobject.color_space().device_gray();
+        } else if let Some(icc_id) = icc_id_opt {
+            xobject.color_space().icc_based(icc_id);
+        } else {
+            xobject.color_space().device_rgb();
+        }
+        xobject.bits_per_component(bits_per_component);
+        if interpolate {
+            xobject.interpolate(true);
+        }
         if let Some(smask_id) = smask_id_opt {
             xobject.s_mask(smask_id);
         }
@@ -80,3 +170,75 @@ pub fn add_image_to_pdf(
 
     Ok(Name(image_name_bytes))
 }
+
+/// Write a blank placeholder page thumbnail, to be referenced from the page's
+/// `/Thumb` entry. This crate has no PDF content-stream rasterizer -- the
+/// tiny-skia/resvg path only rasterizes SVG input, not arbitrary PDF drawing
+/// operators -- so unlike a real viewer-generated thumbnail, this is a flat
+/// white image sized to the page's aspect ratio rather than a preview of its
+/// actual content.
+pub fn add_placeholder_thumbnail(pdf: &mut Pdf, thumb_id: Ref, width: u32, height: u32) {
+    let rgb_data = vec![0xFFu8; width as usize * height as usize * 3];
+    let mut xobject = pdf.image_xobject(thumb_id, &rgb_data);
+    xobject.width(width as i32);
+    xobject.height(height as i32);
+    xobject.color_space().device_rgb();
+    xobject.bits_per_component(8);
+}
+
+/// Decode just enough of `image_data` to report its pixel dimensions, used
+/// to size a tiling pattern's default tile to the image's natural size.
+pub fn image_dimensions(image_data: &[u8]) -> PyResult<(u32, u32)> {
+    let img = image::load_from_memory(image_data).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to decode image: {}", e))
+    })?;
+    Ok((img.width(), img.height()))
+}
+
+/// Register `image_data` as a `PatternType 1` (tiling) pattern whose single
+/// cell paints the image scaled to `tile_width` x `tile_height`, and return
+/// the pattern's resource name. The pattern's image XObject lives entirely
+/// in the pattern's own `/Resources` -- unlike `canvas_draw_image`'s images,
+/// it doesn't need to be added to the page's resource dictionary too.
+pub fn add_image_tiling_pattern(
+    pdf: &mut Pdf,
+    image_data: &[u8],
+    pattern_id: Ref,
+    image_id: Ref,
+    next_ref_id: &mut i32,
+    tile_width: f64,
+    tile_height: f64,
+) -> PyResult<Name<'static>> {
+    let image_name = add_image_to_pdf(pdf, image_data, image_id, next_ref_id, false, None, None)?;
+
+    // Pattern cell content: paint the (unit-square) image XObject scaled up
+    // to fill the tile's bounding box.
+    let mut cell = Content::new();
+    cell.save_state();
+    cell.transform([tile_width as f32, 0.0, 0.0, tile_height as f32, 0.0, 0.0]);
+    cell.x_object(image_name);
+    cell.restore_state();
+    let cell_bytes = cell.finish();
+
+    {
+        let mut pattern = pdf.tiling_pattern(pattern_id, &cell_bytes);
+        pattern.paint_type(PaintType::Colored);
+        pattern.tiling_type(TilingType::ConstantSpacing);
+        pattern.bbox(pdf_writer::Rect::new(
+            0.0,
+            0.0,
+            tile_width as f32,
+            tile_height as f32,
+        ));
+        pattern.x_step(tile_width as f32);
+        pattern.y_step(tile_height as f32);
+        let mut resources = pattern.resources();
+        let mut xobjects = resources.x_objects();
+        xobjects.pair(image_name, image_id);
+    }
+
+    let pattern_name_str = format!("Pat{}", pattern_id.get());
+    let pattern_name_boxed = pattern_name_str.into_boxed_str();
+    let pattern_name_static = Box::leak(pattern_name_boxed);
+    Ok(Name(pattern_name_static.as_bytes()))
+}
@@ -19,20 +19,61 @@ pub mod wmf;
 mod svg_writer;
 mod emfplus;
 mod emf_records;
+mod emf_to_pdf;
+mod wmf_records;
 
-use pdf_writer::{Finish, Name, Pdf, Ref};
+use pdf_writer::types::{AnnotationIcon, AnnotationType, BlendMode, PageMode, RenderingIntent};
+use pdf_writer::writers::{Destination, StructTreeRoot};
+use pdf_writer::{Finish, Name, Null, Pdf, Ref, Str, TextStr};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
-use canvas::PdfCanvas;
-use font_utils::{FontStyle, find_system_font, get_fallback_font, load_font_file, add_truetype_font};
+use canvas::{PdfCanvas, StrokeAlign};
+use font_utils::{FontStyle, FontMetrics, find_system_font, get_fallback_font, load_font_file, add_truetype_font, enumerate_system_fonts};
 use types::{Color, Rect};
 
 /// Map Unicode code point to CID (Character ID) for Type0 fonts
 pub type CidMap = HashMap<u32, u16>;
 
+/// Source of `PdfCanvasRenderer::instance_id`. Renderers are frequently
+/// constructed and used concurrently within a single process (see
+/// `pdf_compiler.py`'s `ThreadPoolExecutor`-based `render_page`), so pid
+/// alone isn't enough to keep their spooled temp files from colliding.
+static NEXT_INSTANCE_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// A node in the tagged-PDF structure tree, built up by
+/// `canvas_begin_tag()`/`canvas_end_tag()` and written to `/StructTreeRoot` at
+/// `save()`.
+struct StructElemData {
+    role: String,
+    parent: Option<Ref>,
+    page: Ref,
+    kids: Vec<StructKid>,
+    alt_text: Option<String>,
+}
+
+/// A child of a structure element: either a marked-content span on its page
+/// or a nested structure element.
+enum StructKid {
+    Mcid(i32),
+    Elem(Ref),
+}
+
+/// A queued annotation from `add_text_annotation()`/`add_highlight_annotation()`/
+/// `add_link_annotation()`, held in `pending_annotations` until the page it
+/// targets is finalized (at the next `new_page()` call or at `save()`),
+/// since a page's `/Annots` array can only be written once its dictionary
+/// is.
+enum AnnotationSpec {
+    Text { x: f64, y: f64, contents: String, author: Option<String>, icon: String },
+    Highlight { quad_points: Vec<f64>, contents: String },
+    Link { x: f64, y: f64, width: f64, height: f64, dest_name: String },
+}
+
 // CanvasCommand is now parsed directly from Python dicts in canvas_run_batch
 // This avoids pyo3 enum parsing complexity while maintaining zero-copy performance
 
@@ -50,33 +91,180 @@ pub struct PdfCanvasRenderer {
     // Font registry: font_key -> (Name, Ref)
     // font_key = "family:style" e.g. "Calibri:Regular", "Arial:Bold"
     font_registry: HashMap<String, (Name<'static>, Ref)>,
-    fonts_used_on_current_page: HashMap<Name<'static>, Ref>,
+    fonts_used_on_current_page: BTreeMap<Name<'static>, Ref>,
     next_font_id: u32,
     // CID maps for Type0 fonts: font_name -> Unicode -> CID mapping
     type0_cid_maps: HashMap<Name<'static>, CidMap>,
-    // ExtGState registry (opacity, etc.)
-    ext_graphics_states: HashMap<u32, (Name<'static>, Ref)>,
-    ext_graphics_states_used_on_current_page: HashMap<Name<'static>, Ref>,
+    // Decoration/width metrics for Type0 fonts: font_name -> FontMetrics
+    font_metrics: HashMap<Name<'static>, FontMetrics>,
+    // Underline/strikethrough flags applied to subsequently drawn strings
+    text_decoration: (bool, bool),
+    // Glyph fallback chain, in priority order, consulted when the active font
+    // lacks a code point. Populated via register_fallback_fonts().
+    fallback_fonts: Vec<(Name<'static>, Ref)>,
+    // Extra directories to search before the built-in platform font paths.
+    // Populated via set_font_search_paths(). Lets deployments (e.g. minimal
+    // Docker images) bundle fonts outside the usual system locations.
+    font_search_paths: Vec<PathBuf>,
+    // Family names to try, in order, when a requested family isn't found at
+    // all, before falling back to the bundled DejaVu Sans. Populated via
+    // set_fallback_chain().
+    fallback_chain: Vec<String>,
+    // Per-font synthesis flags (synth_bold, synth_italic), set in resolve_font()
+    // when find_font_path had to fall back to a Regular file for a requested
+    // bold/italic style. Consulted by canvas_draw_string to fatten/slant glyphs.
+    synthetic_styles: HashMap<Name<'static>, (bool, bool)>,
+    // Opt-out for synthetic bold/italic; set via set_synthesize_styles().
+    synthesize_styles: bool,
+    // Opt-out for GSUB liga/dlig ligature substitution; set via set_ligatures().
+    ligatures_enabled: bool,
+    // ExtGState registry, keyed by (alpha_key, blend_mode) so opacity and
+    // blend mode combine into a single shared ExtGState when both are set.
+    ext_graphics_states: HashMap<(u32, Option<String>), (Name<'static>, Ref)>,
+    ext_graphics_states_used_on_current_page: BTreeMap<Name<'static>, Ref>,
+    // Current alpha/blend mode, applied together whenever either changes via
+    // canvas_set_opacity()/canvas_set_blend_mode(). Reset implicitly by the
+    // PDF `Q` operator (restore_state()), same as every other ExtGState key.
+    current_opacity: f64,
+    current_blend_mode: Option<String>,
+    // Mirrors canvas.rs's own state_stack, since opacity/blend mode are
+    // tracked here rather than in PdfCanvas -- pushed/popped in lockstep
+    // with canvas_save_state()/canvas_restore_state() so "reset on
+    // restore_state" matches the `q`/`Q` operators they already emit.
+    graphics_state_stack: Vec<(f64, Option<String>)>,
     // Image registry
-    images_used_on_current_page: HashMap<Name<'static>, Ref>,
+    images_used_on_current_page: BTreeMap<Name<'static>, Ref>,
     images_registry: HashMap<String, (Ref, Name<'static>)>,
     next_image_id: i32,
+    // Tiling patterns registered via canvas_fill_with_image_pattern(),
+    // keyed by pattern name, written to the page's /Pattern resource dict.
+    patterns_used_on_current_page: BTreeMap<Name<'static>, Ref>,
     // Default font (loaded lazily on first use)
     default_font_loaded: bool,
+    // Explicit default font file, set via the constructor's `default_font_path`
+    // argument. When present, `find_font_path` prefers it over the bundled
+    // DejaVu Sans / system sans-serif search once no requested family (or
+    // configured fallback_chain entry) matches.
+    default_font_path: Option<PathBuf>,
+    // When true (set via the constructor's `streaming` argument), finished
+    // page content streams are spooled to temp files instead of being handed
+    // to `self.pdf` right away, keeping `pdf_writer`'s internal output buffer
+    // from growing until `save()` splices them back in. `pdf_writer` has no
+    // API for writing indirect objects straight to a file, so this only
+    // bounds the *content stream* portion of memory use, not the whole `Pdf`.
+    streaming: bool,
+    // (content_id, temp file path) pairs awaiting `save()` when `streaming`
+    // is enabled, in the order pages were finished.
+    pending_streamed_contents: Vec<(Ref, PathBuf)>,
+    // Unique id for this renderer instance, mixed into spooled temp file
+    // names alongside the process id. `pdf_compiler.py`'s `render_page`
+    // renders pages concurrently via a `ThreadPoolExecutor`, so multiple
+    // `PdfCanvasRenderer`s share a pid and start their `Ref` numbering from
+    // the same baseline -- pid+content_id alone can collide across
+    // instances. Assigned from a process-wide atomic counter at construction.
+    instance_id: u64,
+    // ===== Tagged PDF (structure tree) =====
+    // Struct elements created via canvas_begin_tag()/canvas_end_tag(), keyed by
+    // their allocated Ref. Written to /StructTreeRoot at save().
+    struct_elements: HashMap<Ref, StructElemData>,
+    // Top-level structure elements (opened with no enclosing tag); becomes the
+    // eventual /StructTreeRoot's /K array.
+    struct_tree_roots: Vec<Ref>,
+    // Stack of currently-open structure elements, innermost last.
+    tag_stack: Vec<Ref>,
+    // MCID counter for the page currently being drawn, reset in new_page().
+    next_mcid: i32,
+    // Ordered (by MCID) owning struct element for each marked-content span
+    // opened on the current page so far; becomes that page's /ParentTree entry
+    // once the page is finalized.
+    current_page_parent_tree: Vec<Ref>,
+    // Finalized per-page MCID->struct-element arrays, indexed by the page's
+    // /StructParents key (its index in this Vec).
+    parent_tree_entries: Vec<Vec<Ref>>,
+    // Annotations queued via add_text_annotation()/add_highlight_annotation()/
+    // add_link_annotation(), keyed by target page index, written into that
+    // page's /Annots array when the page is finalized.
+    pending_annotations: HashMap<u32, Vec<AnnotationSpec>>,
+    // Named destinations added via add_named_destination(), keyed by name,
+    // written to the catalog's /Names /Dests name tree at save(). Letting
+    // links target a name instead of a raw page index decouples link
+    // creation from page finalization order -- we often add links before we
+    // know which page a destination will end up on.
+    named_destinations: HashMap<String, (u32, f64, f64, Option<f64>)>,
+    // Optional callback invoked from new_page() with (page_index, total_pages)
+    // so long renders can report progress. Set via set_progress_callback().
+    progress_callback: Option<PyObject>,
+    // Total page count, if known in advance, passed to the progress callback
+    // as its second argument. Set via set_total_pages().
+    total_pages: Option<u32>,
+    // Number of pages started so far, passed to the progress callback as its
+    // first argument.
+    pages_started: u32,
+    // When true, an RGB component outside [0, 1] but within (1, 255] is
+    // assumed to be 0-255 scale and divided by 255 instead of clamped. Set
+    // via the constructor's auto_scale_colors flag.
+    auto_scale_colors: bool,
+    // Whether the one-time out-of-range color warning has already been
+    // logged, so repeated calls at 0-255 scale don't spam the log.
+    color_range_warned: bool,
+    // Whether to attach a `/Thumb` placeholder image to each finalized page.
+    // Off by default since it adds an extra object per page. Set via
+    // set_generate_thumbnails().
+    generate_thumbnails: bool,
+    // Whether the one-time "thumbnails are fake" warning has already been
+    // logged, so enabling generate_thumbnails doesn't spam the log once per
+    // page. Set via set_generate_thumbnails().
+    thumbnail_warned: bool,
+    // Long-edge size in pixels for generated thumbnails (default 106, like
+    // Acrobat). Set via set_generate_thumbnails().
+    thumbnail_max_size: u32,
+    // Requested via set_linearize(); see its doc comment for why this
+    // currently only produces a warning rather than an actually linearized
+    // file.
+    linearize: bool,
+    // Viewer preferences set via set_viewer_preferences(), written to the
+    // catalog's /ViewerPreferences dictionary at save():
+    // (hide_toolbar, hide_menubar, fit_window, center_window, display_doc_title).
+    viewer_preferences: Option<(bool, bool, bool, bool, bool)>,
+    // Document title, settable via set_viewer_preferences()'s `title`
+    // argument, written to the document information dictionary at save().
+    // Meant to be paired with display_doc_title=true so viewers show this
+    // instead of the filename.
+    document_title: Option<String>,
+    // Initial view set via set_open_action(): (page_index, zoom), written
+    // as the catalog's /OpenAction (an /XYZ destination with the current
+    // scroll position preserved and only the zoom applied) and /PageMode
+    // /UseOutlines at save(), so the document opens with the bookmarks
+    // panel showing.
+    open_action: Option<(u32, f64)>,
+    // ICC profile bytes assigned to images with no embedded profile of their
+    // own, via set_default_rgb_profile(). Images that already carry an
+    // ICC profile keep it; this only backfills untagged ones so they get an
+    // explicit /ICCBased color space (e.g. sRGB) instead of bare DeviceRGB,
+    // for PDF/A compatibility.
+    default_rgb_profile: Option<Vec<u8>>,
 }
 
 #[pymethods]
 impl PdfCanvasRenderer {
     #[new]
-    fn new(output_path: String, _page_width: f64, _page_height: f64) -> Self {
-        let mut pdf = Pdf::new();
+    #[pyo3(signature = (output_path, _page_width, _page_height, auto_scale_colors=false, default_font_path=None, streaming=false))]
+    fn new(
+        output_path: String,
+        _page_width: f64,
+        _page_height: f64,
+        auto_scale_colors: bool,
+        default_font_path: Option<String>,
+        streaming: bool,
+    ) -> Self {
+        let pdf = Pdf::new();
 
         // Create references
         let catalog_id = Ref::new(1);
         let page_tree_id = Ref::new(2);
 
-        // Set up catalog
-        pdf.catalog(catalog_id).pages(page_tree_id);
+        // Catalog is written at save() instead of here, since whether it needs
+        // /StructTreeRoot and /MarkInfo isn't known until all tagging is done.
 
         // Start ref IDs after catalog and page tree
         let next_ref = 3;
@@ -94,15 +282,52 @@ impl PdfCanvasRenderer {
             catalog_id: Some(catalog_id),
             next_ref_id: next_ref,
             font_registry: HashMap::new(),
-            fonts_used_on_current_page: HashMap::new(),
+            fonts_used_on_current_page: BTreeMap::new(),
             next_font_id: 1,
             type0_cid_maps: HashMap::new(),
+            font_metrics: HashMap::new(),
+            text_decoration: (false, false),
+            fallback_fonts: Vec::new(),
+            font_search_paths: Vec::new(),
+            fallback_chain: Vec::new(),
+            synthetic_styles: HashMap::new(),
+            synthesize_styles: true,
+            ligatures_enabled: true,
             ext_graphics_states: HashMap::new(),
-            ext_graphics_states_used_on_current_page: HashMap::new(),
-            images_used_on_current_page: HashMap::new(),
+            ext_graphics_states_used_on_current_page: BTreeMap::new(),
+            current_opacity: 1.0,
+            current_blend_mode: None,
+            graphics_state_stack: Vec::new(),
+            images_used_on_current_page: BTreeMap::new(),
+            patterns_used_on_current_page: BTreeMap::new(),
             images_registry: HashMap::new(),
             next_image_id: 2000,
             default_font_loaded: false,
+            default_font_path: default_font_path.map(PathBuf::from),
+            streaming,
+            pending_streamed_contents: Vec::new(),
+            instance_id: NEXT_INSTANCE_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            struct_elements: HashMap::new(),
+            struct_tree_roots: Vec::new(),
+            tag_stack: Vec::new(),
+            next_mcid: 0,
+            current_page_parent_tree: Vec::new(),
+            parent_tree_entries: Vec::new(),
+            pending_annotations: HashMap::new(),
+            named_destinations: HashMap::new(),
+            progress_callback: None,
+            total_pages: None,
+            pages_started: 0,
+            auto_scale_colors,
+            color_range_warned: false,
+            generate_thumbnails: false,
+            thumbnail_warned: false,
+            thumbnail_max_size: 106,
+            linearize: false,
+            viewer_preferences: None,
+            document_title: None,
+            open_action: None,
+            default_rgb_profile: None,
         }
     }
 
@@ -111,12 +336,23 @@ impl PdfCanvasRenderer {
         // Save current page content and finalize page
         if let Some((_page_id, content_id, canvas)) = self.current_page.take() {
             let content_bytes = canvas.finish();
-            self.pdf.stream(content_id, &content_bytes);
+            self.finalize_content_stream(content_id, content_bytes)?;
 
             // Create and finish the previous page
             if let Some((prev_page_info_id, prev_page_width, prev_page_height)) =
                 self.current_page_info.take()
             {
+                let thumb_id = if self.generate_thumbnails {
+                    let (tw, th) =
+                        thumbnail_dimensions(prev_page_width, prev_page_height, self.thumbnail_max_size);
+                    let id = Ref::new(self.next_ref_id);
+                    self.next_ref_id += 1;
+                    image_utils::add_placeholder_thumbnail(&mut self.pdf, id, tw, th);
+                    Some(id)
+                } else {
+                    None
+                };
+
                 let mut page = self.pdf.page(prev_page_info_id);
                 page.media_box(pdf_writer::Rect::new(
                     0.0,
@@ -150,6 +386,28 @@ impl PdfCanvasRenderer {
                             ext_states.pair(*name, *gs_ref);
                         }
                     }
+                    if !self.patterns_used_on_current_page.is_empty() {
+                        let mut patterns = resources.patterns();
+                        for (pattern_name, pattern_id) in &self.patterns_used_on_current_page {
+                            patterns.pair(*pattern_name, *pattern_id);
+                        }
+                    }
+                }
+
+                if !self.current_page_parent_tree.is_empty() {
+                    let key = self.parent_tree_entries.len() as i32;
+                    page.struct_parents(key);
+                    self.parent_tree_entries
+                        .push(std::mem::take(&mut self.current_page_parent_tree));
+                }
+
+                let finalized_page_index = self.pages.len() as u32 - 1;
+                if let Some(specs) = self.pending_annotations.remove(&finalized_page_index) {
+                    write_annotations(&mut page.annotations(), &specs);
+                }
+
+                if let Some(thumb_id) = thumb_id {
+                    page.thumbnail(thumb_id);
                 }
 
                 page.finish();
@@ -160,6 +418,8 @@ impl PdfCanvasRenderer {
         self.images_used_on_current_page.clear();
         self.fonts_used_on_current_page.clear();
         self.ext_graphics_states_used_on_current_page.clear();
+        self.patterns_used_on_current_page.clear();
+        self.next_mcid = 0;
 
         // Create new page references
         let page_id = Ref::new(self.next_ref_id);
@@ -177,6 +437,14 @@ impl PdfCanvasRenderer {
         // Add to pages list
         self.pages.push(page_id);
 
+        self.pages_started += 1;
+        if let Some(callback) = &self.progress_callback {
+            Python::with_gil(|py| -> PyResult<()> {
+                callback.call1(py, (self.pages_started, self.total_pages))?;
+                Ok(())
+            })?;
+        }
+
         // Fonts are loaded lazily - no default font registration here
         // The first canvas_set_font() call will load the appropriate font
 
@@ -188,10 +456,20 @@ impl PdfCanvasRenderer {
         // Save current page content and finalize page
         if let Some((_page_id, content_id, canvas)) = self.current_page.take() {
             let content_bytes = canvas.finish();
-            self.pdf.stream(content_id, &content_bytes);
+            self.finalize_content_stream(content_id, content_bytes)?;
 
             // Create and finish the page
             if let Some((page_info_id, page_width, page_height)) = self.current_page_info.take() {
+                let thumb_id = if self.generate_thumbnails {
+                    let (tw, th) = thumbnail_dimensions(page_width, page_height, self.thumbnail_max_size);
+                    let id = Ref::new(self.next_ref_id);
+                    self.next_ref_id += 1;
+                    image_utils::add_placeholder_thumbnail(&mut self.pdf, id, tw, th);
+                    Some(id)
+                } else {
+                    None
+                };
+
                 let mut page = self.pdf.page(page_info_id);
                 page.media_box(pdf_writer::Rect::new(
                     0.0,
@@ -225,6 +503,28 @@ impl PdfCanvasRenderer {
                             ext_states.pair(*name, *gs_ref);
                         }
                     }
+                    if !self.patterns_used_on_current_page.is_empty() {
+                        let mut patterns = resources.patterns();
+                        for (pattern_name, pattern_id) in &self.patterns_used_on_current_page {
+                            patterns.pair(*pattern_name, *pattern_id);
+                        }
+                    }
+                }
+
+                if !self.current_page_parent_tree.is_empty() {
+                    let key = self.parent_tree_entries.len() as i32;
+                    page.struct_parents(key);
+                    self.parent_tree_entries
+                        .push(std::mem::take(&mut self.current_page_parent_tree));
+                }
+
+                let finalized_page_index = self.pages.len() as u32 - 1;
+                if let Some(specs) = self.pending_annotations.remove(&finalized_page_index) {
+                    write_annotations(&mut page.annotations(), &specs);
+                }
+
+                if let Some(thumb_id) = thumb_id {
+                    page.thumbnail(thumb_id);
                 }
 
                 page.finish();
@@ -238,6 +538,172 @@ impl PdfCanvasRenderer {
             page_tree.count(self.pages.len() as i32);
         }
 
+        // Write the structure tree (if any tags were opened) and the catalog
+        if let Some(catalog_id) = self.catalog_id {
+            let struct_tree_root_id = if !self.struct_elements.is_empty() {
+                let struct_tree_root_id = Ref::new(self.next_ref_id);
+                self.next_ref_id += 1;
+
+                // Each struct element is written as its own indirect object so
+                // nested elements and top-level /K entries can reference each
+                // other by Ref.
+                let elem_ids: HashMap<Ref, Ref> = self
+                    .struct_elements
+                    .keys()
+                    .map(|id| (*id, *id))
+                    .collect();
+                for (elem_id, elem) in &self.struct_elements {
+                    let mut struct_elem = self.pdf.struct_element(*elem_id);
+                    struct_elem.custom_kind(Name(elem.role.as_bytes()));
+                    struct_elem.page(elem.page);
+                    struct_elem.parent(elem.parent.unwrap_or(struct_tree_root_id));
+                    if let Some(alt_text) = &elem.alt_text {
+                        struct_elem.alt(TextStr(alt_text));
+                    }
+                    let mut children = struct_elem.children();
+                    for kid in &elem.kids {
+                        match kid {
+                            StructKid::Mcid(mcid) => {
+                                children.marked_content_id(*mcid);
+                            }
+                            StructKid::Elem(child_id) => {
+                                children.struct_element(*elem_ids.get(child_id).unwrap_or(child_id));
+                            }
+                        }
+                    }
+                }
+
+                // The /ParentTree maps each page's /StructParents key to an
+                // array of struct-element refs, one per MCID on that page.
+                // NumberTree values must themselves be primitives/refs, so each
+                // page's array is written as its own indirect object first.
+                let mut parent_tree_entries = Vec::new();
+                for page_refs in &self.parent_tree_entries {
+                    let array_id = Ref::new(self.next_ref_id);
+                    self.next_ref_id += 1;
+                    self.pdf.indirect(array_id).array().items(page_refs.iter().copied());
+                    parent_tree_entries.push(array_id);
+                }
+
+                let mut struct_tree_root: StructTreeRoot =
+                    self.pdf.indirect(struct_tree_root_id).start();
+                struct_tree_root
+                    .children()
+                    .items(self.struct_tree_roots.iter().copied());
+                if !parent_tree_entries.is_empty() {
+                    let mut parent_tree = struct_tree_root.parent_tree();
+                    let mut nums = parent_tree.nums();
+                    for (key, array_id) in parent_tree_entries.iter().enumerate() {
+                        nums.insert(key as i32, *array_id);
+                    }
+                }
+                struct_tree_root.finish();
+
+                Some(struct_tree_root_id)
+            } else {
+                None
+            };
+
+            // Resolve each named destination to its now-final page Ref and
+            // write it as its own indirect destination array, ready to be
+            // listed in the catalog's /Names /Dests name tree below. Must
+            // happen before `self.pdf.catalog(...)` borrows `self.pdf`
+            // mutably for the rest of this block.
+            let mut named_destination_entries: Vec<(String, Ref)> = Vec::new();
+            if !self.named_destinations.is_empty() {
+                let mut names: Vec<&String> = self.named_destinations.keys().collect();
+                names.sort();
+                for name in names {
+                    let (page_index, x, y, zoom) = self.named_destinations[name];
+                    let Some(&page_id) = self.pages.get(page_index as usize) else {
+                        log::warn!(
+                            "named destination {:?} targets page {}, which was never created; skipping",
+                            name, page_index
+                        );
+                        continue;
+                    };
+                    let dest_id = Ref::new(self.next_ref_id);
+                    self.next_ref_id += 1;
+                    self.pdf
+                        .indirect(dest_id)
+                        .start::<Destination>()
+                        .page(page_id)
+                        .xyz(x as f32, y as f32, zoom.map(|z| z as f32));
+                    named_destination_entries.push((name.clone(), dest_id));
+                }
+            }
+
+            // The document information dictionary is its own indirect
+            // object (referenced from the trailer, not the catalog), so it
+            // also has to be written before `self.pdf.catalog(...)` takes
+            // its mutable borrow of `self.pdf`.
+            if let Some(title) = &self.document_title {
+                let info_id = Ref::new(self.next_ref_id);
+                self.next_ref_id += 1;
+                self.pdf.document_info(info_id).title(TextStr(title));
+            }
+
+            let mut catalog = self.pdf.catalog(catalog_id);
+            if let Some(page_tree_id) = self.page_tree_id {
+                catalog.pages(page_tree_id);
+            }
+            if let Some(struct_tree_root_id) = struct_tree_root_id {
+                catalog.pair(Name(b"StructTreeRoot"), struct_tree_root_id);
+                catalog.mark_info().marked(true);
+            }
+            if !named_destination_entries.is_empty() {
+                let mut names = catalog.names();
+                let mut dests = names.destinations();
+                let mut dest_names = dests.names();
+                for (name, dest_id) in &named_destination_entries {
+                    dest_names.insert(Str(name.as_bytes()), *dest_id);
+                }
+            }
+            if let Some((hide_toolbar, hide_menubar, fit_window, center_window, display_doc_title)) =
+                self.viewer_preferences
+            {
+                let mut prefs = catalog.viewer_preferences();
+                prefs.hide_toolbar(hide_toolbar);
+                prefs.hide_menubar(hide_menubar);
+                prefs.fit_window(fit_window);
+                prefs.center_window(center_window);
+                if display_doc_title {
+                    prefs.pair(Name(b"DisplayDocTitle"), true);
+                }
+            }
+            if let Some((page_index, zoom)) = self.open_action {
+                if let Some(&page_id) = self.pages.get(page_index as usize) {
+                    catalog.page_mode(PageMode::UseOutlines);
+                    // /XYZ with null left/top keeps the viewer's current
+                    // scroll position and only applies the requested zoom.
+                    let mut dest = catalog.insert(Name(b"OpenAction")).array();
+                    dest.item(page_id);
+                    dest.item(Name(b"XYZ"));
+                    dest.item(Null);
+                    dest.item(Null);
+                    dest.item(zoom as f32);
+                } else {
+                    log::warn!(
+                        "open action targets page {}, which was never created; skipping",
+                        page_index
+                    );
+                }
+            }
+            catalog.finish();
+        }
+
+        if self.linearize {
+            log::warn!(
+                "linearize=True was requested, but this renderer does not support \
+                 producing a linearized (fast-web-view) PDF; writing a normal, \
+                 non-linearized file instead."
+            );
+        }
+
+        // If streaming mode spooled any content streams to disk, splice them
+        // back in now, right before the buffer is finalized.
+        self.flush_streamed_contents()?;
+
         // Finish PDF and get bytes
         let pdf = std::mem::replace(&mut self.pdf, Pdf::new());
         let pdf_bytes = pdf.finish();
@@ -265,6 +731,8 @@ impl PdfCanvasRenderer {
             ));
         };
         canvas.save_state();
+        self.graphics_state_stack
+            .push((self.current_opacity, self.current_blend_mode.clone()));
         Ok(())
     }
 
@@ -278,11 +746,16 @@ impl PdfCanvasRenderer {
             ));
         };
         canvas.restore_state();
+        if let Some((opacity, blend_mode)) = self.graphics_state_stack.pop() {
+            self.current_opacity = opacity;
+            self.current_blend_mode = blend_mode;
+        }
         Ok(())
     }
 
     /// Set fill color (RGB 0.0-1.0)
     fn canvas_set_fill_color(&mut self, r: f64, g: f64, b: f64) -> PyResult<()> {
+        let (r, g, b) = self.normalize_color(r, g, b);
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
@@ -297,6 +770,7 @@ impl PdfCanvasRenderer {
 
     /// Set stroke color (RGB 0.0-1.0)
     fn canvas_set_stroke_color(&mut self, r: f64, g: f64, b: f64) -> PyResult<()> {
+        let (r, g, b) = self.normalize_color(r, g, b);
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
@@ -309,6 +783,45 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
+    /// Bring an RGB component into the valid PDF [0, 1] range, handling the
+    /// common mistake of passing 0-255 values from Python callers used to
+    /// other graphics libraries. A component > 1.0 and <= 255.0 is divided by
+    /// 255 when `auto_scale_colors` was set on the constructor; otherwise
+    /// every out-of-range component is clamped, with a one-time warning.
+    fn normalize_component(&mut self, value: f64) -> f64 {
+        if value < 0.0 {
+            self.warn_color_range_once();
+            0.0
+        } else if value > 1.0 {
+            if self.auto_scale_colors && value <= 255.0 {
+                value / 255.0
+            } else {
+                self.warn_color_range_once();
+                value.clamp(0.0, 1.0)
+            }
+        } else {
+            value
+        }
+    }
+
+    fn normalize_color(&mut self, r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+        (
+            self.normalize_component(r),
+            self.normalize_component(g),
+            self.normalize_component(b),
+        )
+    }
+
+    fn warn_color_range_once(&mut self) {
+        if !self.color_range_warned {
+            self.color_range_warned = true;
+            log::warn!(
+                "RGB color component out of [0, 1] range; clamping. Pass auto_scale_colors=True \
+                 to the renderer if you're using 0-255 scale colors."
+            );
+        }
+    }
+
     /// Set line width
     fn canvas_set_line_width(&mut self, width: f64) -> PyResult<()> {
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
@@ -322,8 +835,36 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
+    /// Set where a subsequently-stroked `canvas_rect()`'s border sits
+    /// relative to the rectangle's bounds: `"center"` (default, straddles
+    /// the edge like a plain PDF stroke), `"inside"`, or `"outside"`. Useful
+    /// for table cell borders that must not overlap their neighbors.
+    fn canvas_set_stroke_align(&mut self, align: String) -> PyResult<()> {
+        let parsed = match align.as_str() {
+            "center" => StrokeAlign::Center,
+            "inside" => StrokeAlign::Inside,
+            "outside" => StrokeAlign::Outside,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown stroke align: {:?} (expected \"center\", \"inside\", or \"outside\")",
+                    align
+                )))
+            }
+        };
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.set_stroke_align(parsed);
+        Ok(())
+    }
+
     /// Set dash pattern
-    fn canvas_set_dash(&mut self, pattern: Vec<f64>) -> PyResult<()> {
+    #[pyo3(signature = (pattern, phase=0.0))]
+    fn canvas_set_dash(&mut self, pattern: Vec<f64>, phase: f64) -> PyResult<()> {
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
@@ -334,7 +875,6 @@ impl PdfCanvasRenderer {
         if pattern.is_empty() {
             canvas.set_dash(vec![], 0.0);
         } else {
-            let phase = if pattern.len() > 0 { pattern[0] } else { 0.0 };
             canvas.set_dash(pattern, phase);
         }
         Ok(())
@@ -348,40 +888,7 @@ impl PdfCanvasRenderer {
     /// 3. Try font name variations (with/without style suffix)
     /// 4. Fall back to system default font
     fn canvas_set_font(&mut self, name: String, size: f64) -> PyResult<()> {
-        // Parse font name to extract family and style
-        let (family, style) = Self::parse_font_name(&name);
-        let font_key = format!("{}:{:?}", family.to_lowercase(), style);
-        
-        // Check if font is already loaded
-        let (font_name, font_id) = if let Some(&(name_ref, id_ref)) = self.font_registry.get(&font_key) {
-            (name_ref, id_ref)
-        } else {
-            // Try to load font from system
-            let font_path = self.find_font_path(&family, style)?;
-
-            // Load and register the font
-            let font_data = load_font_file(&font_path)?;
-            let font_id = Ref::new(self.next_ref_id);
-            self.next_ref_id += 1;
-            
-            let (pdf_font_name, cid_map) = add_truetype_font(
-                &mut self.pdf,
-                &font_data,
-                font_id,
-                &mut self.next_ref_id,
-            )?;
-            
-            // Register font
-            self.font_registry.insert(font_key.clone(), (pdf_font_name, font_id));
-            self.type0_cid_maps.insert(pdf_font_name, cid_map);
-
-            // Also register under original name for quick lookup
-            if !self.font_registry.contains_key(&name) {
-                self.font_registry.insert(name.clone(), (pdf_font_name, font_id));
-            }
-            
-            (pdf_font_name, font_id)
-        };
+        let (font_name, font_id) = self.resolve_font(&name)?;
 
         // Register font for current page
         if !self.fonts_used_on_current_page.contains_key(&font_name) {
@@ -401,12 +908,307 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
-    /// Set current graphics state opacity (both fill and stroke)
+    /// Register a chain of fallback fonts, in priority order, for glyph coverage.
+    /// When `canvas_draw_string` encounters a code point the active font can't
+    /// render, it consults these fonts (in the given order) before giving up and
+    /// drawing `.notdef`. Each font is loaded through the same lazy-loading path
+    /// as `canvas_set_font`, so it only touches disk/registers once.
+    fn register_fallback_fonts(&mut self, font_keys: Vec<String>) -> PyResult<()> {
+        let mut resolved = Vec::with_capacity(font_keys.len());
+        for key in &font_keys {
+            resolved.push(self.resolve_font(key)?);
+        }
+        self.fallback_fonts = resolved;
+        Ok(())
+    }
+
+    /// Add directories to search for font files, consulted before the built-in
+    /// platform font paths in `find_font_path`. Lets deployments that bundle
+    /// fonts outside the usual system locations (e.g. `/app/fonts` in a minimal
+    /// Docker image) still resolve them by family name.
+    fn set_font_search_paths(&mut self, dirs: Vec<String>) -> PyResult<()> {
+        self.font_search_paths = dirs.into_iter().map(PathBuf::from).collect();
+        Ok(())
+    }
+
+    /// Set the family names tried, in order, when a requested font can't be
+    /// found at all (not even under the custom search paths), before falling
+    /// back to the bundled DejaVu Sans. Effective for lazily-loaded fonts
+    /// triggered by `canvas_set_font`.
+    fn set_fallback_chain(&mut self, family_names: Vec<String>) -> PyResult<()> {
+        self.fallback_chain = family_names;
+        Ok(())
+    }
+
+    /// Opt out of faux bold/italic synthesis (enabled by default). When a
+    /// requested family has no real bold/italic file and this is false,
+    /// `canvas_draw_string` renders plain Regular instead of stroking/shearing it.
+    fn set_synthesize_styles(&mut self, enabled: bool) -> PyResult<()> {
+        self.synthesize_styles = enabled;
+        Ok(())
+    }
+
+    /// Opt out of GSUB `liga`/`dlig` ligature substitution (enabled by default).
+    /// When false, `canvas_draw_string` shows each cmap-mapped glyph as-is
+    /// instead of merging sequences like "f"+"i" into a ligature glyph.
+    fn set_ligatures(&mut self, enabled: bool) -> PyResult<()> {
+        self.ligatures_enabled = enabled;
+        Ok(())
+    }
+
+    /// Register a callback invoked from `new_page()` as `callback(page_index,
+    /// total_pages)`, so long renders (e.g. 10k-page documents) can report
+    /// progress instead of appearing to hang. `total_pages` is whatever was
+    /// last passed to `set_total_pages()`, or `None` if it wasn't called.
+    fn set_progress_callback(&mut self, callback: PyObject) -> PyResult<()> {
+        self.progress_callback = Some(callback);
+        Ok(())
+    }
+
+    /// Tell the progress callback registered via `set_progress_callback()` how
+    /// many pages the document will have in total, if known in advance.
+    fn set_total_pages(&mut self, total: u32) -> PyResult<()> {
+        self.total_pages = Some(total);
+        Ok(())
+    }
+
+    /// Request that the output PDF be linearized ("fast web view") so a
+    /// viewer can start rendering page 1 before the whole file downloads.
+    ///
+    /// NOT CURRENTLY IMPLEMENTED: true linearization (ISO 32000 Annex F)
+    /// requires reordering every object so page 1 and its resources come
+    /// first, a linearization parameter dictionary, a hint stream describing
+    /// object/page offsets, and a two-section xref -- all built by rewriting
+    /// the finished byte stream after the fact. `pdf_writer` (this crate's
+    /// PDF backend) streams objects out as they're assigned, with no
+    /// supported way to reorder or re-offset what's already been written, so
+    /// that rewrite would have to be a from-scratch byte-level PDF parser and
+    /// re-serializer -- out of scope for a minimal renderer. Setting this
+    /// flag logs a one-time warning at `save()` so callers relying on it
+    /// notice rather than silently getting a non-linearized file, and costs
+    /// nothing otherwise; the file is written exactly as it would be without
+    /// it (smaller and faster to produce, but without the fast-web-view
+    /// benefit).
+    fn set_linearize(&mut self, enabled: bool) -> PyResult<()> {
+        self.linearize = enabled;
+        Ok(())
+    }
+
+    /// Opt into attaching a `/Thumb` placeholder image to each page, sized to
+    /// `max_size` pixels along its long edge (default 106px, like Acrobat).
+    /// Off by default since it adds an extra object per page. Note this is a
+    /// flat white placeholder, not a rasterization of the page's actual
+    /// content -- see `add_placeholder_thumbnail()`'s doc comment for why.
+    /// Logs a one-time warning when enabled, same as `set_linearize()`, so
+    /// callers relying on real thumbnails notice rather than silently
+    /// getting a blank box on every page.
+    #[pyo3(signature = (enabled, max_size=106))]
+    fn set_generate_thumbnails(&mut self, enabled: bool, max_size: u32) -> PyResult<()> {
+        if enabled && !self.thumbnail_warned {
+            self.thumbnail_warned = true;
+            log::warn!(
+                "generate_thumbnails=True was requested, but this renderer has no PDF \
+                 content-stream rasterizer; each page's /Thumb will be a flat white \
+                 placeholder image, not a preview of its actual content."
+            );
+        }
+        self.generate_thumbnails = enabled;
+        self.thumbnail_max_size = max_size.max(1);
+        Ok(())
+    }
+
+    /// Set the catalog's `/ViewerPreferences`, controlling how a viewer
+    /// should present its own chrome while the document is open. `title`,
+    /// if given, is written to the document information dictionary's
+    /// `/Title` at `save()`; pass `display_doc_title=true` alongside it so
+    /// viewers show that title in their window/tab instead of the
+    /// filename.
+    #[pyo3(signature = (hide_toolbar=false, hide_menubar=false, fit_window=false, center_window=false, display_doc_title=false, title=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_viewer_preferences(
+        &mut self,
+        hide_toolbar: bool,
+        hide_menubar: bool,
+        fit_window: bool,
+        center_window: bool,
+        display_doc_title: bool,
+        title: Option<String>,
+    ) -> PyResult<()> {
+        self.viewer_preferences =
+            Some((hide_toolbar, hide_menubar, fit_window, center_window, display_doc_title));
+        if let Some(title) = title {
+            self.document_title = Some(title);
+        }
+        Ok(())
+    }
+
+    /// Set the document's initial view: jump to `page_index` at `zoom` (1.0
+    /// = 100%) when opened, written as the catalog's `/OpenAction` at
+    /// `save()`. Also sets `/PageMode /UseOutlines` so the bookmarks panel
+    /// is showing alongside it.
+    fn set_open_action(&mut self, page_index: u32, zoom: f64) -> PyResult<()> {
+        self.open_action = Some((page_index, zoom));
+        Ok(())
+    }
+
+    /// Assign an ICC profile (e.g. sRGB) to images drawn via
+    /// `canvas_draw_image` that carry no embedded profile of their own,
+    /// so they get an explicit `/ICCBased` color space instead of bare
+    /// `/DeviceRGB`. Images with their own embedded profile are unaffected.
+    /// Pass `None` to go back to tagging untagged images as plain DeviceRGB.
+    fn set_default_rgb_profile(&mut self, profile: Option<Vec<u8>>) -> PyResult<()> {
+        self.default_rgb_profile = profile;
+        Ok(())
+    }
+
+    /// Queue a `/Text` (sticky-note) popup annotation at `(x, y)` on
+    /// `page_index`, shown in PDF viewers' comments pane. `icon` is one of the
+    /// standard PDF icon names ("Comment", "Key", "Note", "Help",
+    /// "NewParagraph", "Paragraph", "Insert"); unrecognized names fall back to
+    /// "Note". `page_index` may refer to a page not yet created (or already
+    /// finalized pages that are still open, i.e. the current one) -- the
+    /// annotation is written once that page is finalized, at the next
+    /// `new_page()` call or at `save()`.
+    #[pyo3(signature = (page_index, x, y, contents, author=None, icon=None))]
+    fn add_text_annotation(
+        &mut self,
+        page_index: u32,
+        x: f64,
+        y: f64,
+        contents: String,
+        author: Option<String>,
+        icon: Option<String>,
+    ) -> PyResult<()> {
+        self.pending_annotations.entry(page_index).or_default().push(AnnotationSpec::Text {
+            x,
+            y,
+            contents,
+            author,
+            icon: icon.unwrap_or_else(|| "Note".to_string()),
+        });
+        Ok(())
+    }
+
+    /// Queue a `/Highlight` markup annotation over the region described by
+    /// `quad_points` (a flat `[x1, y1, x2, y2, x3, y3, x4, y4, ...]` array, one
+    /// quadrilateral per highlighted line of text) on `page_index`, shown as a
+    /// yellow highlight with `contents` as its comment-pane note. Written once
+    /// that page is finalized, same as `add_text_annotation`.
+    fn add_highlight_annotation(
+        &mut self,
+        page_index: u32,
+        quad_points: Vec<f64>,
+        contents: String,
+    ) -> PyResult<()> {
+        self.pending_annotations
+            .entry(page_index)
+            .or_default()
+            .push(AnnotationSpec::Highlight { quad_points, contents });
+        Ok(())
+    }
+
+    /// Record a named destination at `(x, y)` on `page_index`, for
+    /// `add_link_annotation()` (or an external viewer's "go to page") to
+    /// target by name instead of raw page index. Written to the catalog's
+    /// `/Names /Dests` name tree at `save()`, once every page's final `Ref`
+    /// is known. `zoom` is the `/XYZ` zoom factor to apply when navigating
+    /// to the destination; `None` leaves the viewer's current zoom
+    /// unchanged.
+    #[pyo3(signature = (name, page_index, x, y, zoom=None))]
+    fn add_named_destination(
+        &mut self,
+        name: String,
+        page_index: u32,
+        x: f64,
+        y: f64,
+        zoom: Option<f64>,
+    ) -> PyResult<()> {
+        self.named_destinations.insert(name, (page_index, x, y, zoom));
+        Ok(())
+    }
+
+    /// Queue a `/Link` annotation over the rectangle `(x, y, width,
+    /// height)` on `page_index`, jumping to the named destination
+    /// `dest_name` (added via `add_named_destination()`) when clicked.
+    /// Resolving the target by name rather than a page+coordinate pair
+    /// decouples link creation from page finalization order -- the
+    /// destination doesn't need to exist yet, and can even land on a page
+    /// not yet created, as long as it's added before `save()`. Written
+    /// once `page_index` is finalized, same as `add_text_annotation`.
+    fn add_link_annotation(
+        &mut self,
+        page_index: u32,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        dest_name: String,
+    ) -> PyResult<()> {
+        self.pending_annotations.entry(page_index).or_default().push(AnnotationSpec::Link {
+            x,
+            y,
+            width,
+            height,
+            dest_name,
+        });
+        Ok(())
+    }
+
+    /// Set current graphics state opacity (both fill and stroke). Combines
+    /// with any blend mode set via `canvas_set_blend_mode` into one ExtGState.
     fn canvas_set_opacity(&mut self, opacity: f64) -> PyResult<()> {
-        let clamped = opacity.clamp(0.0, 1.0);
-        let alpha_key = (clamped * 1000.0).round() as u32;
-        let (name, gs_ref) =
-            self.get_or_create_ext_graphics_state(alpha_key, clamped as f32);
+        self.current_opacity = opacity.clamp(0.0, 1.0);
+        self.apply_current_graphics_state()
+    }
+
+    /// Set the current blend mode (e.g. "Multiply", "Screen") for subsequent
+    /// fills/strokes, applied via the ExtGState `/BM` entry. Combines with
+    /// any opacity set via `canvas_set_opacity` into one ExtGState. Reset by
+    /// `restore_state()`, like every other ExtGState-backed property, since
+    /// PDF's `Q` operator restores the whole graphics state. Accepts the
+    /// standard PDF 1.4 blend mode names; "Normal" clears back to the
+    /// default (no `/BM` entry).
+    fn canvas_set_blend_mode(&mut self, mode: String) -> PyResult<()> {
+        parse_blend_mode(&mode).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown blend mode: {:?}",
+                mode
+            ))
+        })?;
+        self.current_blend_mode = if mode == "Normal" { None } else { Some(mode) };
+        self.apply_current_graphics_state()
+    }
+
+    /// Set the color rendering intent (e.g. "Perceptual", "Saturation") for
+    /// subsequent painting operations via the `ri` operator. Unlike opacity
+    /// and blend mode, this isn't part of the ExtGState-backed graphics
+    /// state bookkeeping -- it's a plain content stream operator, so it
+    /// isn't automatically reset by `restore_state()`.
+    fn canvas_set_rendering_intent(&mut self, intent: String) -> PyResult<()> {
+        let parsed = parse_rendering_intent(&intent).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown rendering intent: {:?}",
+                intent
+            ))
+        })?;
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.set_rendering_intent(parsed);
+        Ok(())
+    }
+
+    /// Create (or reuse) the ExtGState for the current opacity/blend mode
+    /// combination and apply it to the current page's canvas.
+    fn apply_current_graphics_state(&mut self) -> PyResult<()> {
+        let alpha = self.current_opacity as f32;
+        let alpha_key = (self.current_opacity * 1000.0).round() as u32;
+        let blend_mode = self.current_blend_mode.clone();
+        let (name, gs_ref) = self.get_or_create_ext_graphics_state(alpha_key, alpha, blend_mode);
 
         {
             let canvas = if let Some((_, _, ref mut c)) = self.current_page {
@@ -469,8 +1271,24 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
-    /// Draw line
-    fn canvas_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) -> PyResult<()> {
+    /// Draw a rounded rectangle with an independent radius per corner (e.g.
+    /// only rounding the top two corners of a card). A radius of `0.0`
+    /// produces a square corner. Each radius is clamped to half the smaller
+    /// dimension, with a warning logged if clamping was needed.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_round_rect_corners(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        top_left: f64,
+        top_right: f64,
+        bottom_right: f64,
+        bottom_left: f64,
+        fill: bool,
+        stroke: bool,
+    ) -> PyResult<()> {
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
@@ -478,44 +1296,110 @@ impl PdfCanvasRenderer {
                 "No current page",
             ));
         };
-        canvas.line(x1, y1, x2, y2);
+        let rect = Rect::new(x, y, width, height);
+        canvas.round_rect_corners(rect, top_left, top_right, bottom_right, bottom_left, fill, stroke);
         Ok(())
     }
 
-    /// Draw text string
-    fn canvas_draw_string(&mut self, x: f64, y: f64, text: String) -> PyResult<()> {
-        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
-            c
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                "No current page",
-            ));
-        };
-
-        // Get current font name from canvas
-        let current_font_name = canvas.get_font_name();
-
-        // Require CID map for every font (all fonts are Type0)
-        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
-            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                "No CID map registered for font {:?}",
-                current_font_name
-            ))
-        })?;
-
-        canvas.draw_string(x, y, &text, cid_map);
+    /// Draw a rounded rectangle with its own fill and/or stroke color and
+    /// line width, independent of (and restored after, via save/restore
+    /// state) the canvas's current fill/stroke color and line width. For
+    /// cards that need a fill plus a differently colored border without two
+    /// calls and a color change in between that would leak into later
+    /// drawing if a restore were forgotten. Uses the combined `B` operator
+    /// when both `fill_rgb` and `stroke_rgb` are given, instead of filling
+    /// and stroking the path as two separate operations.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_round_rect_styled(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        fill_rgb: Option<(f64, f64, f64)>,
+        stroke_rgb: Option<(f64, f64, f64)>,
+        line_width: Option<f64>,
+    ) -> PyResult<()> {
+        self.canvas_save_state()?;
+        if let Some((r, g, b)) = fill_rgb {
+            self.canvas_set_fill_color(r, g, b)?;
+        }
+        if let Some((r, g, b)) = stroke_rgb {
+            self.canvas_set_stroke_color(r, g, b)?;
+        }
+        if let Some(w) = line_width {
+            self.canvas_set_line_width(w)?;
+        }
+        {
+            let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+                c
+            } else {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "No current page",
+                ));
+            };
+            let rect = Rect::new(x, y, width, height);
+            canvas.round_rect_combined(rect, radius, fill_rgb.is_some(), stroke_rgb.is_some());
+        }
+        self.canvas_restore_state()?;
         Ok(())
     }
 
-    /// Draw image from bytes
-    fn canvas_draw_image(
+    /// Draw an approximated soft-edged drop shadow behind a rectangle, by
+    /// layering several offset rectangles of decreasing opacity (via the
+    /// ExtGState alpha machinery) outward from a hard core. `blur` is the
+    /// maximum outward spread in points; `blur <= 0.0` draws a single
+    /// hard-edged offset rectangle instead of layering.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_draw_shadow_rect(
         &mut self,
         x: f64,
         y: f64,
         width: f64,
         height: f64,
-        image_data: Vec<u8>,
+        blur: f64,
+        dx: f64,
+        dy: f64,
+        r: f64,
+        g: f64,
+        b: f64,
+        opacity: f64,
     ) -> PyResult<()> {
+        const LAYERS: u32 = 8;
+
+        self.canvas_save_state()?;
+        self.canvas_set_fill_color(r, g, b)?;
+
+        if blur <= 0.0 {
+            self.canvas_set_opacity(opacity)?;
+            self.canvas_rect(x + dx, y + dy, width, height, true, false)?;
+        } else {
+            // Choose a per-layer alpha so that the core (covered by all
+            // LAYERS rectangles) composites to exactly `opacity`, while
+            // pixels covered by fewer of the progressively larger layers
+            // fall off smoothly toward the edge.
+            let per_layer_alpha = 1.0 - (1.0 - opacity).powf(1.0 / LAYERS as f64);
+            self.canvas_set_opacity(per_layer_alpha)?;
+            for step in (1..=LAYERS).rev() {
+                let outset = blur * step as f64 / LAYERS as f64;
+                self.canvas_rect(
+                    x + dx - outset,
+                    y + dy - outset,
+                    width + 2.0 * outset,
+                    height + 2.0 * outset,
+                    true,
+                    false,
+                )?;
+            }
+        }
+
+        self.canvas_restore_state()?;
+        Ok(())
+    }
+
+    /// Draw line
+    fn canvas_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) -> PyResult<()> {
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
             c
         } else {
@@ -523,48 +1407,767 @@ impl PdfCanvasRenderer {
                 "No current page",
             ));
         };
+        canvas.line(x1, y1, x2, y2);
+        Ok(())
+    }
 
-        // Create a unique key for this image
-        // Use a simple approach: use length and first/last bytes as key
-        let key = if image_data.len() > 16 {
-            // Use first 8 and last 8 bytes for uniqueness
-            let prefix: u64 = u64::from_be_bytes([
-                image_data[0],
-                image_data[1],
-                image_data[2],
-                image_data[3],
-                image_data[4],
-                image_data[5],
-                image_data[6],
-                image_data[7],
-            ]);
-            let suffix: u64 = u64::from_be_bytes([
-                image_data[image_data.len() - 8],
-                image_data[image_data.len() - 7],
-                image_data[image_data.len() - 6],
-                image_data[image_data.len() - 5],
-                image_data[image_data.len() - 4],
-                image_data[image_data.len() - 3],
-                image_data[image_data.len() - 2],
-                image_data[image_data.len() - 1],
-            ]);
-            format!(
-                "canvas_image_{:x}_{:x}_{}",
-                prefix,
-                suffix,
-                image_data.len()
-            )
-        } else {
-            // For small images, use all bytes
-            format!(
-                "canvas_image_{}_{}",
-                image_data.len(),
-                image_data
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<String>()
-            )
-        };
+    // ===== Low-level path operators =====
+    //
+    // Map 1:1 to PDF path-construction/painting operators so advanced
+    // callers can emit arbitrary paths (stars, custom icons, ...) without
+    // us adding a dedicated shape helper for each one. `canvas_op_lineto`,
+    // `canvas_op_curveto` and `canvas_op_closepath` require a prior
+    // `canvas_op_moveto`/`canvas_op_rectangle` and raise cleanly otherwise.
+
+    fn canvas_op_moveto(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_moveto(x, y);
+        Ok(())
+    }
+
+    fn canvas_op_lineto(&mut self, x: f64, y: f64) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas
+            .op_lineto(x, y)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_op_curveto(
+        &mut self,
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        x3: f64,
+        y3: f64,
+    ) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas
+            .op_curveto(x1, y1, x2, y2, x3, y3)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    fn canvas_op_closepath(&mut self) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas
+            .op_closepath()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    fn canvas_op_rectangle(&mut self, x: f64, y: f64, width: f64, height: f64) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_rectangle(x, y, width, height);
+        Ok(())
+    }
+
+    #[pyo3(signature = (even_odd=false))]
+    fn canvas_op_fill(&mut self, even_odd: bool) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_fill(even_odd);
+        Ok(())
+    }
+
+    fn canvas_op_stroke(&mut self) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_stroke();
+        Ok(())
+    }
+
+    #[pyo3(signature = (even_odd=false))]
+    fn canvas_op_fill_stroke(&mut self, even_odd: bool) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_fill_stroke(even_odd);
+        Ok(())
+    }
+
+    #[pyo3(signature = (even_odd=false))]
+    fn canvas_op_clip(&mut self, even_odd: bool) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_clip(even_odd);
+        Ok(())
+    }
+
+    fn canvas_op_end_path(&mut self) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.op_end_path();
+        Ok(())
+    }
+
+    /// Get the currently active font's PDF resource name and size, reflecting
+    /// the top of the save/restore stack. Read-only: emits no operators.
+    fn canvas_current_font(&self) -> PyResult<(String, f64)> {
+        let canvas = if let Some((_, _, ref c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        let name = String::from_utf8_lossy(canvas.get_font_name().0).into_owned();
+        Ok((name, canvas.get_font_size()))
+    }
+
+    /// Get the currently active fill color as (r, g, b) in 0.0-1.0, reflecting
+    /// the top of the save/restore stack. Read-only: emits no operators.
+    fn canvas_current_fill_color(&self) -> PyResult<(f64, f64, f64)> {
+        let canvas = if let Some((_, _, ref c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        let color = canvas.get_fill_color();
+        Ok((color.r, color.g, color.b))
+    }
+
+    /// Get the currently active line width, reflecting the top of the
+    /// save/restore stack. Read-only: emits no operators.
+    fn canvas_current_line_width(&self) -> PyResult<f64> {
+        let canvas = if let Some((_, _, ref c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        Ok(canvas.get_line_width())
+    }
+
+    /// Enable or disable underline/strikethrough decoration for strings drawn via
+    /// `canvas_draw_string` from this point on. Persists across draw calls (like the
+    /// current font or fill color) until changed again.
+    fn canvas_set_text_decoration(&mut self, underline: bool, strikethrough: bool) -> PyResult<()> {
+        self.text_decoration = (underline, strikethrough);
+        Ok(())
+    }
+
+    /// Open a tagged-PDF structure element with the given role (e.g. "P",
+    /// "H1", "Table", "Figure") and a marked-content span on the current
+    /// page, nesting under whatever tag is currently open. `alt_text` is
+    /// used as the element's `/Alt` entry, for "Figure" elements in
+    /// particular. This is the foundation for accessible (PDF-UA/WCAG) output.
+    fn canvas_begin_tag(&mut self, role: String, alt_text: Option<String>) -> PyResult<()> {
+        let page_id = if let Some((page_id, _, _)) = self.current_page {
+            page_id
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        let elem_id = Ref::new(self.next_ref_id);
+        self.next_ref_id += 1;
+
+        let parent = self.tag_stack.last().copied();
+        self.struct_elements.insert(
+            elem_id,
+            StructElemData {
+                role,
+                parent,
+                page: page_id,
+                kids: Vec::new(),
+                alt_text,
+            },
+        );
+
+        match parent {
+            Some(parent_id) => {
+                if let Some(parent_elem) = self.struct_elements.get_mut(&parent_id) {
+                    parent_elem.kids.push(StructKid::Elem(elem_id));
+                }
+            }
+            None => self.struct_tree_roots.push(elem_id),
+        }
+
+        let mcid = self.next_mcid;
+        self.next_mcid += 1;
+        let elem = self.struct_elements.get_mut(&elem_id).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Internal error: struct element vanished right after insertion",
+            )
+        })?;
+        elem.kids.push(StructKid::Mcid(mcid));
+        let role_owned = elem.role.clone();
+        self.current_page_parent_tree.push(elem_id);
+
+        self.tag_stack.push(elem_id);
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.begin_tag(Name(role_owned.as_bytes()), mcid);
+
+        Ok(())
+    }
+
+    /// Close the structure element opened by the matching `canvas_begin_tag`.
+    fn canvas_end_tag(&mut self) -> PyResult<()> {
+        if self.tag_stack.pop().is_none() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "canvas_end_tag() called with no matching canvas_begin_tag()",
+            ));
+        }
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.end_tag();
+
+        Ok(())
+    }
+
+    /// Draw text string
+    fn canvas_draw_string(&mut self, x: f64, y: f64, text: String) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        // Get current font name from canvas
+        let current_font_name = canvas.get_font_name();
+
+        // Require CID map for every font (all fonts are Type0)
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+
+        let needs_fallback = !self.fallback_fonts.is_empty()
+            && text.chars().any(|ch| !cid_map.contains_key(&(ch as u32)));
+        let (synth_bold, synth_italic) = self
+            .synthetic_styles
+            .get(&current_font_name)
+            .copied()
+            .unwrap_or((false, false));
+
+        if !needs_fallback {
+            let ligatures = self
+                .ligatures_enabled
+                .then(|| self.font_metrics.get(&current_font_name).map(|m| &m.ligatures))
+                .flatten();
+            canvas.draw_string(x, y, &text, cid_map, (synth_bold, synth_italic), ligatures);
+        } else {
+            // Split into runs by which registered font (current font first, then
+            // each fallback in registration order) actually covers each code
+            // point. A code point none of them cover renders as `.notdef` (CID 0)
+            // under the current font rather than silently dropping.
+            let mut runs: Vec<(Name<'static>, Vec<u8>)> = Vec::new();
+            let mut fallback_fonts_used: Vec<(Name<'static>, Ref)> = Vec::new();
+
+            for ch in text.chars() {
+                let code_point = ch as u32;
+                let (font_name, fallback_id, cid) = if let Some(&cid) = cid_map.get(&code_point) {
+                    (current_font_name, None, cid)
+                } else {
+                    self.fallback_fonts
+                        .iter()
+                        .find_map(|&(fb_name, fb_id)| {
+                            self.type0_cid_maps
+                                .get(&fb_name)
+                                .and_then(|fb_map| fb_map.get(&code_point))
+                                .map(|&cid| (fb_name, Some(fb_id), cid))
+                        })
+                        .unwrap_or((current_font_name, None, 0))
+                };
+
+                if let Some(font_id) = fallback_id {
+                    if !fallback_fonts_used.iter().any(|&(n, _)| n == font_name) {
+                        fallback_fonts_used.push((font_name, font_id));
+                    }
+                }
+
+                let cid_bytes = [(cid >> 8) as u8, (cid & 0xFF) as u8];
+                match runs.last_mut() {
+                    Some(last) if last.0 == font_name => last.1.extend_from_slice(&cid_bytes),
+                    _ => runs.push((font_name, cid_bytes.to_vec())),
+                }
+            }
+
+            for (font_name, font_id) in fallback_fonts_used {
+                self.fonts_used_on_current_page
+                    .entry(font_name)
+                    .or_insert(font_id);
+            }
+
+            canvas.draw_string_multi_font(x, y, &runs, (synth_bold, synth_italic));
+        }
+
+        let (underline, strikethrough) = self.text_decoration;
+        if (underline || strikethrough) && !text.is_empty() {
+            let font_size = canvas.get_font_size();
+            let metrics = self.font_metrics.get(&current_font_name);
+            let width_1000: i32 = text
+                .chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum();
+            let width = width_1000 as f64 * font_size / 1000.0;
+            let underline_metrics = metrics
+                .map(|m| (m.underline_position, m.underline_thickness))
+                .unwrap_or((-100.0, 50.0));
+            let strikeout_metrics = metrics
+                .map(|m| (m.strikeout_position, m.strikeout_thickness))
+                .unwrap_or((200.0, 50.0));
+
+            if underline {
+                let (position, thickness) = underline_metrics;
+                self.draw_decoration_line(x, y, width, font_size, position, thickness)?;
+            }
+            if strikethrough {
+                let (position, thickness) = strikeout_metrics;
+                self.draw_decoration_line(x, y, width, font_size, position, thickness)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw a text string with a solid background behind it (Word-style highlighting).
+    /// Measures the string via the font's own per-codepoint widths, fills a rectangle
+    /// spanning descender to ascender in the highlight color, then draws the text on
+    /// top using the normal `canvas_draw_string` path (so decoration still applies).
+    fn canvas_draw_string_highlighted(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        r: f64,
+        g: f64,
+        b: f64,
+    ) -> PyResult<()> {
+        if !text.is_empty() {
+            let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+                c
+            } else {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "No current page",
+                ));
+            };
+
+            let current_font_name = canvas.get_font_name();
+            let font_size = canvas.get_font_size();
+            let metrics = self.font_metrics.get(&current_font_name);
+            let width_1000: i32 = text
+                .chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum();
+            let width = width_1000 as f64 * font_size / 1000.0;
+            let (ascender, descender) = metrics
+                .map(|m| (m.ascender, m.descender))
+                .unwrap_or((700.0, -200.0));
+            let top = y + ascender as f64 * font_size / 1000.0;
+            let bottom = y + descender as f64 * font_size / 1000.0;
+
+            canvas.save_state();
+            canvas.set_fill_color(Color { r, g, b });
+            canvas.rect(Rect::new(x, bottom, width, top - bottom), true, false);
+            canvas.restore_state();
+        }
+
+        self.canvas_draw_string(x, y, text)
+    }
+
+    /// Draw outlined text: fill with `fill_rgb`, stroke with an independent
+    /// `stroke_rgb` at `stroke_width` points, via text render mode 2
+    /// (fill+stroke). Sets the fill color, render mode, stroke color, and
+    /// line width, draws the string, then restores render mode `Tr 0` and
+    /// the previous stroke color/line width.
+    fn canvas_draw_string_outlined(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        fill_rgb: (f64, f64, f64),
+        stroke_rgb: (f64, f64, f64),
+        stroke_width: f64,
+    ) -> PyResult<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        self.canvas_set_fill_color(fill_rgb.0, fill_rgb.1, fill_rgb.2)?;
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        let current_font_name = canvas.get_font_name();
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+        let ligatures = self
+            .ligatures_enabled
+            .then(|| self.font_metrics.get(&current_font_name).map(|m| &m.ligatures))
+            .flatten();
+        let (synth_bold, synth_italic) = self
+            .synthetic_styles
+            .get(&current_font_name)
+            .copied()
+            .unwrap_or((false, false));
+
+        canvas.draw_string_outlined(
+            x,
+            y,
+            &text,
+            cid_map,
+            (synth_bold, synth_italic),
+            ligatures,
+            Color {
+                r: stroke_rgb.0,
+                g: stroke_rgb.1,
+                b: stroke_rgb.2,
+            },
+            stroke_width,
+        );
+
+        Ok(())
+    }
+
+    /// Draw a line of text stretched (or compressed) to exactly `target_width` points
+    /// by distributing the slack between its words as `TJ` adjustments, instead of
+    /// relying on the caller to have pre-padded the string with guessed spacing.
+    fn canvas_draw_string_justified(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        target_width: f64,
+    ) -> PyResult<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        let current_font_name = canvas.get_font_name();
+        let font_size = canvas.get_font_size();
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+        let metrics = self.font_metrics.get(&current_font_name);
+        let (synth_bold, synth_italic) = self
+            .synthetic_styles
+            .get(&current_font_name)
+            .copied()
+            .unwrap_or((false, false));
+        let width_of_1000 = |s: &str| -> i32 {
+            s.chars()
+                .map(|ch| {
+                    metrics
+                        .and_then(|m| m.widths.get(&(ch as u32)))
+                        .copied()
+                        .unwrap_or(500)
+                })
+                .sum()
+        };
+
+        let words: Vec<&str> = text.split(' ').collect();
+        let gap_count = words.len().saturating_sub(1);
+        if gap_count == 0 {
+            // No word boundary to distribute slack across.
+            let ligatures = self.ligatures_enabled.then(|| metrics.map(|m| &m.ligatures)).flatten();
+            canvas.draw_string(x, y, &text, cid_map, (synth_bold, synth_italic), ligatures);
+            return Ok(());
+        }
+
+        let space_width_1000 = width_of_1000(" ").max(1);
+        let natural_width_1000: i32 = words.iter().map(|w| width_of_1000(w)).sum::<i32>()
+            + space_width_1000 * gap_count as i32;
+        let natural_width = natural_width_1000 as f64 * font_size / 1000.0;
+        let slack_1000_per_gap = ((target_width - natural_width) * 1000.0 / font_size) / gap_count as f64;
+        // TJ amounts are subtracted from the advance, so widening a gap takes a negative number.
+        let adjustment = -(slack_1000_per_gap as f32);
+
+        let segments: Vec<String> = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i < gap_count {
+                    format!("{} ", word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect();
+        let adjustments = vec![adjustment; gap_count];
+
+        canvas.draw_string_positioned(
+            x,
+            y,
+            &segments,
+            &adjustments,
+            cid_map,
+            (synth_bold, synth_italic),
+        );
+        Ok(())
+    }
+
+    /// Draw `text` along an arbitrary path (straight-segment polyline),
+    /// placing each glyph's own baseline origin at its arc-length position
+    /// along the path and rotating it to the tangent of the segment it falls
+    /// on. Glyph advances come from the font's per-codepoint widths, the same
+    /// metrics `canvas_draw_string_justified` measures with. Glyphs that
+    /// would fall past the end of the path are dropped; returns how many
+    /// glyphs were actually placed.
+    fn canvas_draw_string_on_path(
+        &mut self,
+        text: String,
+        path_points: Vec<(f64, f64)>,
+        start_offset: f64,
+        font_name: String,
+        size: f64,
+    ) -> PyResult<usize> {
+        if text.is_empty() || path_points.len() < 2 {
+            return Ok(0);
+        }
+
+        self.canvas_set_font(font_name, size)?;
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        let current_font_name = canvas.get_font_name();
+        let cid_map = self.type0_cid_maps.get(&current_font_name).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "No CID map registered for font {:?}",
+                current_font_name
+            ))
+        })?;
+        let metrics = self.font_metrics.get(&current_font_name);
+        let (synth_bold, synth_italic) = self
+            .synthetic_styles
+            .get(&current_font_name)
+            .copied()
+            .unwrap_or((false, false));
+
+        let mut cursor = start_offset;
+        let mut placed = 0usize;
+        for ch in text.chars() {
+            let Some((x, y, angle_degrees)) = point_on_path(&path_points, cursor) else {
+                break;
+            };
+
+            canvas.save_state();
+            canvas.translate(x, y);
+            canvas.rotate(angle_degrees);
+            canvas.draw_string(0.0, 0.0, &ch.to_string(), cid_map, (synth_bold, synth_italic), None);
+            canvas.restore_state();
+
+            let glyph_width_1000 = metrics
+                .and_then(|m| m.widths.get(&(ch as u32)))
+                .copied()
+                .unwrap_or(500);
+            cursor += glyph_width_1000 as f64 * size / 1000.0;
+            placed += 1;
+        }
+
+        Ok(placed)
+    }
+
+    /// Draw `text` rotated by `angle_radians` about the point `(x, y)`, instead
+    /// of about the page origin. Does the save/translate/rotate/draw/restore
+    /// dance internally so Python callers can't get the anchor math wrong.
+    /// `anchor` controls which part of the (unrotated) text sits at `(x, y)`:
+    /// `"baseline-left"` (default), `"center"`, or `"baseline-right"`.
+    #[pyo3(signature = (x, y, text, angle_radians, anchor=None))]
+    fn canvas_draw_string_rotated(
+        &mut self,
+        x: f64,
+        y: f64,
+        text: String,
+        angle_radians: f64,
+        anchor: Option<String>,
+    ) -> PyResult<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let dx = match anchor.as_deref() {
+            Some("center") | Some("baseline-right") => {
+                let canvas = if let Some((_, _, ref c)) = self.current_page {
+                    c
+                } else {
+                    return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "No current page",
+                    ));
+                };
+                let current_font_name = canvas.get_font_name();
+                let font_size = canvas.get_font_size();
+                let metrics = self.font_metrics.get(&current_font_name);
+                let width_1000: i32 = text
+                    .chars()
+                    .map(|ch| {
+                        metrics
+                            .and_then(|m| m.widths.get(&(ch as u32)))
+                            .copied()
+                            .unwrap_or(500)
+                    })
+                    .sum();
+                let width = width_1000 as f64 * font_size / 1000.0;
+                if anchor.as_deref() == Some("center") {
+                    -width / 2.0
+                } else {
+                    -width
+                }
+            }
+            _ => 0.0,
+        };
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.save_state();
+        canvas.translate(x, y);
+        canvas.rotate(angle_radians.to_degrees());
+        canvas.translate(dx, 0.0);
+
+        self.canvas_draw_string(0.0, 0.0, text)?;
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.restore_state();
+
+        Ok(())
+    }
+
+    /// Draw image from bytes. `interpolate` sets `/Interpolate true` on the
+    /// image XObject, asking the viewer to smooth it when scaled -- opt-in
+    /// per image since it's wanted for downscaled logos/photos but not for
+    /// barcodes or other content that needs to stay crisp pixel-for-pixel.
+    #[pyo3(signature = (x, y, width, height, image_data, interpolate=false, grayscale=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_draw_image(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        image_data: Vec<u8>,
+        interpolate: bool,
+        grayscale: Option<bool>,
+    ) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+
+        let key = image_cache_key(&image_data, interpolate, grayscale);
 
         // Check if image is already registered
         let (image_id, image_name) = if let Some(&(id, name)) = self.images_registry.get(&key) {
@@ -574,11 +2177,17 @@ impl PdfCanvasRenderer {
             let image_id = Ref::new(self.next_image_id);
             self.next_image_id += 1;
 
+            let icc_profile = image_utils::extract_icc_profile(&image_data)
+                .or_else(|| self.default_rgb_profile.clone());
+
             let image_name = image_utils::add_image_to_pdf(
                 &mut self.pdf,
                 &image_data,
                 image_id,
                 &mut self.next_image_id,
+                interpolate,
+                icc_profile.as_deref(),
+                grayscale,
             )?;
 
             self.images_registry.insert(key, (image_id, image_name));
@@ -596,6 +2205,79 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
+    /// Draw a small bitmap as an inline image (`BI`/`ID`/`EI`) directly in
+    /// the content stream instead of registering it as an XObject. For
+    /// tiny 1-bit stencil masks and icons where `canvas_draw_image`'s
+    /// registry bookkeeping is overkill; data over the inline size limit is
+    /// rejected with a message pointing back at `canvas_draw_image`.
+    #[allow(clippy::too_many_arguments)]
+    fn canvas_draw_inline_image(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        data: Vec<u8>,
+        color_space: String,
+        bits: u8,
+    ) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas
+            .draw_inline_image(x, y, width, height, &data, &color_space, bits)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+    }
+
+    /// Register `image_data` as a Type 1 (tiling) pattern and set it as the
+    /// current fill color, so a subsequent fill (e.g. `canvas_rect(...,
+    /// fill=true, ...)`) tiles the image across the filled region -- for
+    /// watermark tiling and textured backgrounds. `tile_width`/`tile_height`
+    /// default to the image's natural pixel size.
+    #[pyo3(signature = (image_data, tile_width=None, tile_height=None))]
+    fn canvas_fill_with_image_pattern(
+        &mut self,
+        image_data: Vec<u8>,
+        tile_width: Option<f64>,
+        tile_height: Option<f64>,
+    ) -> PyResult<()> {
+        let (natural_width, natural_height) = image_utils::image_dimensions(&image_data)?;
+        let tile_width = tile_width.unwrap_or(natural_width as f64).max(1.0);
+        let tile_height = tile_height.unwrap_or(natural_height as f64).max(1.0);
+
+        let image_id = Ref::new(self.next_image_id);
+        self.next_image_id += 1;
+        let pattern_id = Ref::new(self.next_ref_id);
+        self.next_ref_id += 1;
+
+        let pattern_name = image_utils::add_image_tiling_pattern(
+            &mut self.pdf,
+            &image_data,
+            pattern_id,
+            image_id,
+            &mut self.next_image_id,
+            tile_width,
+            tile_height,
+        )?;
+
+        self.patterns_used_on_current_page
+            .insert(pattern_name, pattern_id);
+
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.set_fill_pattern(pattern_name);
+        Ok(())
+    }
+
     /// Translate coordinate system
     fn canvas_translate(&mut self, x: f64, y: f64) -> PyResult<()> {
         let canvas = if let Some((_, _, ref mut c)) = self.current_page {
@@ -637,6 +2319,19 @@ impl PdfCanvasRenderer {
         Ok(())
     }
 
+    /// Skew coordinate system (radians): `ax` shears x along y, `ay` shears y along x
+    fn canvas_skew(&mut self, ax: f64, ay: f64) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        canvas.skew(ax.to_degrees(), ay.to_degrees());
+        Ok(())
+    }
+
     /// Apply transformation matrix [a, b, c, d, e, f]
     fn canvas_transform(&mut self, matrix: Vec<f64>) -> PyResult<()> {
         if matrix.len() != 6 {
@@ -709,7 +2404,11 @@ impl PdfCanvasRenderer {
                 }
                 "SetDash" => {
                     let pattern: Vec<f64> = cmd_dict.get_item("pattern")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'pattern'"))?.extract()?;
-                    self.canvas_set_dash(pattern)?;
+                    let phase: f64 = match cmd_dict.get_item("phase")? {
+                        Some(v) => v.extract()?,
+                        None => 0.0,
+                    };
+                    self.canvas_set_dash(pattern, phase)?;
                 }
                 "SetFont" => {
                     let name: String = cmd_dict.get_item("name")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'name'"))?.extract()?;
@@ -720,6 +2419,62 @@ impl PdfCanvasRenderer {
                     let opacity: f64 = cmd_dict.get_item("opacity")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'opacity'"))?.extract()?;
                     self.canvas_set_opacity(opacity)?;
                 }
+                "SetBlendMode" => {
+                    let mode: String = cmd_dict.get_item("mode")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'mode'"))?.extract()?;
+                    self.canvas_set_blend_mode(mode)?;
+                }
+                "SetRenderingIntent" => {
+                    let intent: String = cmd_dict.get_item("intent")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'intent'"))?.extract()?;
+                    self.canvas_set_rendering_intent(intent)?;
+                }
+                "SetStrokeAlign" => {
+                    let align: String = cmd_dict.get_item("align")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'align'"))?.extract()?;
+                    self.canvas_set_stroke_align(align)?;
+                }
+                "RegisterFallbackFonts" => {
+                    let font_keys: Vec<String> = cmd_dict.get_item("font_keys")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'font_keys'"))?.extract()?;
+                    self.register_fallback_fonts(font_keys)?;
+                }
+                "SetFontSearchPaths" => {
+                    let dirs: Vec<String> = cmd_dict.get_item("dirs")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'dirs'"))?.extract()?;
+                    self.set_font_search_paths(dirs)?;
+                }
+                "SetFallbackChain" => {
+                    let family_names: Vec<String> = cmd_dict.get_item("family_names")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'family_names'"))?.extract()?;
+                    self.set_fallback_chain(family_names)?;
+                }
+                "SetSynthesizeStyles" => {
+                    let enabled: bool = cmd_dict.get_item("enabled")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'enabled'"))?.extract()?;
+                    self.set_synthesize_styles(enabled)?;
+                }
+                "SetLigatures" => {
+                    let enabled: bool = cmd_dict.get_item("enabled")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'enabled'"))?.extract()?;
+                    self.set_ligatures(enabled)?;
+                }
+                "SetProgressCallback" => {
+                    let callback: PyObject = cmd_dict.get_item("callback")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'callback'"))?.extract()?;
+                    self.set_progress_callback(callback)?;
+                }
+                "SetTotalPages" => {
+                    let total: u32 = cmd_dict.get_item("total")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'total'"))?.extract()?;
+                    self.set_total_pages(total)?;
+                }
+                "BeginTag" => {
+                    let role: String = cmd_dict.get_item("role")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'role'"))?.extract()?;
+                    let alt_text: Option<String> = match cmd_dict.get_item("alt_text")? {
+                        Some(v) => v.extract()?,
+                        None => None,
+                    };
+                    self.canvas_begin_tag(role, alt_text)?;
+                }
+                "EndTag" => {
+                    self.canvas_end_tag()?;
+                }
+                "SetTextDecoration" => {
+                    let underline: bool = cmd_dict.get_item("underline")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'underline'"))?.extract()?;
+                    let strikethrough: bool = cmd_dict.get_item("strikethrough")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'strikethrough'"))?.extract()?;
+                    self.canvas_set_text_decoration(underline, strikethrough)?;
+                }
                 "Rect" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
                     let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
@@ -739,6 +2494,20 @@ impl PdfCanvasRenderer {
                     let stroke: bool = cmd_dict.get_item("stroke")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'stroke'"))?.extract()?;
                     self.canvas_round_rect(x, y, width, height, radius, fill, stroke)?;
                 }
+                "DrawShadowRect" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let width: f64 = cmd_dict.get_item("width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'width'"))?.extract()?;
+                    let height: f64 = cmd_dict.get_item("height")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'height'"))?.extract()?;
+                    let blur: f64 = cmd_dict.get_item("blur")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'blur'"))?.extract()?;
+                    let dx: f64 = cmd_dict.get_item("dx")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'dx'"))?.extract()?;
+                    let dy: f64 = cmd_dict.get_item("dy")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'dy'"))?.extract()?;
+                    let r: f64 = cmd_dict.get_item("r")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'r'"))?.extract()?;
+                    let g: f64 = cmd_dict.get_item("g")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'g'"))?.extract()?;
+                    let b: f64 = cmd_dict.get_item("b")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'b'"))?.extract()?;
+                    let opacity: f64 = cmd_dict.get_item("opacity")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'opacity'"))?.extract()?;
+                    self.canvas_draw_shadow_rect(x, y, width, height, blur, dx, dy, r, g, b, opacity)?;
+                }
                 "Line" => {
                     let x1: f64 = cmd_dict.get_item("x1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x1'"))?.extract()?;
                     let y1: f64 = cmd_dict.get_item("y1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y1'"))?.extract()?;
@@ -746,19 +2515,121 @@ impl PdfCanvasRenderer {
                     let y2: f64 = cmd_dict.get_item("y2")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y2'"))?.extract()?;
                     self.canvas_line(x1, y1, x2, y2)?;
                 }
+                "OpMoveTo" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    self.canvas_op_moveto(x, y)?;
+                }
+                "OpLineTo" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    self.canvas_op_lineto(x, y)?;
+                }
+                "OpCurveTo" => {
+                    let x1: f64 = cmd_dict.get_item("x1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x1'"))?.extract()?;
+                    let y1: f64 = cmd_dict.get_item("y1")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y1'"))?.extract()?;
+                    let x2: f64 = cmd_dict.get_item("x2")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x2'"))?.extract()?;
+                    let y2: f64 = cmd_dict.get_item("y2")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y2'"))?.extract()?;
+                    let x3: f64 = cmd_dict.get_item("x3")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x3'"))?.extract()?;
+                    let y3: f64 = cmd_dict.get_item("y3")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y3'"))?.extract()?;
+                    self.canvas_op_curveto(x1, y1, x2, y2, x3, y3)?;
+                }
+                "OpClosePath" => {
+                    self.canvas_op_closepath()?;
+                }
+                "OpRectangle" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let width: f64 = cmd_dict.get_item("width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'width'"))?.extract()?;
+                    let height: f64 = cmd_dict.get_item("height")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'height'"))?.extract()?;
+                    self.canvas_op_rectangle(x, y, width, height)?;
+                }
+                "OpFill" => {
+                    let even_odd: bool = match cmd_dict.get_item("even_odd")? {
+                        Some(v) => v.extract()?,
+                        None => false,
+                    };
+                    self.canvas_op_fill(even_odd)?;
+                }
+                "OpStroke" => {
+                    self.canvas_op_stroke()?;
+                }
+                "OpFillStroke" => {
+                    let even_odd: bool = match cmd_dict.get_item("even_odd")? {
+                        Some(v) => v.extract()?,
+                        None => false,
+                    };
+                    self.canvas_op_fill_stroke(even_odd)?;
+                }
+                "OpClip" => {
+                    let even_odd: bool = match cmd_dict.get_item("even_odd")? {
+                        Some(v) => v.extract()?,
+                        None => false,
+                    };
+                    self.canvas_op_clip(even_odd)?;
+                }
+                "OpEndPath" => {
+                    self.canvas_op_end_path()?;
+                }
                 "DrawString" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
                     let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
                     let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
                     self.canvas_draw_string(x, y, text)?;
                 }
+                "DrawStringHighlighted" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
+                    let r: f64 = cmd_dict.get_item("r")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'r'"))?.extract()?;
+                    let g: f64 = cmd_dict.get_item("g")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'g'"))?.extract()?;
+                    let b: f64 = cmd_dict.get_item("b")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'b'"))?.extract()?;
+                    self.canvas_draw_string_highlighted(x, y, text, r, g, b)?;
+                }
+                "DrawStringJustified" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let text: String = cmd_dict.get_item("text")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'text'"))?.extract()?;
+                    let target_width: f64 = cmd_dict.get_item("target_width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'target_width'"))?.extract()?;
+                    self.canvas_draw_string_justified(x, y, text, target_width)?;
+                }
                 "DrawImage" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
                     let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
                     let width: f64 = cmd_dict.get_item("width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'width'"))?.extract()?;
                     let height: f64 = cmd_dict.get_item("height")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'height'"))?.extract()?;
                     let image_data: Vec<u8> = cmd_dict.get_item("image_data")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'image_data'"))?.extract()?;
-                    self.canvas_draw_image(x, y, width, height, image_data)?;
+                    let interpolate: bool = match cmd_dict.get_item("interpolate")? {
+                        Some(v) => v.extract()?,
+                        None => false,
+                    };
+                    let grayscale: Option<bool> = match cmd_dict.get_item("grayscale")? {
+                        Some(v) => v.extract()?,
+                        None => None,
+                    };
+                    self.canvas_draw_image(x, y, width, height, image_data, interpolate, grayscale)?;
+                }
+                "DrawInlineImage" => {
+                    let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
+                    let y: f64 = cmd_dict.get_item("y")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'y'"))?.extract()?;
+                    let width: f64 = cmd_dict.get_item("width")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'width'"))?.extract()?;
+                    let height: f64 = cmd_dict.get_item("height")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'height'"))?.extract()?;
+                    let data: Vec<u8> = cmd_dict.get_item("data")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'data'"))?.extract()?;
+                    let color_space: String = cmd_dict.get_item("color_space")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'color_space'"))?.extract()?;
+                    let bits: u8 = cmd_dict.get_item("bits")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'bits'"))?.extract()?;
+                    self.canvas_draw_inline_image(x, y, width, height, data, color_space, bits)?;
+                }
+                "FillWithImagePattern" => {
+                    let image_data: Vec<u8> = cmd_dict.get_item("image_data")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'image_data'"))?.extract()?;
+                    let tile_width: Option<f64> = match cmd_dict.get_item("tile_width")? {
+                        Some(v) => v.extract()?,
+                        None => None,
+                    };
+                    let tile_height: Option<f64> = match cmd_dict.get_item("tile_height")? {
+                        Some(v) => v.extract()?,
+                        None => None,
+                    };
+                    self.canvas_fill_with_image_pattern(image_data, tile_width, tile_height)?;
                 }
                 "Translate" => {
                     let x: f64 = cmd_dict.get_item("x")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'x'"))?.extract()?;
@@ -778,6 +2649,11 @@ impl PdfCanvasRenderer {
                     let matrix: Vec<f64> = cmd_dict.get_item("matrix")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'matrix'"))?.extract()?;
                     self.canvas_transform(matrix)?;
                 }
+                "Skew" => {
+                    let ax: f64 = cmd_dict.get_item("ax")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'ax'"))?.extract()?;
+                    let ay: f64 = cmd_dict.get_item("ay")?.ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing 'ay'"))?.extract()?;
+                    self.canvas_skew(ax, ay)?;
+                }
                 _ => {
                     return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                         format!("Unknown command type: {}", cmd_type)
@@ -789,76 +2665,369 @@ impl PdfCanvasRenderer {
     }
 }
 
+/// Build the `images_registry` cache key for `canvas_draw_image`. Small
+/// images are cheap to key directly off their exact bytes; larger ones are
+/// keyed off a hash of the whole buffer (not just the head/tail, which
+/// collide for same-length images sharing a format's fixed header/trailer,
+/// e.g. any two same-size PNGs).
+fn image_cache_key(image_data: &[u8], interpolate: bool, grayscale: Option<bool>) -> String {
+    if image_data.len() > 16 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        image_data.hash(&mut hasher);
+        format!(
+            "canvas_image_{:x}_{}_{}_{:?}",
+            Hasher::finish(&hasher),
+            image_data.len(),
+            interpolate,
+            grayscale
+        )
+    } else {
+        // For small images, use all bytes
+        format!(
+            "canvas_image_{}_{}_{}_{:?}",
+            image_data.len(),
+            image_data
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>(),
+            interpolate,
+            grayscale
+        )
+    }
+}
+
+/// Walk `distance` points along a straight-segment polyline from its start,
+/// returning the `(x, y, tangent_angle_degrees)` at that arc-length position,
+/// or `None` if `distance` is negative or past the end of the path.
+fn point_on_path(path_points: &[(f64, f64)], distance: f64) -> Option<(f64, f64, f64)> {
+    if distance < 0.0 {
+        return None;
+    }
+    let mut remaining = distance;
+    for segment in path_points.windows(2) {
+        let (x0, y0) = segment[0];
+        let (x1, y1) = segment[1];
+        let dx = x1 - x0;
+        let dy = y1 - y0;
+        let seg_len = (dx * dx + dy * dy).sqrt();
+        if seg_len <= f64::EPSILON {
+            continue;
+        }
+        if remaining <= seg_len {
+            let t = remaining / seg_len;
+            return Some((x0 + dx * t, y0 + dy * t, dy.atan2(dx).to_degrees()));
+        }
+        remaining -= seg_len;
+    }
+    None
+}
+
+/// Scale `(page_width, page_height)` down to fit within `max_size` pixels
+/// along its long edge, preserving aspect ratio, for a page thumbnail.
+fn thumbnail_dimensions(page_width: f64, page_height: f64, max_size: u32) -> (u32, u32) {
+    let max_size = max_size.max(1) as f64;
+    let (w, h) = if page_width >= page_height {
+        (max_size, max_size * page_height / page_width.max(f64::EPSILON))
+    } else {
+        (max_size * page_width / page_height.max(f64::EPSILON), max_size)
+    };
+    (w.round().max(1.0) as u32, h.round().max(1.0) as u32)
+}
+
+/// Write queued `AnnotationSpec`s into a finalized page's `/Annots` array.
+fn write_annotations<'a>(
+    annots: &mut pdf_writer::TypedArray<'a, pdf_writer::writers::Annotation<'a>>,
+    specs: &[AnnotationSpec],
+) {
+    for spec in specs {
+        let mut annot = annots.push();
+        match spec {
+            AnnotationSpec::Text { x, y, contents, author, icon } => {
+                annot.subtype(AnnotationType::Text);
+                annot.rect(pdf_writer::Rect::new(
+                    *x as f32,
+                    *y as f32,
+                    *x as f32 + 20.0,
+                    *y as f32 + 20.0,
+                ));
+                annot.contents(TextStr(contents));
+                if let Some(author) = author {
+                    annot.author(TextStr(author));
+                }
+                annot.icon(parse_annotation_icon(icon));
+            }
+            AnnotationSpec::Highlight { quad_points, contents } => {
+                annot.subtype(AnnotationType::Highlight);
+                annot.rect(quad_points_bounds(quad_points));
+                annot.contents(TextStr(contents));
+                annot.quad_points(quad_points.iter().map(|v| *v as f32));
+                annot.color_rgb(1.0, 1.0, 0.0);
+            }
+            AnnotationSpec::Link { x, y, width, height, dest_name } => {
+                annot.subtype(AnnotationType::Link);
+                annot.rect(pdf_writer::Rect::new(
+                    *x as f32,
+                    *y as f32,
+                    *x as f32 + *width as f32,
+                    *y as f32 + *height as f32,
+                ));
+                // No visible border -- the link area is usually laid over
+                // existing text/image content that already looks clickable.
+                annot.border(0.0, 0.0, 0.0, None);
+                annot.pair(Name(b"Dest"), Str(dest_name.as_bytes()));
+            }
+        }
+    }
+}
+
+/// Parse a standard PDF text-annotation icon name, falling back to "Note"
+/// (a sticky note, the most common case) for anything unrecognized.
+fn parse_annotation_icon(name: &str) -> AnnotationIcon<'static> {
+    match name {
+        "Comment" => AnnotationIcon::Comment,
+        "Key" => AnnotationIcon::Key,
+        "Help" => AnnotationIcon::Help,
+        "NewParagraph" => AnnotationIcon::NewParagraph,
+        "Paragraph" => AnnotationIcon::Paragraph,
+        "Insert" => AnnotationIcon::Insert,
+        _ => AnnotationIcon::Note,
+    }
+}
+
+/// Axis-aligned bounding box of a flat `[x1, y1, x2, y2, ...]` QuadPoints
+/// array, used as a highlight annotation's required `/Rect`.
+fn quad_points_bounds(quad_points: &[f64]) -> pdf_writer::Rect {
+    let mut x1 = f64::INFINITY;
+    let mut y1 = f64::INFINITY;
+    let mut x2 = f64::NEG_INFINITY;
+    let mut y2 = f64::NEG_INFINITY;
+    for pair in quad_points.chunks(2) {
+        if let [x, y] = *pair {
+            x1 = x1.min(x);
+            y1 = y1.min(y);
+            x2 = x2.max(x);
+            y2 = y2.max(y);
+        }
+    }
+    pdf_writer::Rect::new(x1 as f32, y1 as f32, x2 as f32, y2 as f32)
+}
+
 impl PdfCanvasRenderer {
+    /// Load (or fetch from the registry) the PDF font resource for `name`,
+    /// registering its CID map and metrics. Shared by `canvas_set_font` and
+    /// `register_fallback_fonts` so both paths go through the same lazy
+    /// system-font discovery and registry bookkeeping.
+    fn resolve_font(&mut self, name: &str) -> PyResult<(Name<'static>, Ref)> {
+        let (family, style) = Self::parse_font_name(name);
+        let font_key = format!("{}:{:?}", family.to_lowercase(), style);
+
+        if let Some(&(name_ref, id_ref)) = self.font_registry.get(&font_key) {
+            return Ok((name_ref, id_ref));
+        }
+
+        // Try to load font from system
+        let (font_path, fell_back_to_regular) = self.find_font_path(&family, style)?;
+
+        // Load and register the font
+        let font_data = load_font_file(&font_path)?;
+        let font_id = Ref::new(self.next_ref_id);
+        self.next_ref_id += 1;
+
+        let (pdf_font_name, cid_map, metrics) =
+            add_truetype_font(&mut self.pdf, &font_data, font_id, &mut self.next_ref_id)?;
+
+        // Register font
+        self.font_registry.insert(font_key, (pdf_font_name, font_id));
+        self.type0_cid_maps.insert(pdf_font_name, cid_map);
+        self.font_metrics.insert(pdf_font_name, metrics);
+
+        // The actual file we loaded is Regular even though a bold/italic style
+        // was requested: remember that so canvas_draw_string can synthesize it.
+        if self.synthesize_styles && fell_back_to_regular {
+            self.synthetic_styles
+                .insert(pdf_font_name, (style.is_bold(), style.italic));
+        }
+
+        // Also register under original name for quick lookup
+        if !self.font_registry.contains_key(name) {
+            self.font_registry
+                .insert(name.to_string(), (pdf_font_name, font_id));
+        }
+
+        Ok((pdf_font_name, font_id))
+    }
+
+    /// Hand a just-finished page's content stream bytes off to be written as
+    /// the indirect object `content_id`. In non-streaming mode this goes
+    /// straight into `self.pdf`'s buffer, same as before. In streaming mode
+    /// the bytes are spooled to a temp file instead and `save()` reads them
+    /// back and writes them into `self.pdf` right before finishing, so they
+    /// don't sit in `pdf_writer`'s output buffer for the rest of the
+    /// document's generation.
+    fn finalize_content_stream(&mut self, content_id: Ref, content_bytes: Vec<u8>) -> PyResult<()> {
+        if !self.streaming {
+            self.pdf.stream(content_id, &content_bytes);
+            return Ok(());
+        }
+        let path = std::env::temp_dir().join(format!(
+            "docquill_stream_{}_{}_{}.bin",
+            std::process::id(),
+            self.instance_id,
+            content_id.get()
+        ));
+        std::fs::write(&path, &content_bytes).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+                "Failed to spool content stream to {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        self.pending_streamed_contents.push((content_id, path));
+        Ok(())
+    }
+
+    /// Read back every content stream spooled by `finalize_content_stream`
+    /// while in streaming mode and write it into `self.pdf`, clearing the
+    /// pending list. Called by `save()` right before `self.pdf.finish()`.
+    fn flush_streamed_contents(&mut self) -> PyResult<()> {
+        let pending = std::mem::take(&mut self.pending_streamed_contents);
+        for (content_id, path) in pending {
+            let content_bytes = std::fs::read(&path).map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
+                    "Failed to read spooled content stream {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            self.pdf.stream(content_id, &content_bytes);
+            let _ = std::fs::remove_file(&path);
+        }
+        Ok(())
+    }
+
+    /// Draw a single decoration line (underline or strikethrough) below/through a
+    /// just-drawn string, using the current fill color so it matches the text.
+    /// `position`/`thickness` are in 1000-unit em space, scaled here by `font_size`.
+    fn draw_decoration_line(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        font_size: f64,
+        position: f32,
+        thickness: f32,
+    ) -> PyResult<()> {
+        let canvas = if let Some((_, _, ref mut c)) = self.current_page {
+            c
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "No current page",
+            ));
+        };
+        let line_width = (thickness as f64 * font_size / 1000.0).max(0.4);
+        let line_y = y + position as f64 * font_size / 1000.0;
+
+        canvas.save_state();
+        canvas.set_stroke_color(canvas.get_fill_color());
+        canvas.set_line_width(line_width);
+        canvas.line(x, line_y, x + width, line_y);
+        canvas.restore_state();
+        Ok(())
+    }
+
     /// Parse font name to extract family and style
     /// Examples:
     /// - "Calibri" -> ("Calibri", Regular)
     /// - "Arial Bold" -> ("Arial", Bold)
     /// - "Times New Roman-Italic" -> ("Times New Roman", Italic)
     /// - "DejaVu Sans-BoldOblique" -> ("DejaVu Sans", BoldItalic)
+    /// - "Calibri Light" -> ("Calibri", Light weight)
+    /// - "Arial Narrow Bold" -> ("Arial", Bold weight + Condensed width)
     fn parse_font_name(name: &str) -> (String, FontStyle) {
-        let name_lower = name.to_lowercase();
-        
-        // Check for style suffixes
-        let is_bold = name_lower.contains("bold") || name_lower.ends_with("-bd") || name_lower.ends_with("bd");
-        let is_italic = name_lower.contains("italic") || name_lower.contains("oblique") 
-            || name_lower.ends_with("-it") || name_lower.ends_with("it");
-        
-        let style = match (is_bold, is_italic) {
-            (true, true) => FontStyle::BoldItalic,
-            (true, false) => FontStyle::Bold,
-            (false, true) => FontStyle::Italic,
-            (false, false) => FontStyle::Regular,
-        };
-        
-        // Extract family name by removing style suffixes
-        let mut family = name.to_string();
-        let suffixes = [
-            "-BoldOblique", "-BoldItalic", "-Bold", "-Oblique", "-Italic",
-            " BoldOblique", " BoldItalic", " Bold", " Oblique", " Italic",
-            "BoldOblique", "BoldItalic", "Bold", "Oblique", "Italic",
+        let style = FontStyle::from_name(name);
+
+        // Extract family name by repeatedly stripping trailing weight/width/italic
+        // keywords (and the separator before them), so multi-word style suffixes
+        // like "Narrow Bold" or "Semibold Italic" are all peeled off in turn.
+        const KEYWORDS: &[&str] = &[
+            "bolditalic", "boldoblique", "extrabold", "semibold", "demibold",
+            "extralight", "ultralight", "condensed", "expanded", "extended",
+            "oblique", "italic", "medium", "narrow", "light", "black", "heavy",
+            "thin", "bold", "wide", "regular", "normal",
         ];
-        
-        for suffix in &suffixes {
-            if let Some(stripped) = family.strip_suffix(suffix) {
-                family = stripped.to_string();
-                break;
+        let mut family = name.to_string();
+        loop {
+            let trimmed = family.trim_end_matches(['-', ' ']).to_string();
+            let lower = trimmed.to_lowercase();
+            let matched = KEYWORDS
+                .iter()
+                .find(|kw| lower.ends_with(*kw) && trimmed.len() > kw.len());
+            match matched {
+                Some(kw) => family = trimmed[..trimmed.len() - kw.len()].to_string(),
+                None => {
+                    family = trimmed;
+                    break;
+                }
             }
         }
-        
-        // Clean up family name
-        family = family.trim_end_matches('-').trim().to_string();
-        
+
         (family, style)
     }
     
-    /// Find font file path for given family and style
+    /// Find font file path for given family and style.
     /// Priority:
-    /// 1. Exact match in system fonts
-    /// 2. System fallback font
-    fn find_font_path(&self, family: &str, style: FontStyle) -> PyResult<PathBuf> {
+    /// 1. Exact match in system fonts (custom search paths first, then platform paths)
+    /// 2. Configured fallback_chain family names, in order
+    /// 3. The constructor's `default_font_path`, if one was given
+    /// 4. System fallback font (bundled DejaVu Sans, then common system fonts)
+    ///
+    /// Returns whether the match found is a Regular file substituted for a
+    /// requested bold/italic style, so the caller can synthesize it. Never
+    /// panics: a missing font anywhere in this chain falls through to the
+    /// next tier, and only the final tier returns a `PyRuntimeError`.
+    fn find_font_path(&self, family: &str, style: FontStyle) -> PyResult<(PathBuf, bool)> {
+        let wants_synthesis = style.is_bold() || style.italic;
+
         // Try exact match first
-        if let Some(path) = find_system_font(family, style) {
-            return Ok(path);
+        if let Some(path) = find_system_font(family, style, &self.font_search_paths) {
+            return Ok((path, false));
         }
-        
+
         // Try with Regular style if requested style not found
-        if style != FontStyle::Regular {
-            if let Some(path) = find_system_font(family, FontStyle::Regular) {
-                return Ok(path);
+        if style != FontStyle::REGULAR {
+            if let Some(path) = find_system_font(family, FontStyle::REGULAR, &self.font_search_paths) {
+                return Ok((path, wants_synthesis));
+            }
+        }
+
+        // Try the configured fallback chain before giving up on the family entirely
+        for fallback_family in &self.fallback_chain {
+            if let Some(path) = find_system_font(fallback_family, style, &self.font_search_paths) {
+                return Ok((path, false));
+            }
+            if let Some(path) =
+                find_system_font(fallback_family, FontStyle::REGULAR, &self.font_search_paths)
+            {
+                return Ok((path, wants_synthesis));
             }
         }
-        
+
+        // An explicit default font set via the constructor takes priority
+        // over the bundled/system fallback search below.
+        if let Some(path) = &self.default_font_path {
+            return Ok((path.clone(), wants_synthesis));
+        }
+
         // Fall back to system default font
-        if let Some(path) = get_fallback_font(style) {
-            return Ok(path);
+        if let Some(path) = get_fallback_font(style, &self.font_search_paths) {
+            return Ok((path, false));
         }
-        
+
         // Last resort: try to get any fallback
-        if let Some(path) = get_fallback_font(FontStyle::Regular) {
-            return Ok(path);
+        if let Some(path) = get_fallback_font(FontStyle::REGULAR, &self.font_search_paths) {
+            return Ok((path, wants_synthesis));
         }
-        
+
         Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
             format!(
                 "No suitable font found for '{}' ({:?}). \
@@ -875,8 +3044,10 @@ impl PdfCanvasRenderer {
         &mut self,
         alpha_key: u32,
         alpha: f32,
+        blend_mode: Option<String>,
     ) -> (Name<'static>, Ref) {
-        if let Some(&(name, ref_id)) = self.ext_graphics_states.get(&alpha_key) {
+        let key = (alpha_key, blend_mode.clone());
+        if let Some(&(name, ref_id)) = self.ext_graphics_states.get(&key) {
             return (name, ref_id);
         }
 
@@ -891,13 +3062,54 @@ impl PdfCanvasRenderer {
         {
             let mut ext = self.pdf.ext_graphics(gs_ref);
             ext.non_stroking_alpha(alpha).stroking_alpha(alpha);
+            if let Some(mode) = blend_mode.as_deref().and_then(parse_blend_mode) {
+                ext.blend_mode(mode);
+            }
         }
 
-        self.ext_graphics_states.insert(alpha_key, (name, gs_ref));
+        self.ext_graphics_states.insert(key, (name, gs_ref));
         (name, gs_ref)
     }
 }
 
+/// Parse a standard PDF 1.4 blend mode name into `pdf_writer`'s `BlendMode`
+/// enum. Shared by `canvas_set_blend_mode` (to validate the name up front)
+/// and `get_or_create_ext_graphics_state` (to write the `/BM` entry).
+fn parse_blend_mode(name: &str) -> Option<BlendMode> {
+    Some(match name {
+        "Normal" => BlendMode::Normal,
+        "Multiply" => BlendMode::Multiply,
+        "Screen" => BlendMode::Screen,
+        "Overlay" => BlendMode::Overlay,
+        "Darken" => BlendMode::Darken,
+        "Lighten" => BlendMode::Lighten,
+        "ColorDodge" => BlendMode::ColorDodge,
+        "ColorBurn" => BlendMode::ColorBurn,
+        "HardLight" => BlendMode::HardLight,
+        "SoftLight" => BlendMode::SoftLight,
+        "Difference" => BlendMode::Difference,
+        "Exclusion" => BlendMode::Exclusion,
+        "Hue" => BlendMode::Hue,
+        "Saturation" => BlendMode::Saturation,
+        "Color" => BlendMode::Color,
+        "Luminosity" => BlendMode::Luminosity,
+        _ => return None,
+    })
+}
+
+/// Parse a PDF rendering intent name into `pdf_writer`'s `RenderingIntent`
+/// enum. Shared by `canvas_set_rendering_intent` (to validate the name up
+/// front) and `Content::set_rendering_intent` (to emit the `ri` operator).
+fn parse_rendering_intent(name: &str) -> Option<RenderingIntent> {
+    Some(match name {
+        "AbsoluteColorimetric" => RenderingIntent::AbsoluteColorimetric,
+        "RelativeColorimetric" => RenderingIntent::RelativeColorimetric,
+        "Saturation" => RenderingIntent::Saturation,
+        "Perceptual" => RenderingIntent::Perceptual,
+        _ => return None,
+    })
+}
+
 // ===== EMF/WMF Converter Functions =====
 
 /// Convert EMF/WMF file to SVG
@@ -905,11 +3117,19 @@ impl PdfCanvasRenderer {
 /// Args:
 ///     input_path: Path to input EMF/WMF file
 ///     output_path: Path to output SVG file
+///     precision: Decimal places for coordinates/lengths in the output SVG
+///         (0 emits integer coordinates). Defaults to 2. Ignored for WMF input.
+///     dpi: Device resolution used to size WMF output lacking a placeable header
+///         (and, if `dpi_override` is set, to override a placeable header's own
+///         units_per_inch). Defaults to 96. Ignored for EMF input.
+///     dpi_override: If true, `dpi` also overrides a placeable WMF header's
+///         physical size instead of only applying to headerless WMF.
 ///
 /// Returns:
 ///     True if conversion successful, False otherwise
 #[pyfunction]
-fn convert_emf_to_svg(input_path: &str, output_path: &str) -> PyResult<bool> {
+#[pyo3(signature = (input_path, output_path, precision=None, dpi=None, dpi_override=false))]
+fn convert_emf_to_svg(input_path: &str, output_path: &str, precision: Option<u8>, dpi: Option<f64>, dpi_override: bool) -> PyResult<bool> {
     let input = std::path::Path::new(input_path);
     if !input.exists() {
         return Err(PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
@@ -920,11 +3140,11 @@ fn convert_emf_to_svg(input_path: &str, output_path: &str) -> PyResult<bool> {
     let data = std::fs::read(input).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(
         format!("Failed to read input file: {}", e)
     ))?;
-    
+
     // Detect format
     let is_emf = emf::is_emf_format(&data);
     let is_wmf = wmf::is_wmf_format(&data);
-    
+
     if !is_emf && !is_wmf {
         return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
             "Input file must be EMF or WMF format"
@@ -932,11 +3152,15 @@ fn convert_emf_to_svg(input_path: &str, output_path: &str) -> PyResult<bool> {
     }
 
     let svg_content = if is_emf {
-        emf::convert_emf_to_svg(&data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        let result = match precision {
+            Some(p) => emf::convert_emf_to_svg_opts(&data, p),
+            None => emf::convert_emf_to_svg(&data),
+        };
+        result.map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
             format!("EMF conversion failed: {}", e)
         ))?
     } else {
-        wmf::convert_wmf_to_svg(&data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        wmf::convert_wmf_to_svg_opts(&data, dpi, dpi_override).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
             format!("WMF conversion failed: {}", e)
         ))?
     };
@@ -948,18 +3172,163 @@ fn convert_emf_to_svg(input_path: &str, output_path: &str) -> PyResult<bool> {
     Ok(true)
 }
 
+/// Convert EMF directly to a single-page PDF, replaying metafile records onto a
+/// PDF canvas instead of rasterizing an intermediate SVG.
+///
+/// Args:
+///     input_path: Path to input EMF file
+///     output_path: Path to output PDF file
+///
+/// Returns:
+///     True if conversion successful, False otherwise
+#[pyfunction]
+fn convert_emf_to_pdf(input_path: &str, output_path: &str) -> PyResult<bool> {
+    let input = std::path::Path::new(input_path);
+    if !input.exists() {
+        return Err(PyErr::new::<pyo3::exceptions::PyFileNotFoundError, _>(
+            format!("Input file not found: {}", input_path)
+        ));
+    }
+
+    let data = std::fs::read(input).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(
+        format!("Failed to read input file: {}", e)
+    ))?;
+
+    if !emf::is_emf_format(&data) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Input file must be EMF format"
+        ));
+    }
+
+    let pdf_bytes = emf_to_pdf::convert_emf_to_pdf(&data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        format!("EMF to PDF conversion failed: {}", e)
+    ))?;
+
+    std::fs::write(output_path, pdf_bytes).map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(
+        format!("Failed to write output file: {}", e)
+    ))?;
+
+    Ok(true)
+}
+
+/// Inspect EMF data without running a full conversion
+///
+/// Args:
+///     data: EMF data as bytes
+///
+/// Returns:
+///     dict with "bounds" (rclBounds, device units), "frame" (rclFrame, 0.01mm units),
+///     "dpi", "record_count", and "has_emf_plus"
+#[pyfunction]
+fn get_emf_info(py: Python, data: &[u8]) -> PyResult<Py<PyDict>> {
+    let info = emf::get_emf_info(data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        format!("Failed to read EMF info: {}", e)
+    ))?;
+
+    let result = PyDict::new(py);
+    result.set_item("bounds", info.bounds)?;
+    result.set_item("frame", info.frame)?;
+    result.set_item("dpi", info.dpi)?;
+    result.set_item("record_count", info.record_count)?;
+    result.set_item("has_emf_plus", info.has_emf_plus)?;
+    Ok(result.into())
+}
+
+/// Enumerate every font file discoverable on the current platform, so the UI
+/// can show users which fonts will actually embed.
+///
+/// Reuses the same directory scanning as `find_system_font`, but enumerates
+/// every font file found rather than matching by name, reading the `name`
+/// table to report the font's real family/subfamily. De-duplicated by
+/// resolved (canonicalized) path.
+///
+/// Returns:
+///     List of dicts, each with "family", "style", and "path"
+#[pyfunction]
+fn list_system_fonts(py: Python) -> PyResult<Vec<Py<PyDict>>> {
+    enumerate_system_fonts(&[])
+        .into_iter()
+        .map(|font| {
+            let result = PyDict::new(py);
+            result.set_item("family", font.family)?;
+            result.set_item("style", font.style)?;
+            result.set_item("path", font.path.to_string_lossy().to_string())?;
+            Ok(result.into())
+        })
+        .collect()
+}
+
+/// Inspect WMF data without running a full conversion
+///
+/// Args:
+///     data: WMF data as bytes
+///
+/// Returns:
+///     dict with "bounds" (placeable header BoundingBox, or None), "dpi", "record_count",
+///     and "has_emf_plus"
+#[pyfunction]
+fn get_wmf_info(py: Python, data: &[u8]) -> PyResult<Py<PyDict>> {
+    let info = wmf::get_wmf_info(data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        format!("Failed to read WMF info: {}", e)
+    ))?;
+
+    let result = PyDict::new(py);
+    result.set_item("bounds", info.bounds)?;
+    result.set_item("dpi", info.dpi)?;
+    result.set_item("record_count", info.record_count)?;
+    result.set_item("has_emf_plus", info.has_emf_plus)?;
+    Ok(result.into())
+}
+
+/// Sniff which metafile format `data` is, so callers can validate input and
+/// route to the right converter up front instead of trying a conversion and
+/// catching the exception if it guessed wrong.
+///
+/// Args:
+///     data: candidate metafile bytes
+///
+/// Returns:
+///     `"emf"`, `"emf+"` (EMF containing EMF+ comment records), `"wmf"`,
+///     `"placeable_wmf"` (WMF with the Aldus placeable header), or `None`
+///     if `data` matches neither format.
+#[pyfunction]
+fn detect_metafile_format(data: &[u8]) -> PyResult<Option<String>> {
+    if emf::is_emf_format(data) {
+        let has_emf_plus = emf::get_emf_info(data)
+            .map(|info| info.has_emf_plus)
+            .unwrap_or(false);
+        return Ok(Some(if has_emf_plus { "emf+" } else { "emf" }.to_string()));
+    }
+    if wmf::is_wmf_format(data) {
+        let is_placeable = data.len() >= 4
+            && u32::from_le_bytes([data[0], data[1], data[2], data[3]]) == 0xCDD79AC6;
+        return Ok(Some(
+            if is_placeable { "placeable_wmf" } else { "wmf" }.to_string(),
+        ));
+    }
+    Ok(None)
+}
+
 /// Convert EMF/WMF bytes to SVG string
 ///
 /// Args:
 ///     emf_data: EMF/WMF data as bytes
+///     precision: Decimal places for coordinates/lengths in the output SVG
+///         (0 emits integer coordinates). Defaults to 2. Ignored for WMF input.
+///     dpi: Device resolution used to size WMF output lacking a placeable header
+///         (and, if `dpi_override` is set, to override a placeable header's own
+///         units_per_inch). Defaults to 96. Ignored for EMF input.
+///     dpi_override: If true, `dpi` also overrides a placeable WMF header's
+///         physical size instead of only applying to headerless WMF.
 ///
 /// Returns:
 ///     SVG content as string
 #[pyfunction]
-fn convert_emf_bytes_to_svg(emf_data: &[u8]) -> PyResult<String> {
+#[pyo3(signature = (emf_data, precision=None, dpi=None, dpi_override=false))]
+fn convert_emf_bytes_to_svg(emf_data: &[u8], precision: Option<u8>, dpi: Option<f64>, dpi_override: bool) -> PyResult<String> {
     let is_emf = emf::is_emf_format(emf_data);
     let is_wmf = wmf::is_wmf_format(emf_data);
-    
+
     if !is_emf && !is_wmf {
         return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
             "Input must be EMF or WMF data"
@@ -967,16 +3336,105 @@ fn convert_emf_bytes_to_svg(emf_data: &[u8]) -> PyResult<String> {
     }
 
     if is_emf {
-        emf::convert_emf_to_svg(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
-            format!("EMF conversion failed: {}", e)
+        match precision {
+            Some(p) => emf::convert_emf_to_svg_opts(emf_data, p).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                format!("EMF conversion failed: {}", e)
+            )),
+            None => emf::convert(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                format!("EMF conversion failed: {}", e)
+            )),
+        }
+    } else if dpi.is_none() && !dpi_override {
+        wmf::convert(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            format!("WMF conversion failed: {}", e)
         ))
     } else {
-        wmf::convert_wmf_to_svg(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        wmf::convert_wmf_to_svg_opts(emf_data, dpi, dpi_override).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
             format!("WMF conversion failed: {}", e)
         ))
     }
 }
 
+/// Convert many EMF/WMF byte buffers to SVG in parallel (via `rayon`), with
+/// the GIL released for the whole batch -- for folders of thousands of small
+/// metafiles, where per-call Python/FFI overhead and single-threaded
+/// conversion both dominate over doing it one file at a time.
+///
+/// Args:
+///     items: List of EMF/WMF file contents as bytes
+///
+/// Returns:
+///     List of (success, svg_or_error) tuples, one per input item, in the
+///     same order as `items`.
+#[pyfunction]
+fn convert_emf_batch(py: Python, items: Vec<Vec<u8>>) -> PyResult<Vec<(bool, String)>> {
+    Ok(py.allow_threads(|| {
+        items
+            .par_iter()
+            .map(|data| {
+                let is_emf = emf::is_emf_format(data);
+                let is_wmf = wmf::is_wmf_format(data);
+                if !is_emf && !is_wmf {
+                    return (false, "Input must be EMF or WMF data".to_string());
+                }
+                let result = if is_emf {
+                    emf::convert_emf_to_svg(data)
+                        .map_err(|e| format!("EMF conversion failed: {}", e))
+                } else {
+                    wmf::convert_wmf_to_svg(data)
+                        .map_err(|e| format!("WMF conversion failed: {}", e))
+                };
+                match result {
+                    Ok(svg) => (true, svg),
+                    Err(e) => (false, e),
+                }
+            })
+            .collect()
+    }))
+}
+
+/// Convert EMF bytes to SVG, never failing outright on an unsupported or malformed record.
+/// Intended for batch/report tooling that would rather get a partial rendering plus a list
+/// of what was skipped than an all-or-nothing error.
+///
+/// Args:
+///     emf_data: EMF data as bytes
+///
+/// Returns:
+///     Tuple of (SVG content as string, list of diagnostic warning strings)
+#[pyfunction]
+fn convert_emf_to_svg_lenient(emf_data: &[u8]) -> PyResult<(String, Vec<String>)> {
+    emf::convert_emf_to_svg_lenient(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        format!("EMF conversion failed: {}", e)
+    ))
+}
+
+/// Convert EMF bytes to SVG, additionally returning layout/complexity metadata from
+/// the same parsing pass -- for an assembler that needs to size the placement box and
+/// flag overly complex graphics for rasterization without re-parsing the SVG.
+///
+/// Args:
+///     emf_data: EMF data as bytes
+///
+/// Returns:
+///     Tuple of (SVG content as string, dict with "view_box", "width_mm", "height_mm",
+///     "element_count" (path/text/image elements emitted), and "record_types" (sorted
+///     list of distinct EMF record type names encountered))
+#[pyfunction]
+fn convert_emf_to_svg_with_meta(py: Python, emf_data: &[u8]) -> PyResult<(String, Py<PyDict>)> {
+    let (svg, meta) = emf::convert_emf_to_svg_with_meta(emf_data).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        format!("EMF conversion failed: {}", e)
+    ))?;
+
+    let result = PyDict::new(py);
+    result.set_item("view_box", meta.view_box)?;
+    result.set_item("width_mm", meta.width_mm)?;
+    result.set_item("height_mm", meta.height_mm)?;
+    result.set_item("element_count", meta.element_count)?;
+    result.set_item("record_types", meta.record_types)?;
+    Ok((svg, result.into()))
+}
+
 /// Convert SVG string to PNG bytes
 ///
 /// Args:
@@ -1048,25 +3506,154 @@ fn convert_svg_to_png(svg_data: &str, width: Option<u32>, height: Option<u32>) -
 #[pyo3(signature = (emf_data, width=None, height=None))]
 fn convert_emf_to_png(emf_data: &[u8], width: Option<u32>, height: Option<u32>) -> PyResult<Vec<u8>> {
     // First convert to SVG
-    let svg_content = convert_emf_bytes_to_svg(emf_data)?;
-    
+    let svg_content = convert_emf_bytes_to_svg(emf_data, None, None, false)?;
+
     // Then convert SVG to PNG
     convert_svg_to_png(&svg_content, width, height)
 }
 
+/// Rasterize an SVG into a pixmap of exactly `width`x`height`, scaling the native SVG
+/// content to fit, and filling `background` (or leaving it transparent) before drawing.
+fn render_svg_to_pixmap(svg_data: &str, width: u32, height: u32, background: Option<(u8, u8, u8)>) -> PyResult<tiny_skia::Pixmap> {
+    use usvg::TreeParsing;
+
+    let opt = usvg::Options::default();
+    let tree = usvg::Tree::from_str(svg_data, &opt).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to parse SVG: {}", e))
+    })?;
+
+    let svg_width = tree.size.width().max(1.0);
+    let svg_height = tree.size.height().max(1.0);
+    let scale_x = width as f32 / svg_width;
+    let scale_y = height as f32 / svg_height;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width.max(1), height.max(1)).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to create pixmap")
+    })?;
+
+    if let Some((r, g, b)) = background {
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, 255));
+    }
+
+    let transform = tiny_skia::Transform::from_scale(scale_x, scale_y);
+    let rtree = resvg::Tree::from_usvg(&tree);
+    rtree.render(transform, &mut pixmap.as_mut());
+
+    Ok(pixmap)
+}
+
+/// Rasterize EMF/WMF data to PNG at a specific DPI, rather than a target pixel size.
+///
+/// The output pixel dimensions are derived from the metafile's frame rectangle (its
+/// physical size in millimeters) multiplied by `dpi`, so the same file produces larger
+/// bitmaps at higher DPI instead of the fixed/auto sizing `convert_emf_to_png` uses. The
+/// background is transparent unless `background` gives an (r, g, b) fill.
+///
+/// Args:
+///     emf_data: EMF/WMF data as bytes
+///     dpi: Target resolution in dots per inch
+///     background: Optional (r, g, b) background color; transparent if omitted
+///
+/// Returns:
+///     PNG data as bytes
+#[pyfunction]
+#[pyo3(signature = (emf_data, dpi, background=None))]
+fn convert_emf_to_png_at_dpi(emf_data: &[u8], dpi: f64, background: Option<(u8, u8, u8)>) -> PyResult<Vec<u8>> {
+    if dpi <= 0.0 || !dpi.is_finite() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("dpi must be a positive, finite number"));
+    }
+
+    let is_emf = emf::is_emf_format(emf_data);
+    let is_wmf = wmf::is_wmf_format(emf_data);
+    if !is_emf && !is_wmf {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Input must be EMF or WMF data"
+        ));
+    }
+
+    // frame_size_px is measured at 96 DPI; scale it to the requested DPI rather than
+    // re-deriving millimeters, since both converters already normalize through that path.
+    let (frame_width_96, frame_height_96) = if is_emf {
+        let (w, h, _header_size) = emf::frame_size_px(emf_data).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to read EMF frame size: {}", e))
+        })?;
+        (w, h)
+    } else {
+        wmf::frame_size_px(emf_data).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to read WMF frame size: {}", e))
+        })?
+    };
+
+    let scale = dpi / 96.0;
+    let target_width = ((frame_width_96 * scale).round() as u32).max(1);
+    let target_height = ((frame_height_96 * scale).round() as u32).max(1);
+
+    let svg_content = convert_emf_bytes_to_svg(emf_data, None, None, false)?;
+    let pixmap = render_svg_to_pixmap(&svg_content, target_width, target_height, background)?;
+
+    pixmap.encode_png().map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to encode PNG: {}", e))
+    })
+}
+
 /// Python module for DocQuill Rust components
 #[pymodule]
 fn docquill_rust(_py: Python, m: &PyModule) -> PyResult<()> {
     // PDF renderer
     m.add_class::<PdfCanvasRenderer>()?;
-    
+    m.add_function(wrap_pyfunction!(list_system_fonts, m)?)?;
+
     // EMF/WMF converter functions
     m.add_function(wrap_pyfunction!(convert_emf_to_svg, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_emf_to_pdf, m)?)?;
+    m.add_function(wrap_pyfunction!(get_emf_info, m)?)?;
+    m.add_function(wrap_pyfunction!(get_wmf_info, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_metafile_format, m)?)?;
     m.add_function(wrap_pyfunction!(convert_emf_bytes_to_svg, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_emf_to_svg_lenient, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_emf_to_svg_with_meta, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_emf_batch, m)?)?;
     
     // SVG to PNG conversion
     m.add_function(wrap_pyfunction!(convert_svg_to_png, m)?)?;
     m.add_function(wrap_pyfunction!(convert_emf_to_png, m)?)?;
-    
+    m.add_function(wrap_pyfunction!(convert_emf_to_png_at_dpi, m)?)?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::image_cache_key;
+
+    // Two "PNGs" of identical length with the same 8-byte signature and the
+    // same trailing IEND chunk, differing only in the middle -- exactly the
+    // shape the old head/tail-only hash treated as identical.
+    fn fake_png(middle: u8) -> Vec<u8> {
+        let mut data = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        data.extend(std::iter::repeat(middle).take(32));
+        data.extend([0x00, 0x00, 0x00, 0x00, b'I', b'E', b'N', b'D']);
+        data
+    }
+
+    #[test]
+    fn cache_key_differs_for_same_size_images_with_matching_head_and_tail() {
+        let a = fake_png(0x11);
+        let b = fake_png(0x22);
+        assert_eq!(a.len(), b.len());
+        assert_ne!(
+            image_cache_key(&a, false, None),
+            image_cache_key(&b, false, None)
+        );
+    }
+
+    #[test]
+    fn cache_key_matches_for_identical_images() {
+        let a = fake_png(0x11);
+        let b = fake_png(0x11);
+        assert_eq!(
+            image_cache_key(&a, false, None),
+            image_cache_key(&b, false, None)
+        );
+    }
+}
@@ -1,9 +1,36 @@
 //! SVG writer for generating SVG output
 
 use svg::Document;
-use svg::node::element::{Rectangle, Image, Text, Path};
+use svg::node::element::{ClipPath, Definitions, Rectangle, Image, Text, Path, LinearGradient, Stop};
 use svg::node::Text as TextNode;
 
+/// Text styling derived from an EMF LOGFONT, for `SvgWriter::add_text_font`
+#[derive(Default, Clone)]
+pub struct FontStyle {
+    pub family: Option<String>,
+    pub size: Option<f64>,
+    pub fill_color: Option<String>,
+    pub weight: Option<u32>,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikeout: bool,
+    /// SVG `text-anchor` ("start", "middle", or "end"); `None` leaves the SVG default (start).
+    pub anchor: Option<String>,
+    /// SVG `dominant-baseline` ("hanging", "text-after-edge", etc.); `None` leaves the
+    /// SVG default (alphabetic, i.e. the given y is the text baseline).
+    pub dominant_baseline: Option<String>,
+    /// Device-space total advance width for the run, from an EMRTEXT `dx` spacing
+    /// array; emitted as `textLength`/`lengthAdjust="spacingAndGlyphs"` so justified
+    /// or right-aligned EMF text keeps its intended per-character spacing.
+    pub text_length: Option<f64>,
+}
+
+/// Default number of decimal places used to format coordinates and lengths
+/// in the generated SVG, when the caller doesn't request a specific
+/// precision via `SvgWriter::with_precision`. Keeps file size and diffs
+/// down compared to emitting `f64`'s full precision.
+pub(crate) const DEFAULT_COORD_PRECISION: u8 = 2;
+
 /// SVG writer for building SVG documents
 pub struct SvgWriter {
     width: u32,
@@ -14,10 +41,42 @@ pub struct SvgWriter {
     min_y: f64,
     max_x: f64,
     max_y: f64,
+    /// <clipPath> definitions registered via `define_clip_rect`/`define_clip_path`, keyed by id
+    clip_defs: Vec<(String, String, bool)>,
+    next_clip_id: u32,
+    /// <linearGradient> definitions registered via `define_linear_gradient`: (id, x1, y1, x2, y2, stops)
+    gradient_defs: Vec<(String, f64, f64, f64, f64, Vec<(f64, String)>)>,
+    next_gradient_id: u32,
+    /// Explicit viewBox set via `set_viewbox`, overriding the content-bounds-derived one
+    fixed_viewbox: Option<(f64, f64, f64, f64)>,
+    /// Decimal places used when formatting coordinates/lengths; 0 emits integers.
+    precision: u8,
+    path_count: usize,
+    text_count: usize,
+    image_count: usize,
+}
+
+/// Emitted-element and geometry summary returned by `finish_with_meta`, for callers
+/// that need to size a placement box or flag overly complex graphics without
+/// re-parsing the SVG they were just handed.
+#[derive(Debug, Clone)]
+pub struct SvgMeta {
+    pub view_box: (f64, f64, f64, f64),
+    pub path_count: usize,
+    pub text_count: usize,
+    pub image_count: usize,
 }
 
 impl SvgWriter {
     pub fn new(width: u32, height: u32) -> Self {
+        Self::with_precision(width, height, DEFAULT_COORD_PRECISION)
+    }
+
+    /// Like `new`, but controls how many decimal places coordinates and lengths
+    /// are rounded to in the output (0 emits integer coordinates). Lower precision
+    /// keeps generated SVGs smaller and more diff-friendly at a small cost in
+    /// positional accuracy.
+    pub fn with_precision(width: u32, height: u32, precision: u8) -> Self {
         Self {
             width,
             height,
@@ -27,7 +86,118 @@ impl SvgWriter {
             min_y: f64::MAX,
             max_x: f64::MIN,
             max_y: f64::MIN,
+            clip_defs: Vec::new(),
+            next_clip_id: 0,
+            gradient_defs: Vec::new(),
+            next_gradient_id: 0,
+            fixed_viewbox: None,
+            precision,
+            path_count: 0,
+            text_count: 0,
+            image_count: 0,
+        }
+    }
+
+    /// Round `v` to `self.precision` decimal places and format it without
+    /// trailing zeros (or a trailing `.`), so whole numbers render as plain
+    /// integers (e.g. `"12"` rather than `"12.00"`).
+    fn fmt_num(&self, v: f64) -> String {
+        let scale = 10f64.powi(self.precision as i32);
+        let mut rounded = (v * scale).round() / scale;
+        if rounded == 0.0 {
+            rounded = 0.0; // normalize -0.0
+        }
+        let s = format!("{:.*}", self.precision as usize, rounded);
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            s
+        }
+    }
+
+    /// Re-format every numeric token in an already-built SVG path data string
+    /// (as produced ad hoc by emf.rs/wmf.rs, e.g. `"M 1.234567 2.345678 L ..."`
+    /// or comma-joined arc params like `"a 1,2 0 1,0 3,0"`) to `self.precision`
+    /// decimal places, leaving path commands, whitespace, and commas untouched.
+    fn round_path_data(&self, d: &str) -> String {
+        let bytes = d.as_bytes();
+        let mut out = String::with_capacity(d.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let c = bytes[i] as char;
+            let starts_number = c.is_ascii_digit()
+                || ((c == '-' || c == '.')
+                    && bytes.get(i + 1).is_some_and(|b| (*b as char).is_ascii_digit()));
+            if starts_number {
+                let start = i;
+                if c == '-' {
+                    i += 1;
+                }
+                let mut seen_dot = false;
+                while i < bytes.len() {
+                    let ch = bytes[i] as char;
+                    if ch.is_ascii_digit() {
+                        i += 1;
+                    } else if ch == '.' && !seen_dot {
+                        seen_dot = true;
+                        i += 1;
+                    } else if (ch == 'e' || ch == 'E')
+                        && bytes.get(i + 1).is_some_and(|b| {
+                            (*b as char).is_ascii_digit() || *b == b'-' || *b == b'+'
+                        })
+                    {
+                        i += 2;
+                        while i < bytes.len() && (bytes[i] as char).is_ascii_digit() {
+                            i += 1;
+                        }
+                    } else {
+                        break;
+                    }
+                }
+                let token = &d[start..i];
+                match token.parse::<f64>() {
+                    Ok(v) => out.push_str(&self.fmt_num(v)),
+                    Err(_) => out.push_str(token),
+                }
+            } else {
+                out.push(c);
+                i += 1;
+            }
         }
+        out
+    }
+
+    /// Force the document's viewBox to an explicit rectangle (in the same device units
+    /// as drawn content) instead of one derived from element bounds. Used when the source
+    /// format gives us an authoritative bounding box (e.g. a WMF placeable header).
+    pub fn set_viewbox(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.fixed_viewbox = Some((x, y, width, height));
+    }
+
+    /// Register a new linearGradient spanning device-space point (x1,y1) to (x2,y2) with the
+    /// given (offset, color) stops, and return its id for use as a `fill="url(#id)"`.
+    pub fn define_linear_gradient(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, stops: Vec<(f64, String)>) -> String {
+        let id = format!("emf-gradient-{}", self.next_gradient_id);
+        self.next_gradient_id += 1;
+        self.gradient_defs.push((id.clone(), x1, y1, x2, y2, stops));
+        id
+    }
+
+    /// Register a new clipPath containing a single rectangle and return its id.
+    pub fn define_clip_rect(&mut self, x: f64, y: f64, width: f64, height: f64) -> String {
+        let path_d = format!("M {x} {y} L {} {y} L {} {} L {x} {} Z", x + width, x + width, y + height, y + height);
+        let path_d = self.round_path_data(&path_d);
+        self.define_clip_path(&path_d, false)
+    }
+
+    /// Register a new clipPath from raw SVG path data (in device coordinates) and return its id.
+    /// `evenodd` selects the even-odd fill rule, used for clip regions built by subtracting
+    /// an excluded rectangle from an outer bound (EMR_EXCLUDECLIPRECT).
+    pub fn define_clip_path(&mut self, path_d: &str, evenodd: bool) -> String {
+        let id = format!("emf-clip-{}", self.next_clip_id);
+        self.next_clip_id += 1;
+        self.clip_defs.push((id.clone(), path_d.to_string(), evenodd));
+        id
     }
     
     fn update_bounds(&mut self, x: f64, y: f64) {
@@ -46,75 +216,166 @@ impl SvgWriter {
 
     /// Add a rectangle to the SVG
     pub fn add_rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Option<&str>, stroke: Option<&str>) {
+        self.add_rect_clipped(x, y, width, height, fill, stroke, None)
+    }
+
+    /// Add a rectangle to the SVG, optionally clipped by a previously registered clipPath id
+    pub fn add_rect_clipped(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Option<&str>, stroke: Option<&str>, clip_id: Option<&str>) {
         self.update_bounds(x, y);
         self.update_bounds(x + width, y + height);
-        
+
         let mut rect = Rectangle::new()
-            .set("x", x)
-            .set("y", y)
-            .set("width", width)
-            .set("height", height);
-        
+            .set("x", self.fmt_num(x))
+            .set("y", self.fmt_num(y))
+            .set("width", self.fmt_num(width))
+            .set("height", self.fmt_num(height));
+
         if let Some(fill_color) = fill {
             rect = rect.set("fill", fill_color);
         } else {
             rect = rect.set("fill", "none");
         }
-        
+
         if let Some(stroke_color) = stroke {
             rect = rect.set("stroke", stroke_color);
         }
-        
+
+        if let Some(id) = clip_id {
+            rect = rect.set("clip-path", format!("url(#{id})"));
+        }
+
         self.elements.push(rect.into());
     }
 
     /// Add text to the SVG
     pub fn add_text(&mut self, x: f64, y: f64, text: &str) {
         let text_elem = Text::new()
-            .set("x", x)
-            .set("y", y)
+            .set("x", self.fmt_num(x))
+            .set("y", self.fmt_num(y))
             .set("font-family", "Arial")
             .set("font-size", 12)
             .add(TextNode::new(text));
         
+        self.text_count += 1;
         self.elements.push(text_elem.into());
     }
 
     /// Add text to the SVG with custom styling
     pub fn add_text_styled(&mut self, x: f64, y: f64, text: &str, font_family: Option<&str>, font_size: Option<f64>, fill_color: Option<&str>) {
+        self.add_text_font(x, y, text, &FontStyle {
+            family: font_family.map(|s| s.to_string()),
+            size: font_size,
+            fill_color: fill_color.map(|s| s.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Add text to the SVG using the full LOGFONT-derived style (family, size, weight, italic,
+    /// underline, strikeout)
+    pub fn add_text_font(&mut self, x: f64, y: f64, text: &str, style: &FontStyle) {
         let mut text_elem = Text::new()
-            .set("x", x)
-            .set("y", y)
-            .set("font-family", font_family.unwrap_or("Arial"))
-            .set("font-size", font_size.unwrap_or(12.0));
-        
-        if let Some(color) = fill_color {
-            text_elem = text_elem.set("fill", color);
+            .set("x", self.fmt_num(x))
+            .set("y", self.fmt_num(y))
+            .set("font-family", style.family.clone().unwrap_or_else(|| "Arial".to_string()))
+            .set("font-size", self.fmt_num(style.size.unwrap_or(12.0)))
+            .set("font-weight", style.weight.unwrap_or(400))
+            .set("font-style", if style.italic { "italic" } else { "normal" });
+
+        if let Some(color) = &style.fill_color {
+            text_elem = text_elem.set("fill", color.as_str());
         }
-        
+
+        if let Some(anchor) = &style.anchor {
+            text_elem = text_elem.set("text-anchor", anchor.as_str());
+        }
+
+        if let Some(baseline) = &style.dominant_baseline {
+            text_elem = text_elem.set("dominant-baseline", baseline.as_str());
+        }
+
+        if let Some(text_length) = style.text_length {
+            text_elem = text_elem
+                .set("textLength", self.fmt_num(text_length))
+                .set("lengthAdjust", "spacingAndGlyphs");
+        }
+
+        let mut decorations = Vec::new();
+        if style.underline {
+            decorations.push("underline");
+        }
+        if style.strikeout {
+            decorations.push("line-through");
+        }
+        if !decorations.is_empty() {
+            text_elem = text_elem.set("text-decoration", decorations.join(" "));
+        }
+
         text_elem = text_elem.add(TextNode::new(text));
-        
+
+        self.text_count += 1;
         self.elements.push(text_elem.into());
     }
 
     /// Add an image to the SVG (as base64 embedded)
     pub fn add_image(&mut self, x: f64, y: f64, width: f64, height: f64, image_data: &[u8], mime_type: &str) {
+        self.add_image_opacity(x, y, width, height, image_data, mime_type, None)
+    }
+
+    /// Add an image to the SVG (as base64 embedded), with an optional overall
+    /// opacity -- for EMR_ALPHABLEND blits whose BLENDFUNCTION carries a
+    /// source-constant-alpha but no per-pixel alpha channel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_image_opacity(&mut self, x: f64, y: f64, width: f64, height: f64, image_data: &[u8], mime_type: &str, opacity: Option<f64>) {
         use base64::{Engine as _, engine::general_purpose};
         let base64_data = general_purpose::STANDARD.encode(image_data);
         let data_uri = format!("data:{};base64,{}", mime_type, base64_data);
-        
-        let image = Image::new()
-            .set("x", x)
-            .set("y", y)
-            .set("width", width)
-            .set("height", height)
+
+        let mut image = Image::new()
+            .set("x", self.fmt_num(x))
+            .set("y", self.fmt_num(y))
+            .set("width", self.fmt_num(width))
+            .set("height", self.fmt_num(height))
             .set("href", data_uri);
-        
+        if let Some(opacity) = opacity {
+            image = image.set("opacity", opacity);
+        }
+
+        self.image_count += 1;
         self.elements.push(image.into());
     }
 
     /// Add a path to the SVG
     pub fn add_path(&mut self, path_data: &str, fill: Option<&str>, stroke: Option<&str>) {
+        self.add_path_clipped(path_data, fill, stroke, None)
+    }
+
+    /// Add a path to the SVG, optionally clipped by a previously registered clipPath id
+    pub fn add_path_clipped(&mut self, path_data: &str, fill: Option<&str>, stroke: Option<&str>, clip_id: Option<&str>) {
+        self.add_path_stroke_clipped(path_data, fill, stroke, None, None, clip_id, None)
+    }
+
+    /// Add a filled polygon to the SVG with an explicit fill rule, for EMR_POLYGON/
+    /// EMR_POLYPOLYGON and their 16-bit variants: "evenodd" under EMF's ALTERNATE
+    /// poly-fill mode (the GDI default) so alternating subpaths punch holes, or
+    /// "nonzero" under WINDING so every subpath fills solid regardless of direction.
+    pub fn add_path_filled_clipped(&mut self, path_data: &str, fill: Option<&str>, clip_id: Option<&str>, fill_rule: &str) {
+        self.add_path_stroke_clipped(path_data, fill, None, None, None, clip_id, Some(fill_rule))
+    }
+
+    /// Add a path to the SVG with an explicit stroke width and/or dasharray, for EMF pens
+    /// (PS_DASH/PS_DOT/etc. and non-hairline widths) where the line style must travel with
+    /// the stroke instead of defaulting to a solid hairline.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_path_stroke_clipped(&mut self, path_data: &str, fill: Option<&str>, stroke: Option<&str>, stroke_width: Option<f64>, dasharray: Option<&str>, clip_id: Option<&str>, fill_rule: Option<&str>) {
+        self.add_path_stroke_join_clipped(path_data, fill, stroke, stroke_width, dasharray, None, None, clip_id, fill_rule)
+    }
+
+    /// Like `add_path_stroke_clipped`, but also sets `stroke-linejoin`/`stroke-linecap`,
+    /// for EMF geometric pens (EMR_EXTCREATEPEN's PS_JOIN_*/PS_ENDCAP_* bits) where the
+    /// join/cap style must travel with the stroke instead of defaulting to SVG's
+    /// miter/butt.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_path_stroke_join_clipped(&mut self, path_data: &str, fill: Option<&str>, stroke: Option<&str>, stroke_width: Option<f64>, dasharray: Option<&str>, linejoin: Option<&str>, linecap: Option<&str>, clip_id: Option<&str>, fill_rule: Option<&str>) {
         // Parse path to extract coordinates for bounds and filtering
         let parts: Vec<&str> = path_data.split_whitespace().collect();
         let mut coords: Vec<(f64, f64)> = Vec::new();
@@ -201,34 +462,74 @@ impl SvgWriter {
         }
         
         // Add path to SVG (use modified path if first segment was removed)
-        let mut path = Path::new().set("d", final_path_data.as_str());
-        
+        let rounded_path_data = self.round_path_data(&final_path_data);
+        let mut path = Path::new().set("d", rounded_path_data);
+
         if let Some(fill_color) = fill {
             path = path.set("fill", fill_color);
         } else {
             path = path.set("fill", "none");
         }
-        
+
         if let Some(stroke_color) = stroke {
             path = path.set("stroke", stroke_color);
+            if let Some(width) = stroke_width {
+                path = path.set("stroke-width", self.fmt_num(width));
+            }
+            if let Some(da) = dasharray {
+                path = path.set("stroke-dasharray", da);
+            }
+            if let Some(join) = linejoin {
+                path = path.set("stroke-linejoin", join);
+            }
+            if let Some(cap) = linecap {
+                path = path.set("stroke-linecap", cap);
+            }
         }
-        
+
+        if let Some(id) = clip_id {
+            path = path.set("clip-path", format!("url(#{id})"));
+        }
+
+        if let Some(rule) = fill_rule {
+            path = path.set("fill-rule", rule);
+        }
+
+        self.path_count += 1;
         self.elements.push(path.into());
     }
 
-    /// Finish and generate SVG string
-    pub fn finish(self) -> String {
-        // Use actual content bounds as viewBox origin - this removes empty margins
-        let (vb_x, vb_y, vb_width, vb_height) = if self.min_x != f64::MAX && self.max_x > self.min_x && 
+    /// Compute the viewBox rectangle that `finish` will emit, without consuming `self`.
+    fn compute_viewbox(&self) -> (f64, f64, f64, f64) {
+        if let Some(fixed) = self.fixed_viewbox {
+            fixed
+        } else if self.min_x != f64::MAX && self.max_x > self.min_x &&
                                                    self.min_y != f64::MAX && self.max_y > self.min_y {
-            // Use actual content bounds - this crops out empty margins
             let content_width = self.max_x - self.min_x;
             let content_height = self.max_y - self.min_y;
             (self.min_x, self.min_y, content_width, content_height)
         } else {
-            // Fallback to rclFrame dimensions starting at 0,0
             (0.0, 0.0, self.width as f64, self.height as f64)
+        }
+    }
+
+    /// Like `finish`, but also returns the emitted-element counts and final viewBox,
+    /// for callers that need to size a placement box or flag overly complex graphics
+    /// without re-parsing the SVG they were just handed.
+    pub fn finish_with_meta(self) -> (String, SvgMeta) {
+        let meta = SvgMeta {
+            view_box: self.compute_viewbox(),
+            path_count: self.path_count,
+            text_count: self.text_count,
+            image_count: self.image_count,
         };
+        (self.finish(), meta)
+    }
+
+    /// Finish and generate SVG string
+    pub fn finish(self) -> String {
+        // Use actual content bounds as viewBox origin - this removes empty margins
+        let (vb_x, vb_y, vb_width, vb_height) = self.compute_viewbox();
         
         // SVG dimensions: use rclFrame aspect ratio but scale up for quality
         let scale_factor = 2.0_f64.max(vb_width / self.width as f64).max(vb_height / self.height as f64);
@@ -238,9 +539,34 @@ impl SvgWriter {
         let mut document = Document::new()
             .set("width", svg_width)
             .set("height", svg_height)
-            .set("viewBox", format!("{:.2} {:.2} {:.2} {:.2}", vb_x, vb_y, vb_width, vb_height))
+            .set("viewBox", format!("{} {} {} {}", self.fmt_num(vb_x), self.fmt_num(vb_y), self.fmt_num(vb_width), self.fmt_num(vb_height)))
             .set("preserveAspectRatio", "none");
-        
+
+        if !self.clip_defs.is_empty() || !self.gradient_defs.is_empty() {
+            let mut defs = Definitions::new();
+            for (id, path_d, evenodd) in &self.clip_defs {
+                let mut clip_path_el = Path::new().set("d", path_d.as_str());
+                if *evenodd {
+                    clip_path_el = clip_path_el.set("fill-rule", "evenodd");
+                }
+                defs = defs.add(ClipPath::new().set("id", id.as_str()).add(clip_path_el));
+            }
+            for (id, x1, y1, x2, y2, stops) in &self.gradient_defs {
+                let mut gradient = LinearGradient::new()
+                    .set("id", id.as_str())
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set("x1", self.fmt_num(*x1))
+                    .set("y1", self.fmt_num(*y1))
+                    .set("x2", self.fmt_num(*x2))
+                    .set("y2", self.fmt_num(*y2));
+                for (offset, color) in stops {
+                    gradient = gradient.add(Stop::new().set("offset", *offset).set("stop-color", color.as_str()));
+                }
+                defs = defs.add(gradient);
+            }
+            document = document.add(defs);
+        }
+
         for element in self.elements {
             document = document.add(element);
         }
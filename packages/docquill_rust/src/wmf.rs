@@ -1,7 +1,19 @@
 //! WMF format parser and converter
 
 use crate::svg_writer::SvgWriter;
-use crate::emf::convert_emf_to_svg;
+use crate::emf::{argb_to_svg_color, convert_emf_to_svg, get_emf_info, ConvertError};
+use crate::wmf_records;
+use std::io::{Cursor, Read};
+
+/// META_EOF record function code, marking the end of a WMF record stream
+const META_EOF: u16 = wmf_records::META_EOF;
+
+/// Low nibble of a WMF LOGPEN's style field that marks a PS_NULL pen (no stroke)
+const PS_NULL: u16 = 5;
+
+/// ExtTextOut fwOpts flags that prepend an (unused, here skipped) clip/opaque rectangle
+const ETO_OPAQUE: u16 = 0x0002;
+const ETO_CLIPPED: u16 = 0x0004;
 
 /// Check if data is WMF format
 pub fn is_wmf_format(data: &[u8]) -> bool {
@@ -24,8 +36,44 @@ pub fn is_wmf_format(data: &[u8]) -> bool {
     false
 }
 
-/// Convert WMF data to SVG string
+/// Default device resolution assumed for WMF logical units, matching the 96 DPI
+/// "standard web DPI" used throughout the EMF conversion path.
+pub const DEFAULT_WMF_DPI: f64 = 96.0;
+
+/// Convert WMF data to SVG string, at the default 96 DPI.
 pub fn convert_wmf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    convert_wmf_to_svg_opts(data, None, false)
+}
+
+/// Convert WMF data to SVG string, at the default 96 DPI, using a matchable
+/// [`ConvertError`] instead of `Box<dyn std::error::Error>`. Mirrors
+/// [`crate::emf::convert`] for callers outside of the PyO3 boundary.
+pub fn convert(data: &[u8]) -> Result<String, ConvertError> {
+    if !is_wmf_format(data) {
+        return Err(ConvertError::UnsupportedFormat);
+    }
+    convert_wmf_to_svg(data).map_err(|e| match e.downcast::<std::io::Error>() {
+        Ok(io_err) => ConvertError::Io(*io_err),
+        Err(_) => ConvertError::Truncated,
+    })
+}
+
+/// Convert WMF data to SVG string, with control over the device resolution used to
+/// size the output.
+///
+/// `dpi` defaults to [`DEFAULT_WMF_DPI`] (96) when `None`. For a WMF with a
+/// placeable header, the header's own `units_per_inch` already determines an
+/// authoritative physical size, so `dpi` is ignored there unless `override_dpi`
+/// is set -- in which case it's substituted for the standard 96 DPI target when
+/// converting the header's bounding box to pixels. For a WMF with no placeable
+/// header (where intrinsic size is ambiguous and the fallback bounds are just a
+/// best guess), `dpi` scales the resulting pixel dimensions directly, letting
+/// callers force a larger or smaller device resolution.
+pub fn convert_wmf_to_svg_opts(
+    data: &[u8],
+    dpi: Option<f64>,
+    override_dpi: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
     if !is_wmf_format(data) {
         return Err("Invalid WMF format".into());
     }
@@ -36,20 +84,763 @@ pub fn convert_wmf_to_svg(data: &[u8]) -> Result<String, Box<dyn std::error::Err
     }
 
     // Parse WMF and convert to SVG
-    let (width_px, height_px) = parse_wmf_size(data)?;
+    let (x_px, y_px, width_px, height_px) = parse_wmf_bounds(data, dpi, override_dpi)?;
     let width = normalize_dimension(width_px);
     let height = normalize_dimension(height_px);
 
-    eprintln!("WMF - Final SVG dimensions: {}x{} pixels", width, height);
+    log::debug!("WMF - Final SVG dimensions: {}x{} pixels", width, height);
 
-    // For now, create a placeholder SVG
-    // TODO: Implement full WMF parsing
     let mut svg = SvgWriter::new(width, height);
-    svg.add_text(10.0, height as f64 / 2.0, "WMF conversion not yet fully implemented");
-    
+    svg.set_viewbox(x_px, y_px, width_px, height_px);
+    parse_wmf_records(data, &mut svg)?;
+
     Ok(svg.finish())
 }
 
+/// Walk the WMF record stream, rendering each drawing primitive straight into `svg`.
+fn parse_wmf_records(data: &[u8], svg: &mut SvgWriter) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    let header_offset = if data.len() >= 4
+        && u32::from_le_bytes([data[0], data[1], data[2], data[3]]) == 0xCDD79AC6
+    {
+        22
+    } else {
+        0
+    };
+    // Standard WMF header (META_HEADER) is 18 bytes, immediately after the placeable
+    // header when present.
+    let records_start = header_offset + 18;
+    if records_start > data.len() {
+        return Ok(());
+    }
+
+    let mut state = WmfState::new();
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(records_start as u64);
+
+    let mut record_count = 0;
+    while cursor.position() + 6 <= data.len() as u64 {
+        let record_size_words = cursor.read_u32::<LittleEndian>()?;
+        let record_function = cursor.read_u16::<LittleEndian>()?;
+        let record_size_bytes = (record_size_words as u64) * 2;
+        if record_size_bytes < 6 {
+            break;
+        }
+        let data_size = (record_size_bytes - 6) as u32;
+        let data_start = cursor.position();
+        if data_start + data_size as u64 > data.len() as u64 {
+            break;
+        }
+
+        if record_function == META_EOF {
+            break;
+        }
+
+        record_count += 1;
+        if record_count <= 20 {
+            log::debug!("WMF record {}: function=0x{:04x} size={}", record_count, record_function, record_size_bytes);
+        }
+
+        dispatch_wmf_record(record_function, &mut cursor, svg, &mut state, data_size)?;
+
+        cursor.set_position(data_start + data_size as u64);
+    }
+
+    Ok(())
+}
+
+/// One allocated WMF GDI pen, recalled from the object table by handle index.
+struct WmfPen {
+    color: u32,
+    style: u16,
+}
+
+/// One allocated WMF GDI brush, recalled from the object table by handle index.
+struct WmfBrush {
+    color: u32,
+}
+
+/// WMF's object table: handles are type-agnostic, so a slot only counts as
+/// free again once neither side holds an object, mirroring the real GDI
+/// object table where a single handle numbering space covers pens, brushes,
+/// and fonts alike.
+struct WmfObjectTable {
+    pens: Vec<Option<WmfPen>>,
+    brushes: Vec<Option<WmfBrush>>,
+}
+
+impl WmfObjectTable {
+    fn new() -> Self {
+        Self {
+            pens: Vec::new(),
+            brushes: Vec::new(),
+        }
+    }
+
+    fn alloc_slot(&mut self) -> usize {
+        for i in 0..self.pens.len() {
+            if self.pens[i].is_none() && self.brushes[i].is_none() {
+                return i;
+            }
+        }
+        self.pens.push(None);
+        self.brushes.push(None);
+        self.pens.len() - 1
+    }
+}
+
+/// MM_ANISOTROPIC/MM_ISOTROPIC window-to-viewport mapping (window/viewport org +
+/// ext from META_SETWINDOWORG/EXT and META_SETVIEWPORTORG/EXT). Other mapping
+/// modes (MM_TEXT, MM_LOMETRIC, ...) aren't distinguished -- they fall back to
+/// this same org/ext transform, which is the identity mapping (window ext ==
+/// viewport ext == (1, 1)) until a record actually changes it.
+struct WmfTransform {
+    window_org: (i32, i32),
+    window_ext: (i32, i32),
+    viewport_org: (i32, i32),
+    viewport_ext: (i32, i32),
+}
+
+impl WmfTransform {
+    fn new() -> Self {
+        Self {
+            window_org: (0, 0),
+            window_ext: (1, 1),
+            viewport_org: (0, 0),
+            viewport_ext: (1, 1),
+        }
+    }
+
+    fn transform(&self, x: f64, y: f64) -> (f64, f64) {
+        let sx = if self.window_ext.0 != 0 {
+            self.viewport_ext.0 as f64 / self.window_ext.0 as f64
+        } else {
+            1.0
+        };
+        let sy = if self.window_ext.1 != 0 {
+            self.viewport_ext.1 as f64 / self.window_ext.1 as f64
+        } else {
+            1.0
+        };
+        (
+            (x - self.window_org.0 as f64) * sx + self.viewport_org.0 as f64,
+            (y - self.window_org.1 as f64) * sy + self.viewport_org.1 as f64,
+        )
+    }
+}
+
+/// Graphics state while walking WMF records, mirroring `emf::GraphicsState`
+/// scaled down to what WMF's much smaller record set actually needs.
+struct WmfState {
+    objects: WmfObjectTable,
+    current_pen_color: u32,
+    current_pen_style: u16,
+    current_brush_color: u32,
+    current_text_color: u32,
+    transform: WmfTransform,
+}
+
+impl WmfState {
+    fn new() -> Self {
+        Self {
+            objects: WmfObjectTable::new(),
+            current_pen_color: 0x00000000, // Black
+            current_pen_style: 0,
+            current_brush_color: 0x00FFFFFF, // White
+            current_text_color: 0x00000000,  // Black
+            transform: WmfTransform::new(),
+        }
+    }
+
+    /// The current pen's stroke color, or `None` for a PS_NULL pen (no stroke at all).
+    fn stroke_color(&self) -> Option<String> {
+        if self.current_pen_style & 0x000F == PS_NULL {
+            None
+        } else {
+            Some(argb_to_svg_color(self.current_pen_color))
+        }
+    }
+}
+
+/// Dispatch a single WMF record to its handler. Unrecognized record types are skipped;
+/// the caller advances the cursor to the next record regardless of what a handler consumed.
+fn dispatch_wmf_record(
+    function: u16,
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &mut WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    match function {
+        wmf_records::META_SETWINDOWORG if data_size >= 4 => {
+            let y = cursor.read_i16::<LittleEndian>()? as i32;
+            let x = cursor.read_i16::<LittleEndian>()? as i32;
+            state.transform.window_org = (x, y);
+        }
+        wmf_records::META_SETWINDOWEXT if data_size >= 4 => {
+            let y = cursor.read_i16::<LittleEndian>()? as i32;
+            let x = cursor.read_i16::<LittleEndian>()? as i32;
+            state.transform.window_ext = (x, y);
+        }
+        wmf_records::META_SETVIEWPORTORG if data_size >= 4 => {
+            let y = cursor.read_i16::<LittleEndian>()? as i32;
+            let x = cursor.read_i16::<LittleEndian>()? as i32;
+            state.transform.viewport_org = (x, y);
+        }
+        wmf_records::META_SETVIEWPORTEXT if data_size >= 4 => {
+            let y = cursor.read_i16::<LittleEndian>()? as i32;
+            let x = cursor.read_i16::<LittleEndian>()? as i32;
+            state.transform.viewport_ext = (x, y);
+        }
+        wmf_records::META_SETTEXTCOLOR if data_size >= 4 => {
+            state.current_text_color = cursor.read_u32::<LittleEndian>()?;
+        }
+        wmf_records::META_CREATEPENINDIRECT => {
+            handle_createpenindirect(cursor, state, data_size)?;
+        }
+        wmf_records::META_CREATEBRUSHINDIRECT => {
+            handle_createbrushindirect(cursor, state, data_size)?;
+        }
+        wmf_records::META_SELECTOBJECT if data_size >= 2 => {
+            let index = cursor.read_u16::<LittleEndian>()? as usize;
+            if index < state.objects.pens.len() {
+                if let Some(pen) = &state.objects.pens[index] {
+                    state.current_pen_color = pen.color;
+                    state.current_pen_style = pen.style;
+                }
+                if let Some(brush) = &state.objects.brushes[index] {
+                    state.current_brush_color = brush.color;
+                }
+            }
+        }
+        wmf_records::META_DELETEOBJECT if data_size >= 2 => {
+            let index = cursor.read_u16::<LittleEndian>()? as usize;
+            if index < state.objects.pens.len() {
+                state.objects.pens[index] = None;
+                state.objects.brushes[index] = None;
+            }
+        }
+        wmf_records::META_POLYLINE => {
+            handle_polyline(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_POLYGON => {
+            handle_polygon(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_POLYPOLYGON => {
+            handle_polypolygon(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_RECTANGLE => {
+            handle_rectangle(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_ROUNDRECT => {
+            handle_roundrect(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_ELLIPSE => {
+            handle_ellipse(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_ARC => {
+            handle_arc(cursor, svg, state, data_size, ArcKind::Arc)?;
+        }
+        wmf_records::META_PIE => {
+            handle_arc(cursor, svg, state, data_size, ArcKind::Pie)?;
+        }
+        wmf_records::META_CHORD => {
+            handle_arc(cursor, svg, state, data_size, ArcKind::Chord)?;
+        }
+        wmf_records::META_TEXTOUT => {
+            handle_textout(cursor, svg, state, data_size)?;
+        }
+        wmf_records::META_EXTTEXTOUT => {
+            handle_exttextout(cursor, svg, state, data_size)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Handle META_CREATEPENINDIRECT: a LOGPEN (style, POINT16 width, COLORREF)
+fn handle_createpenindirect(
+    cursor: &mut Cursor<&[u8]>,
+    state: &mut WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 10 {
+        return Ok(());
+    }
+
+    let style = cursor.read_u16::<LittleEndian>()?;
+    let _width_x = cursor.read_i16::<LittleEndian>()?;
+    let _width_y = cursor.read_i16::<LittleEndian>()?;
+    let color = cursor.read_u32::<LittleEndian>()?;
+
+    let slot = state.objects.alloc_slot();
+    state.objects.pens[slot] = Some(WmfPen { color, style });
+
+    Ok(())
+}
+
+/// Handle META_CREATEBRUSHINDIRECT: a LOGBRUSH (style, COLORREF, hatch)
+fn handle_createbrushindirect(
+    cursor: &mut Cursor<&[u8]>,
+    state: &mut WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 8 {
+        return Ok(());
+    }
+
+    let _style = cursor.read_u16::<LittleEndian>()?;
+    let color = cursor.read_u32::<LittleEndian>()?;
+    let _hatch = cursor.read_u16::<LittleEndian>()?;
+
+    let slot = state.objects.alloc_slot();
+    state.objects.brushes[slot] = Some(WmfBrush { color });
+
+    Ok(())
+}
+
+/// Handle META_POLYLINE: an open polyline, stroked with the current pen
+fn handle_polyline(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    _data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if cursor.position() as usize + 2 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let point_count = cursor.read_u16::<LittleEndian>()?;
+    if point_count == 0 || point_count > 10000 {
+        return Ok(());
+    }
+
+    let mut path = String::new();
+    for i in 0..point_count {
+        if cursor.position() as usize + 4 > cursor.get_ref().len() {
+            break;
+        }
+        let x = cursor.read_i16::<LittleEndian>()? as f64;
+        let y = cursor.read_i16::<LittleEndian>()? as f64;
+        let (tx, ty) = state.transform.transform(x, y);
+        if i == 0 {
+            path.push_str(&format!("M {} {}", tx, ty));
+        } else {
+            path.push_str(&format!(" L {} {}", tx, ty));
+        }
+    }
+
+    if let Some(color) = state.stroke_color() {
+        svg.add_path_clipped(&path, None, Some(&color), None);
+    }
+
+    Ok(())
+}
+
+/// Handle META_POLYGON: a closed polygon, filled with the current brush
+/// (matching `emf::handle_polygon`, closed shapes here render fill-only, with
+/// no pen outline)
+fn handle_polygon(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    _data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if cursor.position() as usize + 2 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let point_count = cursor.read_u16::<LittleEndian>()?;
+    if point_count == 0 || point_count > 10000 {
+        return Ok(());
+    }
+
+    let mut path = String::new();
+    for i in 0..point_count {
+        if cursor.position() as usize + 4 > cursor.get_ref().len() {
+            break;
+        }
+        let x = cursor.read_i16::<LittleEndian>()? as f64;
+        let y = cursor.read_i16::<LittleEndian>()? as f64;
+        let (tx, ty) = state.transform.transform(x, y);
+        if i == 0 {
+            path.push_str(&format!("M {} {}", tx, ty));
+        } else {
+            path.push_str(&format!(" L {} {}", tx, ty));
+        }
+    }
+    path.push_str(" Z");
+
+    let color = argb_to_svg_color(state.current_brush_color);
+    svg.add_path_clipped(&path, Some(&color), None, None);
+
+    Ok(())
+}
+
+/// Handle META_POLYPOLYGON: a sequence of closed polygons sharing one fill
+fn handle_polypolygon(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    _data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if cursor.position() as usize + 2 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let polygon_count = cursor.read_u16::<LittleEndian>()?;
+    if polygon_count == 0 || polygon_count > 1000 {
+        return Ok(());
+    }
+
+    let mut point_counts = Vec::with_capacity(polygon_count as usize);
+    let mut total_points = 0u32;
+    for _ in 0..polygon_count {
+        if cursor.position() as usize + 2 > cursor.get_ref().len() {
+            return Ok(());
+        }
+        let count = cursor.read_u16::<LittleEndian>()?;
+        point_counts.push(count);
+        total_points += count as u32;
+    }
+    if total_points > 10000 {
+        return Ok(());
+    }
+
+    let color = argb_to_svg_color(state.current_brush_color);
+    for &point_count in &point_counts {
+        let mut path = String::new();
+        for i in 0..point_count {
+            if cursor.position() as usize + 4 > cursor.get_ref().len() {
+                break;
+            }
+            let x = cursor.read_i16::<LittleEndian>()? as f64;
+            let y = cursor.read_i16::<LittleEndian>()? as f64;
+            let (tx, ty) = state.transform.transform(x, y);
+            if i == 0 {
+                path.push_str(&format!("M {} {}", tx, ty));
+            } else {
+                path.push_str(&format!(" L {} {}", tx, ty));
+            }
+        }
+        path.push_str(" Z");
+        svg.add_path_clipped(&path, Some(&color), None, None);
+    }
+
+    Ok(())
+}
+
+/// Read a WMF rectangle-shaped record's RECT16 fields, which are always stored
+/// in call-reversed order (bottom, right, top, left), and return them as
+/// (left, top, right, bottom).
+fn read_rect16(cursor: &mut Cursor<&[u8]>) -> Result<(f64, f64, f64, f64), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let bottom = cursor.read_i16::<LittleEndian>()? as f64;
+    let right = cursor.read_i16::<LittleEndian>()? as f64;
+    let top = cursor.read_i16::<LittleEndian>()? as f64;
+    let left = cursor.read_i16::<LittleEndian>()? as f64;
+    Ok((left, top, right, bottom))
+}
+
+/// Handle META_RECTANGLE: filled with the current brush, no pen outline
+/// (matching `emf::handle_rectangle`)
+fn handle_rectangle(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if data_size < 8 {
+        return Ok(());
+    }
+    let (left, top, right, bottom) = read_rect16(cursor)?;
+    let (x1, y1) = state.transform.transform(left, top);
+    let (x2, y2) = state.transform.transform(right, bottom);
+    let x = x1.min(x2);
+    let y = y1.min(y2);
+    let width = (x2 - x1).abs();
+    let height = (y2 - y1).abs();
+
+    let color = argb_to_svg_color(state.current_brush_color);
+    svg.add_rect_clipped(x, y, width, height, Some(&color), None, None);
+
+    Ok(())
+}
+
+/// Handle META_ROUNDRECT: filled with the current brush, no pen outline
+fn handle_roundrect(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 12 {
+        return Ok(());
+    }
+    let height_logical = cursor.read_i16::<LittleEndian>()? as f64;
+    let width_logical = cursor.read_i16::<LittleEndian>()? as f64;
+    let (left, top, right, bottom) = read_rect16(cursor)?;
+
+    let (x1, y1) = state.transform.transform(left, top);
+    let (x2, y2) = state.transform.transform(right, bottom);
+    let x = x1.min(x2);
+    let y = y1.min(y2);
+    let width = (x2 - x1).abs();
+    let height = (y2 - y1).abs();
+
+    let (sx, _) = state.transform.transform(width_logical.abs(), 0.0);
+    let (ox, _) = state.transform.transform(0.0, 0.0);
+    let (_, sy) = state.transform.transform(0.0, height_logical.abs());
+    let (_, oy) = state.transform.transform(0.0, 0.0);
+    let rx = ((sx - ox).abs() / 2.0).min(width / 2.0);
+    let ry = ((sy - oy).abs() / 2.0).min(height / 2.0);
+
+    let path = format!(
+        "M {x1} {y} L {x2} {y} A {rx} {ry} 0 0 1 {x2} {y2} L {x3} {y2} A {rx} {ry} 0 0 1 {x} {y4} L {x} {y5} A {rx} {ry} 0 0 1 {x4} {y} Z",
+        x1 = x + rx,
+        y = y,
+        x2 = x + width - rx,
+        y2 = y + height,
+        x3 = x + width - rx,
+        y4 = y + height - ry,
+        y5 = y + ry,
+        x4 = x + rx,
+    );
+
+    let color = argb_to_svg_color(state.current_brush_color);
+    svg.add_path_clipped(&path, Some(&color), None, None);
+
+    Ok(())
+}
+
+/// Handle META_ELLIPSE: filled with the current brush, no pen outline
+/// (matching `emf::handle_ellipse`'s two-arc approximation of a full circle/ellipse)
+fn handle_ellipse(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if data_size < 8 {
+        return Ok(());
+    }
+    let (left, top, right, bottom) = read_rect16(cursor)?;
+    let (x1, y1) = state.transform.transform(left, top);
+    let (x2, y2) = state.transform.transform(right, bottom);
+    let x = x1.min(x2);
+    let y = y1.min(y2);
+    let width = (x2 - x1).abs();
+    let height = (y2 - y1).abs();
+
+    let cx = x + width / 2.0;
+    let cy = y + height / 2.0;
+    let rx = width / 2.0;
+    let ry = height / 2.0;
+
+    let path = format!(
+        "M {} {} m -{},0 a {},{} 0 1,0 {},0 a {},{} 0 1,0 -{},0",
+        cx, cy, rx, rx, ry, rx * 2.0, rx, ry, rx * 2.0
+    );
+
+    let color = argb_to_svg_color(state.current_brush_color);
+    svg.add_path_clipped(&path, Some(&color), None, None);
+
+    Ok(())
+}
+
+/// Which of the three radial-line shapes a record built on the same
+/// ellipse-plus-two-radial-points layout represents.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ArcKind {
+    /// META_ARC: an open arc, stroked only
+    Arc,
+    /// META_PIE: a wedge from the center out to the arc, filled
+    Pie,
+    /// META_CHORD: the arc closed by a straight line between its endpoints, filled
+    Chord,
+}
+
+/// Handle META_ARC/META_PIE/META_CHORD: all three share an ellipse (RECT16) plus
+/// two radial points that define the start/end angles of the arc swept
+/// counterclockwise between them. The radial points only set the angle -- the
+/// actual arc endpoints are wherever that angle crosses the ellipse boundary.
+fn handle_arc(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+    kind: ArcKind,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 16 {
+        return Ok(());
+    }
+
+    let y_end = cursor.read_i16::<LittleEndian>()? as f64;
+    let x_end = cursor.read_i16::<LittleEndian>()? as f64;
+    let y_start = cursor.read_i16::<LittleEndian>()? as f64;
+    let x_start = cursor.read_i16::<LittleEndian>()? as f64;
+    let (left, top, right, bottom) = read_rect16(cursor)?;
+
+    let (dx1, dy1) = state.transform.transform(left, top);
+    let (dx2, dy2) = state.transform.transform(right, bottom);
+    let x = dx1.min(dx2);
+    let y = dy1.min(dy2);
+    let width = (dx2 - dx1).abs();
+    let height = (dy2 - dy1).abs();
+    let cx = x + width / 2.0;
+    let cy = y + height / 2.0;
+    let rx = width / 2.0;
+    let ry = height / 2.0;
+    if rx <= 0.0 || ry <= 0.0 {
+        return Ok(());
+    }
+
+    let (tx_start, ty_start) = state.transform.transform(x_start, y_start);
+    let (tx_end, ty_end) = state.transform.transform(x_end, y_end);
+    let angle_start = (ty_start - cy).atan2(tx_start - cx);
+    let angle_end = (ty_end - cy).atan2(tx_end - cx);
+
+    let start_point = (cx + rx * angle_start.cos(), cy + ry * angle_start.sin());
+    let end_point = (cx + rx * angle_end.cos(), cy + ry * angle_end.sin());
+
+    // GDI sweeps counterclockwise from the start angle to the end angle; in this
+    // y-down pixel space that's SVG's sweep-flag=0 (decreasing angle) direction.
+    let mut sweep = angle_start - angle_end;
+    if sweep < 0.0 {
+        sweep += 2.0 * std::f64::consts::PI;
+    }
+    let large_arc_flag = if sweep > std::f64::consts::PI { 1 } else { 0 };
+
+    let arc_segment = format!(
+        "M {} {} A {} {} 0 {} 0 {} {}",
+        start_point.0, start_point.1, rx, ry, large_arc_flag, end_point.0, end_point.1
+    );
+
+    match kind {
+        ArcKind::Arc => {
+            if let Some(color) = state.stroke_color() {
+                svg.add_path_clipped(&arc_segment, None, Some(&color), None);
+            }
+        }
+        ArcKind::Chord => {
+            let path = format!("{} Z", arc_segment);
+            let color = argb_to_svg_color(state.current_brush_color);
+            svg.add_path_clipped(&path, Some(&color), None, None);
+        }
+        ArcKind::Pie => {
+            let path = format!(
+                "M {} {} L {} {} {} L {} {} Z",
+                cx, cy, start_point.0, start_point.1, &arc_segment[arc_segment.find('A').unwrap_or(0)..], end_point.0, end_point.1
+            );
+            let color = argb_to_svg_color(state.current_brush_color);
+            svg.add_path_clipped(&path, Some(&color), None, None);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle META_TEXTOUT: Count, String, then YStart/XStart (call-reversed order)
+fn handle_textout(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 2 {
+        return Ok(());
+    }
+    let count = cursor.read_i16::<LittleEndian>()?.max(0) as usize;
+    let string_bytes = count + (count % 2); // padded to an even length
+
+    if cursor.position() as usize + string_bytes > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let mut text_bytes = vec![0u8; count];
+    cursor.read_exact(&mut text_bytes)?;
+    if string_bytes > count {
+        cursor.read_u8()?; // consume the padding byte
+    }
+
+    if cursor.position() as usize + 4 > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let y = cursor.read_i16::<LittleEndian>()? as f64;
+    let x = cursor.read_i16::<LittleEndian>()? as f64;
+
+    if text_bytes.is_empty() {
+        return Ok(());
+    }
+    let text = String::from_utf8_lossy(&text_bytes);
+    let (tx, ty) = state.transform.transform(x, y);
+    let color = argb_to_svg_color(state.current_text_color);
+    svg.add_text_styled(tx, ty, &text, None, None, Some(&color));
+
+    Ok(())
+}
+
+/// Handle META_EXTTEXTOUT: YStart, XStart, Count, fwOpts, optional clip/opaque
+/// rectangle (skipped -- this renderer doesn't clip text), String
+fn handle_exttextout(
+    cursor: &mut Cursor<&[u8]>,
+    svg: &mut SvgWriter,
+    state: &WmfState,
+    data_size: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use byteorder::{LittleEndian, ReadBytesExt};
+
+    if data_size < 8 {
+        return Ok(());
+    }
+    let y = cursor.read_i16::<LittleEndian>()? as f64;
+    let x = cursor.read_i16::<LittleEndian>()? as f64;
+    let count = cursor.read_i16::<LittleEndian>()?.max(0) as usize;
+    let opts = cursor.read_u16::<LittleEndian>()?;
+
+    if opts & (ETO_OPAQUE | ETO_CLIPPED) != 0 {
+        if cursor.position() as usize + 8 > cursor.get_ref().len() {
+            return Ok(());
+        }
+        for _ in 0..4 {
+            cursor.read_i16::<LittleEndian>()?;
+        }
+    }
+
+    let string_bytes = count + (count % 2);
+    if cursor.position() as usize + string_bytes > cursor.get_ref().len() {
+        return Ok(());
+    }
+    let mut text_bytes = vec![0u8; count];
+    cursor.read_exact(&mut text_bytes)?;
+
+    if text_bytes.is_empty() {
+        return Ok(());
+    }
+    let text = String::from_utf8_lossy(&text_bytes);
+    let (tx, ty) = state.transform.transform(x, y);
+    let color = argb_to_svg_color(state.current_text_color);
+    svg.add_text_styled(tx, ty, &text, None, None, Some(&color));
+
+    Ok(())
+}
+
 /// Extract embedded EMF from WMF (if present)
 fn extract_embedded_emf(_data: &[u8]) -> Option<Vec<u8>> {
     // TODO: Implement WMF escape record parsing to extract embedded EMF
@@ -57,17 +848,26 @@ fn extract_embedded_emf(_data: &[u8]) -> Option<Vec<u8>> {
     None
 }
 
-/// Parse WMF size from header
-/// Returns size in pixels (converted from logical units using units_per_inch for placeable WMF)
-fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+/// Parse WMF bounds from header
+/// Returns (x, y, width, height) in pixels (converted from logical units using
+/// units_per_inch for placeable WMF), with (x, y) as the viewBox origin.
+///
+/// `dpi` (defaulting to [`DEFAULT_WMF_DPI`] when `None`) and `override_dpi` control
+/// the device resolution used for that conversion -- see `convert_wmf_to_svg_opts`.
+fn parse_wmf_bounds(
+    data: &[u8],
+    dpi: Option<f64>,
+    override_dpi: bool,
+) -> Result<(f64, f64, f64, f64), Box<dyn std::error::Error>> {
     use byteorder::{LittleEndian, ReadBytesExt};
     let mut cursor = std::io::Cursor::new(data);
-    
+    let target_dpi = dpi.unwrap_or(DEFAULT_WMF_DPI);
+
     // Check for placeable WMF header (22 bytes)
     if data.len() >= 22 {
         // Check placeable header signature (0x9AC6CDD7 in little-endian)
         let sig = cursor.read_u32::<LittleEndian>()?;
-        
+
         if sig == 0xCDD79AC6 {
             // Placeable WMF header found
             // This header contains bounding box and units_per_inch, which define the physical size
@@ -77,32 +877,38 @@ fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>>
             let right = cursor.read_i16::<LittleEndian>()?;
             let bottom = cursor.read_i16::<LittleEndian>()?;
             let units_per_inch = cursor.read_u16::<LittleEndian>()?;
-            
+
             // Calculate size in logical units
             let width_logical = (right - left) as f64;
             let height_logical = (bottom - top) as f64;
-            
+
             if units_per_inch > 0 && width_logical > 0.0 && height_logical > 0.0 {
-                // Convert from logical units to pixels at 96 DPI (standard web DPI)
+                // Convert from logical units to pixels at the target DPI (96, the standard
+                // web DPI, unless the caller opted into overriding it with `dpi`)
                 // This matches the approach used for EMF rclFrame conversion
                 // 1 inch = units_per_inch logical units
-                // 1 inch = 96 pixels (96 DPI)
-                // Therefore: 1 logical unit = 96 / units_per_inch pixels
-                let logical_to_px = 96.0 / units_per_inch as f64;
+                // 1 inch = target_dpi pixels
+                // Therefore: 1 logical unit = target_dpi / units_per_inch pixels
+                let effective_dpi = if override_dpi { target_dpi } else { DEFAULT_WMF_DPI };
+                let logical_to_px = effective_dpi / units_per_inch as f64;
                 let width_px = width_logical * logical_to_px;
                 let height_px = height_logical * logical_to_px;
-                
-                eprintln!("WMF Placeable Header - BoundingBox: {}x{} logical units, {} units/inch", 
+                let x_px = left as f64 * logical_to_px;
+                let y_px = top as f64 * logical_to_px;
+
+                log::debug!("WMF Placeable Header - BoundingBox: {}x{} logical units, {} units/inch",
                           width_logical, height_logical, units_per_inch);
-                eprintln!("WMF Placeable Header - Size: {:.2}px x {:.2}px (96 DPI)", width_px, height_px);
-                
-                return Ok((width_px.max(1.0), height_px.max(1.0)));
+                log::debug!("WMF Placeable Header - Size: {:.2}px x {:.2}px ({} DPI)", width_px, height_px, effective_dpi);
+
+                return Ok((x_px, y_px, width_px.max(1.0), height_px.max(1.0)));
             } else if width_logical > 0.0 && height_logical > 0.0 {
-                // If units_per_inch is 0 or invalid, use logical units directly
+                // If units_per_inch is 0 or invalid, use logical units directly, scaled
+                // by the requested DPI relative to the 96 DPI default
                 // This is a fallback, but may not be accurate
-                eprintln!("WMF Placeable Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
+                let scale = target_dpi / DEFAULT_WMF_DPI;
+                log::debug!("WMF Placeable Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)",
                           width_logical, height_logical);
-                return Ok((width_logical.max(1.0), height_logical.max(1.0)));
+                return Ok((left as f64 * scale, top as f64 * scale, (width_logical * scale).max(1.0), (height_logical * scale).max(1.0)));
             }
         } else {
             // Standard WMF - try to read from metafile header
@@ -115,21 +921,108 @@ fn parse_wmf_size(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>>
                 let top = cursor.read_i16::<LittleEndian>()?;
                 let right = cursor.read_i16::<LittleEndian>()?;
                 let bottom = cursor.read_i16::<LittleEndian>()?;
-                
+
                 let width = (right - left).abs() as f64;
                 let height = (bottom - top).abs() as f64;
                 if width > 0.0 && height > 0.0 {
-                    eprintln!("WMF Standard Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)", 
+                    // No DPI is recorded for a standard (non-placeable) header, so a 1:1
+                    // logical-unit-to-pixel mapping is assumed (GDI's default MM_TEXT mode);
+                    // `dpi` scales that mapping for callers who know better.
+                    let scale = target_dpi / DEFAULT_WMF_DPI;
+                    log::debug!("WMF Standard Header - BoundingBox: {}x{} logical units (no units/inch, using as pixels)",
                               width, height);
-                    return Ok((width, height));
+                    return Ok((left.min(right) as f64 * scale, top.min(bottom) as f64 * scale, width * scale, height * scale));
                 }
             }
         }
     }
-    
-    // Fallback: default size
-    eprintln!("WMF - No valid size found in header, using default: 800x600");
-    Ok((800.0, 600.0))
+
+    // Fallback: default size, scaled by the requested DPI
+    let scale = target_dpi / DEFAULT_WMF_DPI;
+    log::debug!("WMF - No valid size found in header, using default: 800x600 (scaled by {}x)", scale);
+    Ok((0.0, 0.0, 800.0 * scale, 600.0 * scale))
+}
+
+/// Frame size in pixels (at 96 DPI), for callers that only need page dimensions (e.g. the
+/// DPI-based PNG rasterizer) without running the full SVG conversion.
+pub(crate) fn frame_size_px(data: &[u8]) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    let (_x, _y, width_px, height_px) = parse_wmf_bounds(data, None, false)?;
+    Ok((width_px, height_px))
+}
+
+/// Cheap-to-compute WMF metadata, mirroring `emf::EmfInfo` for the WMF side.
+pub struct WmfInfo {
+    /// BoundingBox from the placeable header, in logical units, if the header is present
+    pub bounds: Option<(i16, i16, i16, i16)>,
+    pub dpi: f64,
+    pub record_count: u32,
+    pub has_emf_plus: bool,
+}
+
+/// Read the placeable header (if present) plus a cheap scan over record size/function
+/// fields (skipping each record's parameters) to report bounds, DPI, and record count
+/// without parsing drawing geometry.
+pub fn get_wmf_info(data: &[u8]) -> Result<WmfInfo, Box<dyn std::error::Error>> {
+    if !is_wmf_format(data) {
+        return Err("Invalid WMF format".into());
+    }
+
+    use byteorder::{LittleEndian, ReadBytesExt};
+    let mut cursor = std::io::Cursor::new(data);
+
+    let (bounds, dpi, header_offset) = if data.len() >= 22 {
+        let sig = cursor.read_u32::<LittleEndian>()?;
+        if sig == 0xCDD79AC6 {
+            cursor.set_position(6);
+            let left = cursor.read_i16::<LittleEndian>()?;
+            let top = cursor.read_i16::<LittleEndian>()?;
+            let right = cursor.read_i16::<LittleEndian>()?;
+            let bottom = cursor.read_i16::<LittleEndian>()?;
+            let units_per_inch = cursor.read_u16::<LittleEndian>()?;
+            let dpi = if units_per_inch > 0 { units_per_inch as f64 } else { 96.0 };
+            (Some((left, top, right, bottom)), dpi, 22usize)
+        } else {
+            (None, 96.0, 0usize)
+        }
+    } else {
+        (None, 96.0, 0usize)
+    };
+
+    // Standard WMF header (META_HEADER) is 18 bytes, immediately after the placeable
+    // header when present; records follow it.
+    let records_start = header_offset + 18;
+    let mut record_count: u32 = 0;
+    if records_start <= data.len() {
+        let mut scan_cursor = std::io::Cursor::new(data);
+        scan_cursor.set_position(records_start as u64);
+        while scan_cursor.position() + 6 <= data.len() as u64 {
+            let record_size_words = scan_cursor.read_u32::<LittleEndian>()?;
+            let record_function = scan_cursor.read_u16::<LittleEndian>()?;
+            let record_size_bytes = (record_size_words as u64) * 2;
+            if record_size_bytes < 6 {
+                break;
+            }
+            let data_size = record_size_bytes - 6;
+            if scan_cursor.position() + data_size > data.len() as u64 {
+                break;
+            }
+            record_count += 1;
+            if record_function == META_EOF {
+                break;
+            }
+            scan_cursor.set_position(scan_cursor.position() + data_size);
+        }
+    }
+
+    // WMF itself never carries EMF+ records directly; only a WMF wrapping an embedded
+    // EMF (via META_ESCAPE_ENHANCED_METAFILE) could. Report that honestly once extraction
+    // lands instead of guessing.
+    let has_emf_plus = match extract_embedded_emf(data) {
+        Some(embedded) => get_emf_info(&embedded).map(|info| info.has_emf_plus).unwrap_or(false),
+        None => false,
+    };
+
+    Ok(WmfInfo { bounds, dpi, record_count, has_emf_plus })
 }
 
 /// Normalize dimension value
@@ -0,0 +1,26 @@
+//! WMF (Windows Metafile) record function codes.
+//!
+//! Unlike EMF's 32-bit `EMR_*` record types, WMF records are identified by a
+//! 16-bit `rdFunction` code inherited from 16-bit Windows GDI call numbers.
+
+pub const META_EOF: u16 = 0x0000;
+pub const META_SELECTOBJECT: u16 = 0x012D;
+pub const META_SETTEXTCOLOR: u16 = 0x0209;
+pub const META_SETWINDOWORG: u16 = 0x020B;
+pub const META_SETWINDOWEXT: u16 = 0x020C;
+pub const META_SETVIEWPORTORG: u16 = 0x020D;
+pub const META_SETVIEWPORTEXT: u16 = 0x020E;
+pub const META_POLYGON: u16 = 0x0324;
+pub const META_POLYLINE: u16 = 0x0325;
+pub const META_ELLIPSE: u16 = 0x0418;
+pub const META_RECTANGLE: u16 = 0x041B;
+pub const META_TEXTOUT: u16 = 0x0521;
+pub const META_POLYPOLYGON: u16 = 0x0538;
+pub const META_ROUNDRECT: u16 = 0x061C;
+pub const META_ARC: u16 = 0x0817;
+pub const META_PIE: u16 = 0x081A;
+pub const META_CHORD: u16 = 0x0830;
+pub const META_EXTTEXTOUT: u16 = 0x0A32;
+pub const META_DELETEOBJECT: u16 = 0x01F0;
+pub const META_CREATEPENINDIRECT: u16 = 0x02FA;
+pub const META_CREATEBRUSHINDIRECT: u16 = 0x02FC;